@@ -0,0 +1 @@
+ALTER TABLE mapsets DROP COLUMN last_checked
\ No newline at end of file
@@ -0,0 +1 @@
+ALTER TABLE mapsets ADD COLUMN last_checked TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP
\ No newline at end of file
@@ -0,0 +1,2 @@
+DROP TABLE bg_session_scores;
+DROP TABLE bg_sessions;
\ No newline at end of file
@@ -0,0 +1,16 @@
+CREATE TABLE bg_sessions (
+    session_id BIGINT UNSIGNED AUTO_INCREMENT PRIMARY KEY,
+    channel_id BIGINT UNSIGNED NOT NULL,
+    mode TINYINT UNSIGNED NOT NULL,
+    rounds INT UNSIGNED NOT NULL,
+    ended_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE TABLE bg_session_scores (
+    session_id BIGINT UNSIGNED NOT NULL,
+    user_id BIGINT UNSIGNED NOT NULL,
+    points INT UNSIGNED NOT NULL,
+    guesses INT UNSIGNED NOT NULL,
+    PRIMARY KEY (session_id, user_id),
+    FOREIGN KEY (session_id) REFERENCES bg_sessions(session_id) ON DELETE CASCADE
+)
\ No newline at end of file
@@ -0,0 +1,10 @@
+CREATE TABLE bggame_stats (
+    discord_id BIGINT UNSIGNED PRIMARY KEY,
+    score INT UNSIGNED NOT NULL
+);
+
+INSERT INTO bggame_stats(discord_id, score)
+SELECT discord_id, SUM(score) FROM bggame_scores GROUP BY discord_id;
+
+DROP TABLE bggame_scores;
+DROP TABLE bg_season_winners;
\ No newline at end of file
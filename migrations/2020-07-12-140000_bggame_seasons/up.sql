@@ -0,0 +1,21 @@
+CREATE TABLE bggame_scores (
+    discord_id BIGINT UNSIGNED NOT NULL,
+    guild_id BIGINT UNSIGNED NOT NULL,
+    season INT UNSIGNED NOT NULL,
+    score INT UNSIGNED NOT NULL,
+    PRIMARY KEY (discord_id, guild_id, season)
+);
+
+-- Previous scores belong to neither a guild nor a season
+INSERT INTO bggame_scores(discord_id, guild_id, season, score)
+SELECT discord_id, 0, 0, score FROM bggame_stats;
+
+DROP TABLE bggame_stats;
+
+CREATE TABLE bg_season_winners (
+    season INT UNSIGNED NOT NULL,
+    guild_id BIGINT UNSIGNED NOT NULL,
+    discord_id BIGINT UNSIGNED NOT NULL,
+    score INT UNSIGNED NOT NULL,
+    PRIMARY KEY (season, guild_id)
+)
\ No newline at end of file
@@ -0,0 +1 @@
+DROP TABLE bg_mapset_stats
\ No newline at end of file
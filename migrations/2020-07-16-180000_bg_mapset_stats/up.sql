@@ -0,0 +1,7 @@
+CREATE TABLE bg_mapset_stats (
+    mapset_id INT UNSIGNED PRIMARY KEY,
+    shown INT UNSIGNED NOT NULL,
+    guessed INT UNSIGNED NOT NULL,
+    guess_secs DOUBLE NOT NULL,
+    hints INT UNSIGNED NOT NULL
+)
\ No newline at end of file
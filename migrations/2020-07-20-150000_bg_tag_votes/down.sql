@@ -0,0 +1,2 @@
+DROP TABLE bg_tag_votes;
+DROP TABLE bg_tag_locks;
\ No newline at end of file
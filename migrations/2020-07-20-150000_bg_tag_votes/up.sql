@@ -0,0 +1,13 @@
+CREATE TABLE bg_tag_votes (
+    mapset_id INT UNSIGNED NOT NULL,
+    user_id BIGINT UNSIGNED NOT NULL,
+    tag INT UNSIGNED NOT NULL,
+    PRIMARY KEY (mapset_id, user_id, tag)
+);
+
+CREATE TABLE bg_tag_locks (
+    mapset_id INT UNSIGNED NOT NULL,
+    tag INT UNSIGNED NOT NULL,
+    locked_by BIGINT UNSIGNED NOT NULL,
+    PRIMARY KEY (mapset_id, tag)
+)
\ No newline at end of file
@@ -0,0 +1 @@
+DROP TABLE bg_aliases
\ No newline at end of file
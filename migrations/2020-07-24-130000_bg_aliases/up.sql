@@ -0,0 +1,7 @@
+CREATE TABLE bg_aliases (
+    mapset_id INT UNSIGNED NOT NULL,
+    kind TINYINT UNSIGNED NOT NULL,
+    alias VARCHAR(191) NOT NULL,
+    added_by BIGINT UNSIGNED,
+    PRIMARY KEY (mapset_id, kind, alias)
+) CHARACTER SET utf8mb4
\ No newline at end of file
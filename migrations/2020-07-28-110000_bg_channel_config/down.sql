@@ -0,0 +1 @@
+DROP TABLE bg_channel_config
\ No newline at end of file
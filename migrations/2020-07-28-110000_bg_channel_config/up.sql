@@ -0,0 +1,9 @@
+CREATE TABLE bg_channel_config (
+    channel_id BIGINT UNSIGNED NOT NULL PRIMARY KEY,
+    mode TINYINT UNSIGNED NOT NULL DEFAULT 0,
+    included INT UNSIGNED NOT NULL DEFAULT 0,
+    excluded INT UNSIGNED NOT NULL DEFAULT 0,
+    reveal VARCHAR(16) NOT NULL DEFAULT 'circle',
+    hint_cooldown INT UNSIGNED NOT NULL DEFAULT 0,
+    auto_stop INT UNSIGNED NOT NULL DEFAULT 3
+)
\ No newline at end of file
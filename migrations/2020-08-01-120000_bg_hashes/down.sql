@@ -0,0 +1 @@
+DROP TABLE bg_hashes
\ No newline at end of file
@@ -0,0 +1,4 @@
+CREATE TABLE bg_hashes (
+    mapset_id INT UNSIGNED NOT NULL PRIMARY KEY,
+    hash BIGINT UNSIGNED NOT NULL
+)
\ No newline at end of file
@@ -0,0 +1 @@
+DROP TABLE higherlower_scores
\ No newline at end of file
@@ -0,0 +1,6 @@
+CREATE TABLE higherlower_scores (
+    discord_id BIGINT UNSIGNED NOT NULL,
+    version TINYINT UNSIGNED NOT NULL,
+    highscore INT UNSIGNED NOT NULL,
+    PRIMARY KEY (discord_id, version)
+)
\ No newline at end of file
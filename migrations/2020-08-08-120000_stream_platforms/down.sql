@@ -0,0 +1,16 @@
+CREATE TABLE twitch_users (
+    user_id BIGINT UNSIGNED PRIMARY KEY,
+    name VARCHAR(32) NOT NULL
+);
+
+INSERT INTO twitch_users(user_id, name)
+SELECT user_id, name FROM stream_users WHERE platform = 0;
+
+DELETE FROM stream_tracks WHERE platform <> 0;
+
+ALTER TABLE stream_tracks
+    MODIFY user_id BIGINT UNSIGNED NOT NULL,
+    MODIFY platform TINYINT UNSIGNED NOT NULL,
+    ADD CONSTRAINT stream_tracks_user_fk FOREIGN KEY (user_id) REFERENCES twitch_users(user_id);
+
+DROP TABLE stream_users
\ No newline at end of file
@@ -0,0 +1,31 @@
+CREATE TABLE stream_users (
+    platform TINYINT UNSIGNED NOT NULL,
+    user_id VARCHAR(64) NOT NULL,
+    name VARCHAR(100) NOT NULL,
+    PRIMARY KEY (platform, user_id)
+) CHARACTER SET utf8mb4;
+
+-- All previous users and tracks are twitch ones
+INSERT INTO stream_users(platform, user_id, name)
+SELECT 0, user_id, name FROM twitch_users;
+
+-- The foreign key was created without a name so look up the generated one
+SET @fk_name = (
+    SELECT CONSTRAINT_NAME FROM information_schema.KEY_COLUMN_USAGE
+    WHERE TABLE_SCHEMA = DATABASE()
+        AND TABLE_NAME = 'stream_tracks'
+        AND REFERENCED_TABLE_NAME = 'twitch_users'
+    LIMIT 1
+);
+SET @drop_fk = CONCAT('ALTER TABLE stream_tracks DROP FOREIGN KEY ', @fk_name);
+PREPARE drop_fk FROM @drop_fk;
+EXECUTE drop_fk;
+DEALLOCATE PREPARE drop_fk;
+
+UPDATE stream_tracks SET platform = 0;
+
+ALTER TABLE stream_tracks
+    MODIFY user_id VARCHAR(64) NOT NULL,
+    MODIFY platform TINYINT UNSIGNED NOT NULL DEFAULT 0;
+
+DROP TABLE twitch_users
\ No newline at end of file
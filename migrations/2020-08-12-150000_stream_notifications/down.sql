@@ -0,0 +1 @@
+DROP TABLE stream_notifications
\ No newline at end of file
@@ -0,0 +1,13 @@
+CREATE TABLE stream_notifications (
+    channel_id BIGINT UNSIGNED NOT NULL,
+    message_id BIGINT UNSIGNED NOT NULL,
+    platform TINYINT UNSIGNED NOT NULL,
+    user_id VARCHAR(64) NOT NULL,
+    username VARCHAR(100) NOT NULL,
+    title VARCHAR(255) NOT NULL,
+    url VARCHAR(255) NOT NULL,
+    image_url VARCHAR(255) NOT NULL,
+    started_at DATETIME NOT NULL,
+    peak_viewers INT UNSIGNED NOT NULL,
+    PRIMARY KEY (channel_id, message_id)
+) CHARACTER SET utf8mb4
\ No newline at end of file
@@ -0,0 +1,3 @@
+ALTER TABLE stream_tracks
+    DROP COLUMN role_id,
+    DROP COLUMN template
\ No newline at end of file
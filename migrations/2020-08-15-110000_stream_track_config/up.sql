@@ -0,0 +1,3 @@
+ALTER TABLE stream_tracks
+    ADD role_id BIGINT UNSIGNED,
+    ADD template VARCHAR(1000) CHARACTER SET utf8mb4
\ No newline at end of file
@@ -0,0 +1,5 @@
+DROP TABLE role_menus;
+
+ALTER TABLE role_assign
+    DROP COLUMN emoji,
+    DROP COLUMN role_group
\ No newline at end of file
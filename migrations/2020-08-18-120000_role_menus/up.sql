@@ -0,0 +1,12 @@
+ALTER TABLE role_assign
+    ADD emoji VARCHAR(64) CHARACTER SET utf8mb4,
+    ADD role_group VARCHAR(32) CHARACTER SET utf8mb4;
+
+-- Settings that apply to all roles of a message
+CREATE TABLE role_menus (
+    channel BIGINT UNSIGNED NOT NULL,
+    message BIGINT UNSIGNED NOT NULL,
+    required_role BIGINT UNSIGNED,
+    max_roles TINYINT UNSIGNED,
+    PRIMARY KEY (channel, message)
+)
\ No newline at end of file
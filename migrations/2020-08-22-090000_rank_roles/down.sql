@@ -0,0 +1 @@
+DROP TABLE rank_roles
\ No newline at end of file
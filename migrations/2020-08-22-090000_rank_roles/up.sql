@@ -0,0 +1,10 @@
+-- Roles that are given to linked members based on their osu! rank
+CREATE TABLE rank_roles (
+    guild_id BIGINT UNSIGNED NOT NULL,
+    role_id BIGINT UNSIGNED NOT NULL,
+    mode TINYINT UNSIGNED NOT NULL,
+    country BOOL NOT NULL,
+    rank_min INT UNSIGNED NOT NULL,
+    rank_max INT UNSIGNED NOT NULL,
+    PRIMARY KEY (guild_id, role_id)
+)
\ No newline at end of file
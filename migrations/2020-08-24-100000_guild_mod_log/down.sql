@@ -0,0 +1,2 @@
+ALTER TABLE guilds
+    DROP COLUMN mod_log
\ No newline at end of file
@@ -0,0 +1,2 @@
+ALTER TABLE guilds
+    ADD mod_log BIGINT UNSIGNED
\ No newline at end of file
@@ -0,0 +1 @@
+DROP TABLE mod_log
\ No newline at end of file
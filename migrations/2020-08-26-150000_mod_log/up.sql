@@ -0,0 +1,11 @@
+-- Administrative actions and role changes within guilds
+CREATE TABLE mod_log (
+    entry_id BIGINT UNSIGNED AUTO_INCREMENT PRIMARY KEY,
+    guild_id BIGINT UNSIGNED NOT NULL,
+    action TINYINT UNSIGNED NOT NULL,
+    user_id BIGINT UNSIGNED NOT NULL,
+    target_id BIGINT UNSIGNED,
+    details VARCHAR(1024) CHARACTER SET utf8mb4 NOT NULL,
+    timestamp TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    INDEX (guild_id, timestamp)
+)
\ No newline at end of file
@@ -0,0 +1,9 @@
+-- Enabled / disabled commands and groups, channel_id 0 for the whole guild
+CREATE TABLE command_permissions (
+    guild_id BIGINT UNSIGNED NOT NULL,
+    channel_id BIGINT UNSIGNED NOT NULL,
+    name VARCHAR(32) NOT NULL,
+    enabled BOOL NOT NULL,
+    roles VARCHAR(256) NOT NULL,
+    PRIMARY KEY (guild_id, channel_id, name)
+)
\ No newline at end of file
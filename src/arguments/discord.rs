@@ -1,9 +1,10 @@
 use crate::arguments;
 
 use regex::Regex;
+use rosu::models::GameMode;
 use serenity::{
     framework::standard::Args,
-    model::id::{ChannelId, MessageId, RoleId},
+    model::id::{ChannelId, MessageId, RoleId, UserId},
 };
 use std::str::FromStr;
 
@@ -11,11 +12,13 @@ pub struct RoleAssignArgs {
     pub channel_id: ChannelId,
     pub message_id: MessageId,
     pub role_id: RoleId,
+    pub emoji: Option<String>,
+    pub group: Option<String>,
 }
 
 impl RoleAssignArgs {
     pub fn new(mut args: Args) -> Result<Self, String> {
-        let mut args = arguments::first_n(&mut args, 3);
+        let mut args = arguments::first_n(&mut args, 5);
         let rgx = Regex::new(r"<#([0-9]*)>$").unwrap();
         let channel_id = args.next().and_then(|arg| parse(&arg, &rgx)).map(ChannelId);
         if channel_id.is_none() {
@@ -45,10 +48,189 @@ impl RoleAssignArgs {
             channel_id: channel_id.unwrap(),
             message_id: message_id.unwrap(),
             role_id: role_id.unwrap(),
+            emoji: args.next(),
+            group: args.next().map(|group| group.to_lowercase()),
         })
     }
 }
 
+pub struct RoleMenuArgs {
+    pub title: String,
+    pub roles: Vec<(String, RoleId)>,
+    pub required_role: Option<RoleId>,
+    pub max_roles: Option<u8>,
+    pub exclusive: bool,
+}
+
+impl RoleMenuArgs {
+    pub fn new(mut args: Args) -> Result<Self, String> {
+        let mut args: Vec<_> = arguments::first_n(&mut args, 46).collect();
+        let required_role = match arguments::take_value(&mut args, &["-required"]) {
+            Some(Some(role)) => match parse_role(&role) {
+                Some(role) => Some(role),
+                None => return Err(format!("Could not parse `{}` as role", role)),
+            },
+            Some(None) => return Err("After `-required` you must specify a role".to_string()),
+            None => None,
+        };
+        let max_roles = match arguments::take_value(&mut args, &["-max"]) {
+            Some(max) => match max.map(|max| max.parse::<u8>()) {
+                Some(Ok(max)) if max > 0 => Some(max),
+                _ => {
+                    return Err("After `-max` you must specify a positive integer \
+                                that is at most 255"
+                        .to_string())
+                }
+            },
+            None => None,
+        };
+        let exclusive = arguments::keywords(&mut args, &["--exclusive"]);
+        let mut args = args.into_iter();
+        let title = match args.next() {
+            Some(title) => title,
+            None => return Err("The first argument must be the title of the menu".to_string()),
+        };
+        let mut roles = Vec::new();
+        while let Some(emoji) = args.next() {
+            let role = args.next().and_then(|arg| parse_role(&arg));
+            match role {
+                Some(role) => roles.push((emoji, role)),
+                None => {
+                    return Err(format!(
+                        "The emoji `{}` must be followed by a role mention or role id",
+                        emoji
+                    ))
+                }
+            }
+        }
+        if roles.is_empty() {
+            return Err("After the title you must specify pairs of emoji and role".to_string());
+        } else if roles.len() > 20 {
+            return Err("A message can have at most 20 different reactions".to_string());
+        }
+        Ok(Self {
+            title,
+            roles,
+            required_role,
+            max_roles,
+            exclusive,
+        })
+    }
+}
+
+pub struct RankRoleArgs {
+    pub role_id: RoleId,
+    pub mode: GameMode,
+    pub country: bool,
+    pub rank_min: u32,
+    pub rank_max: u32,
+}
+
+impl RankRoleArgs {
+    pub fn new(mut args: Args) -> Result<Self, String> {
+        let mut args = arguments::first_n(&mut args, 4);
+        let role_id = match args.next() {
+            Some(arg) => match parse_role(&arg) {
+                Some(role) => role,
+                None => return Err(format!("Could not parse `{}` as role", arg)),
+            },
+            None => return Err("The first argument must be a role".to_string()),
+        };
+        let mut mode = GameMode::STD;
+        let mut country = false;
+        let mut range = None;
+        for arg in args {
+            match arg.to_lowercase().as_str() {
+                "osu" | "std" | "standard" => mode = GameMode::STD,
+                "taiko" | "tko" => mode = GameMode::TKO,
+                "ctb" | "fruits" => mode = GameMode::CTB,
+                "mania" | "mna" => mode = GameMode::MNA,
+                "global" => country = false,
+                "country" | "national" => country = true,
+                other => match arguments::parse_range(other) {
+                    Some(parsed) => range = Some(parsed),
+                    None => return Err(format!("Unknown argument `{}`", arg)),
+                },
+            }
+        }
+        let (rank_min, rank_max) = match range {
+            Some((min, max)) => (min.unwrap_or(1).max(1), max.unwrap_or(u32::MAX)),
+            None => {
+                return Err("You must specify a rank range of the form \
+                            `a..b`, `a..`, or `..b` e.g. `1000..9999`"
+                    .to_string())
+            }
+        };
+        if rank_min > rank_max {
+            return Err("The minimum rank must not be above the maximum rank".to_string());
+        }
+        Ok(Self {
+            role_id,
+            mode,
+            country,
+            rank_min,
+            rank_max,
+        })
+    }
+}
+
+pub struct PermissionArgs {
+    /// Name of the command or group as it was given
+    pub name: String,
+    pub channel_id: Option<ChannelId>,
+    pub roles: Vec<RoleId>,
+}
+
+impl PermissionArgs {
+    /// If `with_roles`, channels must be mentioned since ids are considered roles
+    pub fn new(mut args: Args, with_roles: bool) -> Result<Self, String> {
+        let mut args = arguments::first_n(&mut args, 12);
+        let name = match args.next() {
+            Some(name) => name,
+            None => return Err("You must specify a command or a group".to_string()),
+        };
+        let mut channel_id = None;
+        let mut roles = Vec::new();
+        for arg in args {
+            if !with_roles || arg.starts_with("<#") {
+                match parse_channel(&arg) {
+                    Some(channel) => channel_id = Some(channel),
+                    None => return Err(format!("Could not parse `{}` as channel", arg)),
+                }
+            } else {
+                match parse_role(&arg) {
+                    Some(role) if roles.len() < 10 => roles.push(role),
+                    Some(_) => return Err("You can specify at most 10 roles".to_string()),
+                    None => return Err(format!("Could not parse `{}` as role", arg)),
+                }
+            }
+        }
+        Ok(Self {
+            name,
+            channel_id,
+            roles,
+        })
+    }
+}
+
+/// User mention or user id
+pub fn parse_user(arg: &str) -> Option<UserId> {
+    let rgx = Regex::new(r"<@!?([0-9]*)>$").unwrap();
+    parse(arg, &rgx).map(UserId)
+}
+
+/// Channel mention or channel id
+pub fn parse_channel(arg: &str) -> Option<ChannelId> {
+    let rgx = Regex::new(r"<#([0-9]*)>$").unwrap();
+    parse(arg, &rgx).map(ChannelId)
+}
+
+/// Role mention or role id
+pub fn parse_role(arg: &str) -> Option<RoleId> {
+    let rgx = Regex::new(r"<@&([0-9]*)>$").unwrap();
+    parse(arg, &rgx).map(RoleId)
+}
+
 fn parse(arg: &str, regex: &Regex) -> Option<u64> {
     u64::from_str(arg).ok().or_else(|| {
         regex
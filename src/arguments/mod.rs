@@ -2,16 +2,20 @@ mod discord;
 mod name;
 mod osu_id;
 mod osu_stats;
+mod prune;
 mod rank;
 mod simulate;
+mod streams;
 mod top;
 
 pub use discord::*;
 pub use name::*;
 pub use osu_id::*;
 pub use osu_stats::*;
+pub use prune::*;
 pub use rank::*;
 pub use simulate::*;
+pub use streams::*;
 pub use top::*;
 
 use regex::Regex;
@@ -240,6 +244,38 @@ fn miss(args: &mut Vec<String>) -> Result<Option<u32>, String> {
     }
 }
 
+// Value following one of the keys, `Some(None)` if the key is given without value
+fn take_value(args: &mut Vec<String>, keys: &[&str]) -> Option<Option<String>> {
+    let idx = args.iter().position(|arg| keys.contains(&arg.as_str()))?;
+    args.remove(idx);
+    if idx < args.len() {
+        Some(Some(args.remove(idx)))
+    } else {
+        Some(None)
+    }
+}
+
+// A single value is the minimum
+fn parse_range(arg: &str) -> Option<(Option<u32>, Option<u32>)> {
+    let parse = |value: &str| {
+        if value.is_empty() {
+            Some(None)
+        } else {
+            value.parse::<u32>().ok().map(Some)
+        }
+    };
+    let mut split = arg.split("..");
+    let min = parse(split.next()?)?;
+    let max = match split.next() {
+        Some(max) => parse(max)?,
+        None => None,
+    };
+    match (min, max) {
+        (None, None) => None,
+        range => Some(range),
+    }
+}
+
 fn keywords(args: &mut Vec<String>, keys: &[&str]) -> bool {
     for (i, arg) in args.iter().enumerate() {
         if keys.contains(&arg.as_str()) {
@@ -249,3 +285,27 @@ fn keywords(args: &mut Vec<String>, keys: &[&str]) -> bool {
     }
     false
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn integer_ranges() {
+        assert_eq!(parse_range("100"), Some((Some(100), None)));
+        assert_eq!(parse_range("100..500"), Some((Some(100), Some(500))));
+        assert_eq!(parse_range("..500"), Some((None, Some(500))));
+        assert_eq!(parse_range("100.."), Some((Some(100), None)));
+        assert_eq!(parse_range(".."), None);
+        assert_eq!(parse_range("many"), None);
+    }
+
+    #[test]
+    fn values_of_keys() {
+        let mut args = vec!["-t".to_owned(), "owc".to_owned(), "-t".to_owned()];
+        let keys = ["-t"];
+        assert_eq!(take_value(&mut args, &keys), Some(Some("owc".to_owned())));
+        assert_eq!(take_value(&mut args, &keys), Some(None));
+        assert_eq!(take_value(&mut args, &keys), None);
+    }
+}
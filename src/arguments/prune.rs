@@ -0,0 +1,197 @@
+use crate::arguments::{self, parse_user};
+
+use regex::{Regex, RegexBuilder};
+use serenity::{
+    framework::standard::Args,
+    model::{
+        channel::Message,
+        id::{MessageId, UserId},
+        misc::Mentionable,
+    },
+};
+use std::iter::FromIterator;
+
+/// Most messages that can be deleted at once
+pub const MAX_PRUNE: usize = 1000;
+
+pub struct PruneArgs {
+    pub amount: usize,
+    pub author: Option<UserId>,
+    pub bots: bool,
+    /// Lowercase text the content must contain
+    pub contains: Option<String>,
+    pub regex: Option<Regex>,
+    pub attachments: bool,
+    pub embeds: bool,
+    pub before: Option<MessageId>,
+    pub after: Option<MessageId>,
+}
+
+impl PruneArgs {
+    pub fn new(mut args: Args) -> Result<Self, String> {
+        let mut args = Vec::from_iter(arguments::first_n(&mut args, 14));
+        let author = match arguments::take_value(&mut args, &["-u", "-user"]) {
+            Some(Some(user)) => match parse_user(&user) {
+                Some(user) => Some(user),
+                None => return Err(format!("Could not parse `{}` as user", user)),
+            },
+            Some(None) => return Err("After `-user` you must specify a user".to_string()),
+            None => None,
+        };
+        let contains = match arguments::take_value(&mut args, &["-c", "-contains"]) {
+            Some(Some(text)) => Some(text.to_lowercase()),
+            Some(None) => return Err("After `-contains` you must specify a text".to_string()),
+            None => None,
+        };
+        let regex = match arguments::take_value(&mut args, &["-r", "-regex"]) {
+            Some(Some(regex)) => match RegexBuilder::new(&regex).size_limit(1 << 16).build() {
+                Ok(regex) => Some(regex),
+                Err(_) => return Err(format!("`{}` is not a valid regex", regex)),
+            },
+            Some(None) => return Err("After `-regex` you must specify a regex".to_string()),
+            None => None,
+        };
+        let before = message_id(&mut args, "-before")?;
+        let after = message_id(&mut args, "-after")?;
+        let bots = arguments::keywords(&mut args, &["--b", "--bots"]);
+        let attachments = arguments::keywords(&mut args, &["--a", "--attachments"]);
+        let embeds = arguments::keywords(&mut args, &["--e", "--embeds"]);
+        let mut args = args.into_iter();
+        let amount = match args.next() {
+            Some(arg) => match arg.parse::<usize>() {
+                Ok(amount) if (1..=MAX_PRUNE).contains(&amount) => Some(amount),
+                Ok(_) => {
+                    return Err(format!(
+                        "The amount must be an integer between 1 and {}",
+                        MAX_PRUNE
+                    ))
+                }
+                Err(_) => return Err(format!("Unknown argument `{}`", arg)),
+            },
+            None => None,
+        };
+        if let Some(arg) = args.next() {
+            return Err(format!("Unknown argument `{}`", arg));
+        }
+        let mut args = Self {
+            amount: 1,
+            author,
+            bots,
+            contains,
+            regex,
+            attachments,
+            embeds,
+            before,
+            after,
+        };
+        // Without amount, delete all filtered messages of the last hundred
+        args.amount = amount.unwrap_or(if args.has_filters() { 100 } else { 1 });
+        Ok(args)
+    }
+
+    fn has_filters(&self) -> bool {
+        self.author.is_some()
+            || self.bots
+            || self.contains.is_some()
+            || self.regex.is_some()
+            || self.attachments
+            || self.embeds
+    }
+
+    /// Whether the message passes all filters except `before` and `after`
+    pub fn matches(&self, msg: &Message) -> bool {
+        if self.author.map_or(false, |author| author != msg.author.id)
+            || (self.bots && !msg.author.bot)
+            || (self.attachments && msg.attachments.is_empty())
+            || (self.embeds && msg.embeds.is_empty())
+        {
+            return false;
+        }
+        if let Some(ref text) = self.contains {
+            if !msg.content.to_lowercase().contains(text) {
+                return false;
+            }
+        }
+        self.regex
+            .as_ref()
+            .map_or(true, |regex| regex.is_match(&msg.content))
+    }
+
+    /// Description of the filters for the mod log
+    pub fn filters(&self) -> String {
+        let mut filters = Vec::new();
+        if let Some(author) = self.author {
+            filters.push(format!("from {}", author.mention()));
+        }
+        if self.bots {
+            filters.push("from bots".to_owned());
+        }
+        if let Some(ref text) = self.contains {
+            filters.push(format!("containing `{}`", text));
+        }
+        if let Some(ref regex) = self.regex {
+            filters.push(format!("matching `{}`", regex));
+        }
+        if self.attachments {
+            filters.push("with attachments".to_owned());
+        }
+        if self.embeds {
+            filters.push("with embeds".to_owned());
+        }
+        if let Some(before) = self.before {
+            filters.push(format!("before message {}", before));
+        }
+        if let Some(after) = self.after {
+            filters.push(format!("after message {}", after));
+        }
+        if filters.is_empty() {
+            "None".to_owned()
+        } else {
+            filters.join(", ")
+        }
+    }
+}
+
+fn message_id(args: &mut Vec<String>, key: &str) -> Result<Option<MessageId>, String> {
+    match arguments::take_value(args, &[key]) {
+        Some(Some(id)) => match id.parse::<u64>() {
+            Ok(id) => Ok(Some(MessageId(id))),
+            Err(_) => Err(format!("Could not parse `{}` as message id", id)),
+        },
+        Some(None) => Err(format!("After `{}` you must specify a message id", key)),
+        None => Ok(None),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use serenity::framework::standard::Delimiter;
+
+    fn parse(content: &str) -> Result<PruneArgs, String> {
+        PruneArgs::new(Args::new(content, &[Delimiter::Single(' ')]))
+    }
+
+    #[test]
+    fn amounts() {
+        assert_eq!(parse("").unwrap().amount, 1);
+        assert_eq!(parse("250").unwrap().amount, 250);
+        assert_eq!(parse("--bots").unwrap().amount, 100);
+        assert_eq!(parse("-u <@!123> 20").unwrap().amount, 20);
+        assert!(parse("0").is_err());
+        assert!(parse("1001").is_err());
+    }
+
+    #[test]
+    fn filters() {
+        let args = parse("-u <@123> -c \"Hello There\" -after 456 --embeds").unwrap();
+        assert_eq!(args.author, Some(UserId(123)));
+        assert_eq!(args.contains.as_deref(), Some("hello there"));
+        assert_eq!(args.after, Some(MessageId(456)));
+        assert!(args.embeds && !args.bots && !args.attachments);
+        assert!(parse("-r \"(unclosed\"").is_err());
+        assert!(parse("-before abc").is_err());
+        assert!(parse("--everything").is_err());
+    }
+}
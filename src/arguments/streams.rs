@@ -0,0 +1,150 @@
+use crate::{arguments, streams::Stream};
+
+use serenity::framework::standard::Args;
+use std::iter::FromIterator;
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum StreamSortBy {
+    Viewers,
+    Uptime,
+}
+
+pub struct StreamFilterArgs {
+    pub language: Option<String>,
+    pub viewers_min: Option<u32>,
+    pub viewers_max: Option<u32>,
+    pub keywords: Vec<String>,
+    pub tracked: bool,
+    pub sort_by: StreamSortBy,
+}
+
+impl StreamFilterArgs {
+    pub fn new(mut args: Args) -> Result<Self, String> {
+        let mut args = Vec::from_iter(arguments::first_n(&mut args, 12));
+        // Parse language
+        let language = match arguments::take_value(&mut args, &["-l", "-lang", "-language"]) {
+            Some(Some(language)) => Some(language.to_lowercase()),
+            Some(None) => {
+                return Err(String::from(
+                    "After the language keyword you must specify a language code e.g. `en`",
+                ))
+            }
+            None => None,
+        };
+        // Parse min and max viewers
+        let viewers = arguments::take_value(&mut args, &["-v", "-viewers"]);
+        let (viewers_min, viewers_max) = match viewers {
+            Some(value) => match value.as_deref().and_then(arguments::parse_range) {
+                Some(range) => range,
+                None => {
+                    return Err(String::from(
+                        "After the viewers keyword you must specify either an integer for \
+                        min viewers or an integer range of the form `a..b`, `a..`, or `..b`",
+                    ))
+                }
+            },
+            None => (None, None),
+        };
+        // Parse title keywords, there can be multiple
+        let mut keywords = Vec::new();
+        while let Some(keyword) = arguments::take_value(&mut args, &["-t", "-title"]) {
+            match keyword {
+                Some(keyword) => keywords.push(keyword.to_lowercase()),
+                None => {
+                    return Err(String::from(
+                        "After the title keyword you must specify a word the title should contain",
+                    ))
+                }
+            }
+        }
+        let tracked = arguments::keywords(&mut args, &["--t", "--tracked"]);
+        // Parse order
+        let sort_by = if arguments::keywords(&mut args, &["--u", "--uptime"]) {
+            StreamSortBy::Uptime
+        } else {
+            arguments::keywords(&mut args, &["--v", "--viewers"]);
+            StreamSortBy::Viewers
+        };
+        if let Some(arg) = args.first() {
+            return Err(format!("Unknown argument `{}`", arg));
+        }
+        Ok(Self {
+            language,
+            viewers_min,
+            viewers_max,
+            keywords,
+            tracked,
+            sort_by,
+        })
+    }
+
+    /// Whether the stream passes all filters except `tracked`
+    pub fn matches(&self, stream: &Stream) -> bool {
+        if let Some(ref language) = self.language {
+            if stream.language.as_ref() != Some(language) {
+                return false;
+            }
+        }
+        if self
+            .viewers_min
+            .map_or(false, |min| stream.viewer_count < min)
+            || self
+                .viewers_max
+                .map_or(false, |max| stream.viewer_count > max)
+        {
+            return false;
+        }
+        if self.keywords.is_empty() {
+            return true;
+        }
+        let title = stream.title.to_lowercase();
+        self.keywords.iter().any(|keyword| title.contains(keyword))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::streams::Platform;
+    use serenity::framework::standard::Delimiter;
+
+    fn parse(content: &str) -> StreamFilterArgs {
+        StreamFilterArgs::new(Args::new(content, &[Delimiter::Single(' ')])).unwrap()
+    }
+
+    fn stream(title: &str, viewers: u32, language: Option<&str>) -> Stream {
+        Stream {
+            platform: Platform::Twitch,
+            user_id: String::from("0"),
+            username: String::from("name"),
+            title: title.to_owned(),
+            url: String::new(),
+            thumbnail_url: String::new(),
+            viewer_count: viewers,
+            started_at: None,
+            language: language.map(str::to_owned),
+            game: None,
+        }
+    }
+
+    #[test]
+    fn language_and_viewers() {
+        let args = parse("-lang EN -v 100..500");
+        assert!(args.matches(&stream("osu!", 100, Some("en"))));
+        assert!(args.matches(&stream("osu!", 500, Some("en"))));
+        assert!(!args.matches(&stream("osu!", 99, Some("en"))));
+        assert!(!args.matches(&stream("osu!", 501, Some("en"))));
+        assert!(!args.matches(&stream("osu!", 200, Some("de"))));
+        assert!(!args.matches(&stream("osu!", 200, None)));
+    }
+
+    #[test]
+    fn title_keywords() {
+        let args = parse("-t owc -t Tournament");
+        assert!(args.matches(&stream("OWC 2020 finals", 0, None)));
+        assert!(args.matches(&stream("Local tournament", 0, None)));
+        assert!(!args.matches(&stream("Farming pp", 0, None)));
+        assert!(parse("").matches(&stream("Farming pp", 0, None)));
+    }
+}
@@ -0,0 +1,161 @@
+use super::RevealMode;
+use crate::commands::utility::MapsetTags;
+
+use rosu::models::GameMode;
+use std::str::FromStr;
+
+pub const MAX_HINT_COOLDOWN: u32 = 60;
+pub const MAX_AUTO_STOP: u32 = 30;
+
+/// Game settings stored for a channel, used by `<bg start` instead of asking for tags
+#[derive(Clone, Debug, PartialEq)]
+pub struct BgConfig {
+    pub mode: GameMode,
+    pub included: MapsetTags,
+    pub excluded: MapsetTags,
+    pub reveal: RevealMode,
+    /// Seconds between two hints
+    pub hint_cooldown: u32,
+    /// Minutes without a correct guess until the game stops
+    pub auto_stop: u32,
+}
+
+impl Default for BgConfig {
+    fn default() -> Self {
+        Self {
+            mode: GameMode::STD,
+            included: MapsetTags::empty(),
+            excluded: MapsetTags::empty(),
+            reveal: RevealMode::Circle,
+            hint_cooldown: 0,
+            auto_stop: 3,
+        }
+    }
+}
+
+impl BgConfig {
+    /// Apply a `<bg config` setting, returns a message on invalid values
+    pub fn update(&mut self, setting: ConfigSetting, value: &str) -> Result<(), String> {
+        match setting {
+            ConfigSetting::Mode => {
+                self.mode = match value.to_lowercase().as_str() {
+                    "osu" | "std" | "standard" | "o" => GameMode::STD,
+                    "mna" | "mania" | "m" => GameMode::MNA,
+                    _ => return Err("The mode must be either `std` or `mania`".to_owned()),
+                };
+            }
+            ConfigSetting::Include | ConfigSetting::Exclude => {
+                let tags = parse_tags(value)?;
+                if setting == ConfigSetting::Include {
+                    self.included = tags;
+                    self.excluded.remove(tags);
+                } else {
+                    self.excluded = tags;
+                    self.included.remove(tags);
+                }
+            }
+            ConfigSetting::Reveal => {
+                self.reveal = value.parse().map_err(|_| {
+                    "The reveal mode must be one of `circle`, `pixel`, `blur`, \
+                    `tiles`, `gray`, `invert`, `mirror`, or `random`"
+                        .to_owned()
+                })?;
+            }
+            ConfigSetting::HintCooldown => {
+                self.hint_cooldown = match value.parse() {
+                    Ok(secs) if secs <= MAX_HINT_COOLDOWN => secs,
+                    _ => {
+                        return Err(format!(
+                            "The hint cooldown must be between 0 and {} seconds",
+                            MAX_HINT_COOLDOWN
+                        ))
+                    }
+                };
+            }
+            ConfigSetting::AutoStop => {
+                self.auto_stop = match value.parse() {
+                    Ok(minutes) if minutes > 0 && minutes <= MAX_AUTO_STOP => minutes,
+                    _ => {
+                        return Err(format!(
+                            "The auto-stop must be between 1 and {} minutes",
+                            MAX_AUTO_STOP
+                        ))
+                    }
+                };
+            }
+        }
+        Ok(())
+    }
+}
+
+// Whitespace or comma separated tags, `none` for no tags
+fn parse_tags(value: &str) -> Result<MapsetTags, String> {
+    let mut tags = MapsetTags::empty();
+    for tag in value.split(|c: char| c == ',' || c.is_whitespace()) {
+        match tag {
+            "" => {}
+            _ if tag.eq_ignore_ascii_case("none") => return Ok(MapsetTags::empty()),
+            _ => tags.insert(tag.parse::<MapsetTags>().map_err(|tag| {
+                format!(
+                    "Could not parse tag `{}`.\nBe sure to only give these tags:\n\
+                    `farm, streams, alternate, old, meme, hardname, \
+                    easy, hard, tech, weeb, bluesky, english`",
+                    tag
+                )
+            })?),
+        }
+    }
+    Ok(tags)
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ConfigSetting {
+    Mode,
+    Include,
+    Exclude,
+    Reveal,
+    HintCooldown,
+    AutoStop,
+}
+
+impl FromStr for ConfigSetting {
+    type Err = ();
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let setting = match s.to_lowercase().as_str() {
+            "mode" | "m" => ConfigSetting::Mode,
+            "include" | "included" | "i" => ConfigSetting::Include,
+            "exclude" | "excluded" | "e" => ConfigSetting::Exclude,
+            "reveal" | "r" => ConfigSetting::Reveal,
+            "hintcooldown" | "hint" | "cooldown" | "h" => ConfigSetting::HintCooldown,
+            "autostop" | "stop" | "timeout" | "a" => ConfigSetting::AutoStop,
+            _ => return Err(()),
+        };
+        Ok(setting)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn include_and_exclude_stay_disjoint() {
+        let mut config = BgConfig::default();
+        config.update(ConfigSetting::Include, "easy, weeb").unwrap();
+        config.update(ConfigSetting::Exclude, "weeb farm").unwrap();
+        assert_eq!(config.included, MapsetTags::Easy);
+        assert_eq!(config.excluded, MapsetTags::Weeb | MapsetTags::Farm);
+        config.update(ConfigSetting::Include, "none").unwrap();
+        assert!(config.included.is_empty());
+    }
+
+    #[test]
+    fn invalid_values_are_rejected() {
+        let mut config = BgConfig::default();
+        assert!(config.update(ConfigSetting::Mode, "taiko").is_err());
+        assert!(config.update(ConfigSetting::AutoStop, "0").is_err());
+        assert!(config.update(ConfigSetting::HintCooldown, "61").is_err());
+        assert!(config.update(ConfigSetting::Include, "sad").is_err());
+        assert_eq!(config, BgConfig::default());
+    }
+}
@@ -0,0 +1,123 @@
+use crate::{
+    commands::utility::MapsetTags,
+    database::{BgMapsetStats, MapsetTagWrapper},
+};
+
+use std::{collections::HashMap, fmt, str::FromStr};
+
+// How strongly mapsets far from the target difficulty are suppressed
+const SPREAD: f64 = 0.2;
+// Every mapset keeps a small chance of being picked
+const MIN_WEIGHT: f64 = 0.01;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Difficulty {
+    Easy,
+    Medium,
+    Hard,
+}
+
+impl Difficulty {
+    fn target(self) -> f64 {
+        match self {
+            Difficulty::Easy => 0.15,
+            Difficulty::Medium => 0.5,
+            Difficulty::Hard => 0.85,
+        }
+    }
+
+    /// Selection weights of the mapsets, in the same order
+    pub fn weights(
+        self,
+        mapsets: &[MapsetTagWrapper],
+        stats: &HashMap<u32, BgMapsetStats>,
+    ) -> Vec<f64> {
+        let target = self.target();
+        mapsets
+            .iter()
+            .map(|mapset| {
+                let difficulty = mapset_difficulty(mapset, stats.get(&mapset.mapset_id));
+                let distance = (difficulty - target) / SPREAD;
+                (-distance * distance).exp().max(MIN_WEIGHT)
+            })
+            .collect()
+    }
+}
+
+impl FromStr for Difficulty {
+    type Err = ();
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "easy" | "e" => Ok(Difficulty::Easy),
+            "medium" | "m" => Ok(Difficulty::Medium),
+            "hard" | "h" => Ok(Difficulty::Hard),
+            _ => Err(()),
+        }
+    }
+}
+
+impl fmt::Display for Difficulty {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            Difficulty::Easy => "easy",
+            Difficulty::Medium => "medium",
+            Difficulty::Hard => "hard",
+        };
+        f.write_str(name)
+    }
+}
+
+// Use the recorded stats if there are enough, otherwise the manual tags
+fn mapset_difficulty(mapset: &MapsetTagWrapper, stats: Option<&BgMapsetStats>) -> f64 {
+    if let Some(difficulty) = stats.and_then(BgMapsetStats::difficulty) {
+        difficulty
+    } else if mapset.has_tags(MapsetTags::Easy) {
+        0.2
+    } else if mapset.has_tags(MapsetTags::Hard) {
+        0.8
+    } else {
+        0.5
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rosu::models::GameMode;
+
+    fn mapset(mapset_id: u32, tags: MapsetTags) -> MapsetTagWrapper {
+        MapsetTagWrapper {
+            mapset_id,
+            mode: GameMode::STD,
+            filetype: "png".to_owned(),
+            tags,
+        }
+    }
+
+    #[test]
+    fn stats_outweigh_tags() {
+        let mapsets = [mapset(1, MapsetTags::Easy), mapset(2, MapsetTags::empty())];
+        let mut stats = HashMap::new();
+        // Rarely guessed despite the easy tag
+        let hard_stats = BgMapsetStats {
+            shown: 10,
+            guessed: 1,
+            guess_secs: 100.0,
+            hints: 25,
+        };
+        stats.insert(1, hard_stats);
+        let weights = Difficulty::Hard.weights(&mapsets, &stats);
+        assert!(weights[0] > weights[1]);
+        let weights = Difficulty::Easy.weights(&mapsets, &stats);
+        assert!(weights[0] < weights[1]);
+    }
+
+    #[test]
+    fn tags_without_stats() {
+        let mapsets = [mapset(1, MapsetTags::Easy), mapset(2, MapsetTags::Hard)];
+        let stats = HashMap::new();
+        let weights = Difficulty::Easy.weights(&mapsets, &stats);
+        assert!(weights[0] > weights[1]);
+        assert!(weights.iter().all(|&weight| weight >= MIN_WEIGHT));
+    }
+}
@@ -1,17 +1,34 @@
-use super::{util, Hints, ImageReveal};
-use crate::{database::MapsetTagWrapper, util::globals::HOMEPAGE, BgGames, MySQL};
+use super::{
+    session,
+    util::{self, Aliases, ContentResult},
+    Difficulty, Hints, ImageReveal, RevealMode, Session, StartOptions, TagVoting,
+};
+use crate::{
+    database::MapsetTagWrapper,
+    embeds::{BGPodiumEmbed, BGScoreboardEmbed, EmbedData},
+    util::globals::HOMEPAGE,
+    BgGames, MySQL,
+};
 
 use failure::Error;
 use image::GenericImageView;
+use rand::distributions::WeightedIndex;
 use rosu::models::GameMode;
 use serenity::{
     collector::{MessageCollector, MessageCollectorBuilder},
     framework::standard::CommandResult,
     http::client::Http,
-    model::id::ChannelId,
+    model::id::{ChannelId, GuildId},
     prelude::{Context, RwLock, TypeMap},
 };
-use std::{collections::VecDeque, env, fmt::Write, path::PathBuf, sync::Arc};
+use std::{
+    collections::{HashMap, VecDeque},
+    env,
+    fmt::Write,
+    path::PathBuf,
+    sync::Arc,
+    time::{Duration, Instant},
+};
 use tokio::{
     fs,
     stream::StreamExt,
@@ -61,7 +78,22 @@ impl BackGroundGame {
         game.hint()
     }
 
-    pub async fn start(&self, ctx: &Context, channel: ChannelId, mapsets: Vec<MapsetTagWrapper>) {
+    /// Without a given amount of rounds, the game runs until it's stopped or times out.
+    /// The reveal mode applies to all rounds, unless it's random.
+    pub async fn start(
+        &self,
+        ctx: &Context,
+        channel: ChannelId,
+        guild: Option<GuildId>,
+        mapsets: Vec<MapsetTagWrapper>,
+        options: StartOptions,
+    ) {
+        {
+            let mut game = self.game.write().await;
+            game.reveal_mode = options.reveal;
+            game.hint_cooldown = Duration::from_secs(options.hint_cooldown as u64);
+            game.voting = Some(TagVoting::new(ctx).await);
+        }
         let mut collector = MessageCollectorBuilder::new(ctx)
             .channel_id(channel)
             .filter(|msg| !msg.author.bot)
@@ -70,20 +102,41 @@ impl BackGroundGame {
         let mut rx = self.rx.clone();
         let data = Arc::clone(&ctx.data);
         let http = Arc::clone(&ctx.http);
+        let auto_stop = Duration::from_secs(60 * options.auto_stop as u64);
         tokio::spawn(async move {
             let mut previous_ids = VecDeque::with_capacity(100);
+            let mut session = options.rounds.map(Session::new);
+            let weights = match options.difficulty {
+                Some(difficulty) => selection_weights(&data, difficulty, &mapsets).await,
+                None => None,
+            };
             loop {
                 // Initialize game
-                let img = {
+                let (img, mode) = {
                     let mut game = game_lock.write().await;
-                    game.restart_with_img(Arc::clone(&data), &mapsets, &mut previous_ids)
-                        .await
+                    let img = game
+                        .restart_with_img(
+                            Arc::clone(&data),
+                            &mapsets,
+                            weights.as_ref(),
+                            &mut previous_ids,
+                        )
+                        .await;
+                    (img, game.reveal.mode())
                 };
+                let mut content = match session {
+                    Some(ref session) => {
+                        format!("Round {}/{}:", session.round + 1, session.rounds)
+                    }
+                    None => "Here's the next one:".to_owned(),
+                };
+                if mode != RevealMode::Circle {
+                    let _ = write!(content, " *({})*", mode);
+                }
                 let _ = channel
                     .send_message(&http, |m| {
                         let bytes: &[u8] = &img;
-                        m.content("Here's the next one:")
-                            .add_file((bytes, "bg_img.png"))
+                        m.content(content).add_file((bytes, "bg_img.png"))
                     })
                     .await;
 
@@ -92,11 +145,30 @@ impl BackGroundGame {
                     option = rx.recv() => option.unwrap_or_else(|| LoopResult::Stop),
                     // Let the game run
                     result = game_loop(&mut collector, &http, &game_lock, channel) => result,
-                    // Timeout after 3 minutes
-                    _ = time::delay_for(time::Duration::from_secs(180)) => LoopResult::Stop,
+                    // Timeout after the channel's auto-stop duration
+                    _ = time::delay_for(auto_stop) => LoopResult::Stop,
                 };
 
+                // Remember how the round went to estimate the mapset's difficulty,
+                // stopped games were abandoned rather than given up on
+                if !matches!(result, LoopResult::Stop) {
+                    let guess_secs = match result {
+                        LoopResult::Winner { secs, .. } => Some(secs),
+                        _ => None,
+                    };
+                    let (mapset_id, hints) = {
+                        let game = game_lock.read().await;
+                        (game.mapset_id, game.hints_used)
+                    };
+                    let data = data.read().await;
+                    let mysql = data.get::<MySQL>().unwrap();
+                    if let Err(why) = mysql.record_bg_round(mapset_id, guess_secs, hints).await {
+                        warn!("Error while recording bg round: {}", why);
+                    }
+                }
+
                 // Process the result
+                let mut winner = None;
                 match result {
                     LoopResult::Restart => {
                         // Send message
@@ -117,6 +189,11 @@ impl BackGroundGame {
                         );
                         let _ = game.resolve(&http, channel, content).await;
                         // Then quit
+                        if let Some(ref session) = session {
+                            if session.round > 0 {
+                                finish_session(&http, &data, channel, &mapsets, session).await;
+                            }
+                        }
                         game.discord_data
                             .as_ref()
                             .unwrap()
@@ -129,14 +206,50 @@ impl BackGroundGame {
                         debug!("Game finished");
                         break;
                     }
-                    LoopResult::Winner(user_id) => {
+                    LoopResult::Winner {
+                        user_id,
+                        name,
+                        points,
+                        ..
+                    } => {
                         if mapsets.len() >= 20 {
                             let data = data.read().await;
                             let mysql = data.get::<MySQL>().unwrap();
-                            if let Err(why) = mysql.increment_bggame_score(user_id).await {
+                            if let Err(why) = mysql
+                                .increment_bggame_score(user_id, guild.map(|guild| guild.0))
+                                .await
+                            {
                                 error!("Error while incrementing bggame score: {}", why);
                             }
                         }
+                        winner = Some((user_id, name, points));
+                    }
+                }
+                // Keep track of the session's scores
+                if let Some(ref mut session) = session {
+                    if let Some((user_id, ref name, points)) = winner {
+                        session.add_points(user_id, name.clone(), points);
+                    }
+                    session.finish_round();
+                    let standings = session.standings();
+                    let winner = winner
+                        .as_ref()
+                        .map(|(_, name, points)| (name.as_str(), *points));
+                    let embed_data =
+                        BGScoreboardEmbed::new(session.round, session.rounds, &standings, winner);
+                    let _ = channel
+                        .send_message(&http, |m| m.embed(|e| embed_data.build(e)))
+                        .await;
+                    if session.finished() {
+                        finish_session(&http, &data, channel, &mapsets, session).await;
+                        data.write()
+                            .await
+                            .get_mut::<BgGames>()
+                            .unwrap()
+                            .remove(&channel);
+                        collector.stop();
+                        debug!("Game session finished");
+                        break;
                     }
                 }
             }
@@ -144,9 +257,72 @@ impl BackGroundGame {
     }
 }
 
-#[derive(Clone, Copy)]
+// Selection weights of the mapsets based on their recorded stats
+async fn selection_weights(
+    data: &RwLock<TypeMap>,
+    difficulty: Difficulty,
+    mapsets: &[MapsetTagWrapper],
+) -> Option<WeightedIndex<f64>> {
+    let stats = {
+        let data = data.read().await;
+        let mysql = data.get::<MySQL>().unwrap();
+        mysql.get_bg_mapset_stats().await
+    };
+    let stats = match stats {
+        Ok(stats) => stats,
+        Err(why) => {
+            warn!("Error while getting bg mapset stats: {}", why);
+            HashMap::new()
+        }
+    };
+    match WeightedIndex::new(difficulty.weights(mapsets, &stats)) {
+        Ok(weights) => Some(weights),
+        Err(why) => {
+            warn!("Could not create bg selection weights: {}", why);
+            None
+        }
+    }
+}
+
+// Send the podium and store the results
+async fn finish_session(
+    http: &Http,
+    data: &RwLock<TypeMap>,
+    channel: ChannelId,
+    mapsets: &[MapsetTagWrapper],
+    session: &Session,
+) {
+    let standings = session.standings();
+    let embed_data = BGPodiumEmbed::new(session.round, session.rounds, &standings);
+    let _ = channel
+        .send_message(http, |m| m.embed(|e| embed_data.build(e)))
+        .await;
+    if standings.is_empty() || mapsets.len() < 20 {
+        return;
+    }
+    let scores: Vec<_> = standings
+        .iter()
+        .map(|score| (score.user_id, score.points, score.guesses))
+        .collect();
+    let data = data.read().await;
+    let mysql = data.get::<MySQL>().unwrap();
+    let mode = mapsets[0].mode;
+    if let Err(why) = mysql
+        .insert_bg_session(channel.0, mode, session.round, &scores)
+        .await
+    {
+        error!("Error while storing bg game session: {}", why);
+    }
+}
+
+#[derive(Clone)]
 enum LoopResult {
-    Winner(u64),
+    Winner {
+        user_id: u64,
+        name: String,
+        points: u32,
+        secs: f32,
+    },
     Restart,
     Stop,
 }
@@ -157,10 +333,17 @@ async fn game_loop(
     game_lock: &RwLock<GameData>,
     channel: ChannelId,
 ) -> LoopResult {
+    let round_start = Instant::now();
     // Collect and evaluate messages
     while let Some(msg) = collector.next().await {
         let mut game = game_lock.write().await;
-        let content_result = check_msg_content(&msg.content, &game);
+        let content_result = util::check_guess(
+            &msg.content,
+            &game.title,
+            &game.artist,
+            &game.aliases,
+            game.hints.artist_guessed,
+        );
         match content_result {
             // Title correct?
             ContentResult::Title { exact } => {
@@ -169,6 +352,8 @@ async fn game_loop(
                 } else {
                     format!("You were close enough {}, gratz", msg.author.name)
                 };
+                let elapsed = round_start.elapsed();
+                let points = session::round_points(elapsed, game.hints_used, game.bigger_used);
                 let _ = write!(
                     content,
                     " \\:)\nMapset: {}beatmapsets/{}",
@@ -176,7 +361,12 @@ async fn game_loop(
                 );
                 // Send message
                 let _ = game.resolve(&http, channel, content).await;
-                return LoopResult::Winner(msg.author.id.0);
+                return LoopResult::Winner {
+                    user_id: msg.author.id.0,
+                    name: msg.author.name.clone(),
+                    points,
+                    secs: elapsed.as_secs_f32(),
+                };
             }
             // Artist correct?
             ContentResult::Artist { exact } => {
@@ -202,56 +392,21 @@ async fn game_loop(
     LoopResult::Stop
 }
 
-enum ContentResult {
-    Title { exact: bool },
-    Artist { exact: bool },
-    None,
-}
-
-fn check_msg_content(content: &str, game: &GameData) -> ContentResult {
-    // Guessed the title exactly?
-    if content == game.title {
-        return ContentResult::Title { exact: true };
-    }
-    // Guessed sufficiently many words of the title?
-    if game.title.contains(&" ") {
-        let mut same_word_len = 0;
-        for title_word in game.title.split(' ') {
-            for content_word in content.split(' ') {
-                if title_word == content_word {
-                    same_word_len += title_word.len();
-                    if same_word_len > 8 {
-                        return ContentResult::Title { exact: false };
-                    }
-                }
-            }
-        }
-    }
-    // Similar enough to the title?
-    let similarity = util::similarity(content, &game.title);
-    if similarity > 0.5 {
-        return ContentResult::Title { exact: false };
-    }
-    if !game.hints.artist_guessed {
-        // Guessed the artist exactly?
-        if content == game.artist {
-            return ContentResult::Artist { exact: true };
-        // Similar enough to the artist?
-        } else if similarity < 0.3 && util::similarity(content, &game.artist) > 0.5 {
-            return ContentResult::Artist { exact: false };
-        }
-    }
-    ContentResult::None
-}
-
 #[derive(Default)]
 pub struct GameData {
     pub title: String,
     pub artist: String,
     pub mapset_id: u32,
+    aliases: Aliases,
     discord_data: Option<Arc<RwLock<TypeMap>>>,
     hints: Hints,
     reveal: ImageReveal,
+    reveal_mode: RevealMode,
+    hints_used: usize,
+    bigger_used: usize,
+    hint_cooldown: Duration,
+    last_hint: Option<Instant>,
+    voting: Option<TagVoting>,
 }
 
 impl GameData {
@@ -259,6 +414,7 @@ impl GameData {
         &mut self,
         data: Arc<RwLock<TypeMap>>,
         mapsets: &[MapsetTagWrapper],
+        weights: Option<&WeightedIndex<f64>>,
         previous_ids: &mut VecDeque<u32>,
     ) -> Result<(), Error> {
         let mut path = PathBuf::from(env::var("BG_PATH")?);
@@ -267,9 +423,10 @@ impl GameData {
             GameMode::MNA => path.push("mania"),
             GameMode::TKO | GameMode::CTB => panic!("TKO and CTB not yet supported as bg game"),
         }
-        let mapset = util::get_random_mapset(mapsets, previous_ids).await?;
+        let mapset = util::get_random_mapset(mapsets, weights, previous_ids).await?;
         debug!("Next BG mapset id: {}", mapset.mapset_id);
         let (title, artist) = util::get_title_artist(mapset.mapset_id, &data).await?;
+        let aliases = util::get_aliases(mapset.mapset_id, &data).await;
         let filename = format!("{}.{}", mapset.mapset_id, mapset.filetype);
         path.push(filename);
         let img_vec = fs::read(path).await?;
@@ -283,7 +440,11 @@ impl GameData {
         self.title = title;
         self.artist = artist;
         self.mapset_id = mapset.mapset_id;
-        self.reveal = ImageReveal::new(img);
+        self.aliases = aliases;
+        self.reveal = ImageReveal::new(img, self.reveal_mode.pick());
+        self.hints_used = 0;
+        self.last_hint = None;
+        self.bigger_used = 0;
         self.discord_data = Some(data);
         Ok(())
     }
@@ -292,10 +453,12 @@ impl GameData {
         &mut self,
         data: Arc<RwLock<TypeMap>>,
         mapsets: &[MapsetTagWrapper],
+        weights: Option<&WeightedIndex<f64>>,
         previous_ids: &mut VecDeque<u32>,
     ) -> Vec<u8> {
         loop {
-            match self.restart(Arc::clone(&data), mapsets, previous_ids).await {
+            let restart = self.restart(Arc::clone(&data), mapsets, weights, previous_ids);
+            match restart.await {
                 Ok(_) => match self.reveal.sub_image() {
                     Ok(img) => return img,
                     Err(why) => warn!(
@@ -309,31 +472,45 @@ impl GameData {
     }
 
     pub fn sub_image(&mut self) -> Result<Vec<u8>, Error> {
-        self.reveal.increase_radius();
+        self.bigger_used += 1;
+        self.reveal.enhance();
         self.reveal.sub_image()
     }
 
     pub fn hint(&mut self) -> String {
+        if let Some(last_hint) = self.last_hint {
+            let elapsed = last_hint.elapsed();
+            if elapsed < self.hint_cooldown {
+                let remaining = (self.hint_cooldown - elapsed).as_secs() + 1;
+                return format!("The next hint is available in {} seconds", remaining);
+            }
+        }
+        self.last_hint = Some(Instant::now());
+        self.hints_used += 1;
         self.hints.get(&self.title, &self.artist)
     }
 
+    /// Reveal the full background and let players vote on its tags
     pub async fn resolve(&self, http: &Http, channel: ChannelId, content: String) -> CommandResult {
-        match self.reveal.full() {
+        let response = match self.reveal.full() {
             Ok(bytes) => {
                 channel
                     .send_message(http, |m| {
                         m.content(content)
                             .add_file((bytes.as_slice(), "bg_img.png"))
                     })
-                    .await?;
+                    .await?
             }
             Err(why) => {
                 warn!(
                     "Could not get full reveal of mapset id {}: {}",
                     self.mapset_id, why
                 );
-                channel.send_message(http, |m| m.content(content)).await?;
+                channel.send_message(http, |m| m.content(content)).await?
             }
+        };
+        if let Some(ref voting) = self.voting {
+            voting.start(response, self.mapset_id);
         }
         Ok(())
     }
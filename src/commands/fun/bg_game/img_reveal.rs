@@ -1,65 +1,281 @@
 use failure::Error;
-use image::{png::PNGEncoder, ColorType, DynamicImage, GenericImageView};
-use rand::RngCore;
+use image::{
+    imageops::{self, FilterType},
+    png::PNGEncoder,
+    ColorType, DynamicImage, GenericImageView, RgbaImage,
+};
+use rand::{seq::SliceRandom, RngCore};
+use std::{fmt, str::FromStr};
+
+const TILES_X: u32 = 8;
+const TILES_Y: u32 = 6;
+const TILES_PER_LEVEL: usize = 4;
+const PIXEL_SIZE: f32 = 48.0;
+const BLUR_SIGMA: f32 = 16.0;
+
+/// How the background is shown before it's fully revealed
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum RevealMode {
+    /// Growing circular section around a random point
+    Circle,
+    /// Pixelation that gets finer
+    Pixelate,
+    /// Blur that lessens
+    Blur,
+    /// Randomly revealed tiles
+    Tiles,
+    /// Circular section without colors
+    Grayscale,
+    /// Circular section with inverted colors
+    Inverted,
+    /// Circular section of the horizontally mirrored image
+    Mirrored,
+    /// Any of the above, chosen anew each round
+    Random,
+}
+
+impl RevealMode {
+    /// Resolve `Random` into a specific mode
+    pub fn pick(self) -> Self {
+        if self != RevealMode::Random {
+            return self;
+        }
+        let modes = [
+            RevealMode::Circle,
+            RevealMode::Pixelate,
+            RevealMode::Blur,
+            RevealMode::Tiles,
+            RevealMode::Grayscale,
+            RevealMode::Inverted,
+            RevealMode::Mirrored,
+        ];
+        *modes.choose(&mut rand::thread_rng()).unwrap()
+    }
+}
+
+impl Default for RevealMode {
+    fn default() -> Self {
+        RevealMode::Circle
+    }
+}
+
+impl FromStr for RevealMode {
+    type Err = ();
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mode = match s.to_lowercase().as_str() {
+            "circle" | "c" => RevealMode::Circle,
+            "pixelate" | "pixelated" | "pixel" | "p" => RevealMode::Pixelate,
+            "blur" | "blurred" | "b" => RevealMode::Blur,
+            "tiles" | "tile" | "t" => RevealMode::Tiles,
+            "grayscale" | "gray" | "grey" | "g" => RevealMode::Grayscale,
+            "inverted" | "invert" | "i" => RevealMode::Inverted,
+            "mirrored" | "mirror" | "m" => RevealMode::Mirrored,
+            "random" | "r" => RevealMode::Random,
+            _ => return Err(()),
+        };
+        Ok(mode)
+    }
+}
+
+impl fmt::Display for RevealMode {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            RevealMode::Circle => "circle",
+            RevealMode::Pixelate => "pixelated",
+            RevealMode::Blur => "blurred",
+            RevealMode::Tiles => "tiles",
+            RevealMode::Grayscale => "grayscale",
+            RevealMode::Inverted => "inverted",
+            RevealMode::Mirrored => "mirrored",
+            RevealMode::Random => "random",
+        };
+        f.write_str(name)
+    }
+}
 
 pub struct ImageReveal {
     original: DynamicImage,
+    // Recolored or flipped original for the circle based modes
+    distorted: Option<DynamicImage>,
+    mode: RevealMode,
+    level: u32,
     x: u32,
     y: u32,
     radius: u32,
+    tiles: Vec<(u32, u32)>,
 }
 
 impl ImageReveal {
-    pub fn new(original: DynamicImage) -> Self {
+    /// `mode` should not be `Random`, resolve it with `RevealMode::pick` first
+    pub fn new(original: DynamicImage, mode: RevealMode) -> Self {
         let (w, h) = original.dimensions();
         let radius = 100;
         let mut rng = rand::thread_rng();
         let x = radius + rng.next_u32() % (w - 2 * radius);
         let y = radius + rng.next_u32() % (h - 2 * radius);
+        let distorted = match mode {
+            RevealMode::Grayscale => Some(original.grayscale()),
+            RevealMode::Inverted => {
+                let mut img = original.clone();
+                img.invert();
+                Some(img)
+            }
+            RevealMode::Mirrored => Some(original.fliph()),
+            _ => None,
+        };
+        let mut tiles: Vec<_> = (0..TILES_X)
+            .flat_map(|x| (0..TILES_Y).map(move |y| (x, y)))
+            .collect();
+        tiles.shuffle(&mut rng);
         Self {
             original,
+            distorted,
+            mode,
+            level: 0,
             x,
             y,
             radius,
+            tiles,
         }
     }
 
-    pub fn increase_radius(&mut self) {
+    pub fn mode(&self) -> RevealMode {
+        self.mode
+    }
+
+    /// Reveal more of the image
+    pub fn enhance(&mut self) {
+        self.level += 1;
         self.radius += 75;
     }
 
     pub fn sub_image(&self) -> Result<Vec<u8>, Error> {
-        let cx = self.x.saturating_sub(self.radius);
-        let cy = self.y.saturating_sub(self.radius);
-        let (w, h) = self.original.dimensions();
-        let w = (self.x + self.radius).min(w) - cx;
-        let h = (self.y + self.radius).min(h) - cy;
-        let sub_image = self.original.view(cx, cy, w, h).to_image().into_vec();
-        let mut png_bytes: Vec<u8> = Vec::with_capacity((w * h) as usize);
-        let png_encoder = PNGEncoder::new(&mut png_bytes);
-        png_encoder.encode(&sub_image, w, h, ColorType::Rgba8)?;
-        Ok(png_bytes)
+        match self.mode {
+            RevealMode::Pixelate => encode(&self.pixelated()),
+            RevealMode::Blur => {
+                let sigma = BLUR_SIGMA - 3.0 * self.level as f32;
+                if sigma <= 0.0 {
+                    self.full()
+                } else {
+                    encode(&self.original.blur(sigma))
+                }
+            }
+            RevealMode::Tiles => encode(&self.tiled()),
+            _ => {
+                let img = self.distorted.as_ref().unwrap_or(&self.original);
+                let cx = self.x.saturating_sub(self.radius);
+                let cy = self.y.saturating_sub(self.radius);
+                let (w, h) = img.dimensions();
+                let w = (self.x + self.radius).min(w) - cx;
+                let h = (self.y + self.radius).min(h) - cy;
+                let sub_image = img.view(cx, cy, w, h).to_image();
+                encode(&DynamicImage::ImageRgba8(sub_image))
+            }
+        }
     }
 
     pub fn full(&self) -> Result<Vec<u8>, Error> {
+        encode(&self.original)
+    }
+
+    fn pixelated(&self) -> DynamicImage {
+        let (w, h) = self.original.dimensions();
+        let size = (PIXEL_SIZE * 0.6_f32.powi(self.level as i32)).max(1.0) as u32;
+        if size == 1 {
+            return self.original.clone();
+        }
+        let small_w = (w / size).max(1);
+        let small_h = (h / size).max(1);
+        self.original
+            .resize_exact(small_w, small_h, FilterType::Triangle)
+            .resize_exact(w, h, FilterType::Nearest)
+    }
+
+    fn tiled(&self) -> DynamicImage {
         let (w, h) = self.original.dimensions();
-        let mut png_bytes: Vec<u8> = Vec::with_capacity((w * h) as usize);
-        let png_encoder = PNGEncoder::new(&mut png_bytes);
-        // doesn't work without next line for some reason
-        let img = self.original.view(0, 0, w, h).to_image().into_vec();
-        png_encoder.encode(&img, w, h, ColorType::Rgba8)?;
-        Ok(png_bytes)
+        let tile_w = (w + TILES_X - 1) / TILES_X;
+        let tile_h = (h + TILES_Y - 1) / TILES_Y;
+        let mut img = RgbaImage::new(w, h);
+        let amount = TILES_PER_LEVEL * (self.level as usize + 1);
+        for &(tx, ty) in self.tiles.iter().take(amount) {
+            let x = tx * tile_w;
+            let y = ty * tile_h;
+            if x >= w || y >= h {
+                continue;
+            }
+            let tile = self
+                .original
+                .view(x, y, tile_w.min(w - x), tile_h.min(h - y));
+            imageops::replace(&mut img, &tile, x, y);
+        }
+        DynamicImage::ImageRgba8(img)
     }
 }
 
+fn encode(img: &DynamicImage) -> Result<Vec<u8>, Error> {
+    let (w, h) = img.dimensions();
+    let mut png_bytes: Vec<u8> = Vec::with_capacity((w * h) as usize);
+    let png_encoder = PNGEncoder::new(&mut png_bytes);
+    // doesn't work without converting into rgba first for some reason
+    let img = img.view(0, 0, w, h).to_image().into_vec();
+    png_encoder.encode(&img, w, h, ColorType::Rgba8)?;
+    Ok(png_bytes)
+}
+
 impl Default for ImageReveal {
     fn default() -> Self {
         let original = DynamicImage::new_rgba8(1, 1);
         Self {
             original,
+            distorted: None,
+            mode: RevealMode::Circle,
+            level: 0,
             x: 0,
             y: 0,
             radius: 0,
+            tiles: Vec::new(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_reveal_modes() {
+        assert_eq!("blur".parse(), Ok(RevealMode::Blur));
+        assert_eq!("P".parse(), Ok(RevealMode::Pixelate));
+        assert_eq!("mirror".parse(), Ok(RevealMode::Mirrored));
+        assert!("sepia".parse::<RevealMode>().is_err());
+    }
+
+    #[test]
+    fn random_mode_gets_resolved() {
+        for _ in 0..20 {
+            assert_ne!(RevealMode::Random.pick(), RevealMode::Random);
+        }
+        assert_eq!(RevealMode::Tiles.pick(), RevealMode::Tiles);
+    }
+
+    #[test]
+    fn every_mode_renders() {
+        let original = DynamicImage::new_rgba8(400, 300);
+        let modes = [
+            RevealMode::Circle,
+            RevealMode::Pixelate,
+            RevealMode::Blur,
+            RevealMode::Tiles,
+            RevealMode::Grayscale,
+            RevealMode::Inverted,
+            RevealMode::Mirrored,
+        ];
+        for &mode in modes.iter() {
+            let mut reveal = ImageReveal::new(original.clone(), mode);
+            for _ in 0..8 {
+                assert!(reveal.sub_image().is_ok(), "{} failed", mode);
+                reveal.enhance();
+            }
         }
     }
 }
@@ -1,39 +1,58 @@
+mod config;
+mod difficulty;
 mod game;
 mod hints;
 mod img_reveal;
-mod util;
+mod session;
+mod tag_votes;
+pub(super) mod util;
 
+pub use config::BgConfig;
+use config::ConfigSetting;
+use difficulty::Difficulty;
 pub use game::BackGroundGame;
 use hints::Hints;
-use img_reveal::ImageReveal;
+use img_reveal::{ImageReveal, RevealMode};
+use session::Session;
+pub use session::SessionScore;
+use tag_votes::TagVoting;
 
 use crate::{
-    commands::utility::MapsetTags,
+    commands::{checks::*, utility::MapsetTags},
     database::MapsetTagWrapper,
-    embeds::{BGHelpEmbed, BGRankingEmbed, BGStartEmbed, BGTagsEmbed, EmbedData},
+    embeds::{
+        BGConfigEmbed, BGHelpEmbed, BGRankingEmbed, BGRankingView, BGSeasonWinnersEmbed,
+        BGSessionsEmbed, BGStartEmbed, BGTagsEmbed, EmbedData,
+    },
     pagination::{BGRankingPagination, Pagination},
-    util::{numbers, MessageExt},
-    BgGames, MySQL,
+    util::{datetime, numbers, MessageExt},
+    BgGames, MySQL, SongGames,
 };
 
+use chrono::Utc;
 use failure::Error;
 use rosu::models::GameMode;
 use serenity::{
     framework::standard::{macros::command, Args, CommandResult},
     model::{
         channel::{Message, ReactionType},
-        id::UserId,
+        id::{ChannelId, UserId},
     },
     prelude::Context,
 };
 use std::{collections::HashMap, convert::TryFrom, sync::Arc, time::Duration};
 use tokio::{stream::StreamExt, time};
 
+const MAX_ROUNDS: usize = 50;
+const SEASON_WINNERS_AMOUNT: usize = 12;
+
 #[command]
 #[description = "Given part of a map's background, try to guess \
 the **title** of the map's song.\nCheck `<bg` for more help"]
 #[aliases("bg")]
-#[sub_commands("start", "hint", "bigger", "stop", "stats", "ranking")]
+#[sub_commands(
+    "start", "hint", "bigger", "stop", "stats", "ranking", "seasons", "sessions", "config"
+)]
 async fn backgroundgame(ctx: &Context, msg: &Message, args: Args) -> CommandResult {
     let response = if args.is_empty() {
         let data = BGHelpEmbed::new();
@@ -56,17 +75,52 @@ async fn backgroundgame(ctx: &Context, msg: &Message, args: Args) -> CommandResu
 #[aliases("s", "skip", "resolve", "r")]
 #[bucket = "bg_start"]
 #[sub_commands("mania")]
-async fn start(ctx: &Context, msg: &Message) -> CommandResult {
+async fn start(ctx: &Context, msg: &Message, mut args: Args) -> CommandResult {
     let channel = msg.channel_id;
     // Check if channel already has a running game
     {
         let mut data = ctx.data.write().await;
+        if data.get::<SongGames>().unwrap().contains_key(&channel) {
+            drop(data);
+            msg.channel_id
+                .say(
+                    ctx,
+                    "There is already a song game running in this channel, \
+                    stop it first with `<sg stop`",
+                )
+                .await?;
+            return Ok(());
+        }
         let games = data.get_mut::<BgGames>().unwrap();
         if games.contains_key(&channel) {
             games.get_mut(&channel).unwrap().restart()?;
             return Ok(());
         }
     }
+    let config = channel_config(ctx, channel).await;
+    let options = match StartOptions::parse(&mut args, config.as_ref()) {
+        Ok(options) => options,
+        Err(content) => {
+            msg.channel_id
+                .say(ctx, content)
+                .await?
+                .reaction_delete(ctx, msg.author.id)
+                .await;
+            return Ok(());
+        }
+    };
+    // A stored config replaces picking the tags
+    if let Some(config) = config {
+        return start_with_tags(
+            ctx,
+            msg,
+            config.mode,
+            config.included,
+            config.excluded,
+            options,
+        )
+        .await;
+    }
     // Send initial message
     let embed_data = BGStartEmbed::new(msg.author.id);
     let response = channel
@@ -78,24 +132,8 @@ async fn start(ctx: &Context, msg: &Message) -> CommandResult {
         .author_id(msg.author.id)
         .removed(true)
         .await;
-    let reactions = [
-        "🍋",
-        "🤓",
-        "🤡",
-        "🎨",
-        "🍨",
-        "👨‍🌾",
-        "😱",
-        "🪀",
-        "🟦",
-        "🗽",
-        "🌀",
-        "👴",
-        "💯",
-        "✅",
-        "❌",
-    ];
-    for &reaction in reactions.iter() {
+    let reactions = MapsetTags::EMOJIS.iter().chain(["✅", "❌"].iter());
+    for &reaction in reactions {
         let reaction = ReactionType::try_from(reaction).unwrap();
         response.react(ctx, reaction).await?;
     }
@@ -105,25 +143,15 @@ async fn start(ctx: &Context, msg: &Message) -> CommandResult {
     while let Some(reaction) = collector.next().await {
         let tag = if let ReactionType::Unicode(ref r) = reaction.as_inner_ref().emoji {
             match r.as_str() {
-                "🍋" => MapsetTags::Easy,
-                "🤓" => MapsetTags::Hard,
-                "🤡" => MapsetTags::Meme,
-                "👴" => MapsetTags::Old,
-                "😱" => MapsetTags::HardName,
-                "🟦" => MapsetTags::BlueSky,
-                "🪀" => MapsetTags::Alternate,
-                "🗽" => MapsetTags::English,
-                "👨‍🌾" => MapsetTags::Farm,
-                "💯" => MapsetTags::Tech,
-                "🎨" => MapsetTags::Weeb,
-                "🌀" => MapsetTags::Streams,
-                "🍨" => MapsetTags::Kpop,
                 "✅" if reaction.as_inner_ref().user_id == msg.author.id => break,
                 "❌" if reaction.as_inner_ref().user_id == msg.author.id => {
                     msg.reply(ctx, "Game cancelled").await?;
                     return Ok(());
                 }
-                _ => continue,
+                emoji => match MapsetTags::from_emoji(emoji) {
+                    Some(tag) => tag,
+                    None => continue,
+                },
             }
         } else {
             continue;
@@ -137,12 +165,24 @@ async fn start(ctx: &Context, msg: &Message) -> CommandResult {
         }
     }
     collector.stop();
+    start_with_tags(ctx, msg, GameMode::STD, included, excluded, options).await
+}
+
+async fn start_with_tags(
+    ctx: &Context,
+    msg: &Message,
+    mode: GameMode,
+    included: MapsetTags,
+    excluded: MapsetTags,
+    options: StartOptions,
+) -> CommandResult {
+    let channel = msg.channel_id;
     // Get all mapsets matching the given tags
     let mapsets = {
         let data = ctx.data.read().await;
         let mysql = data.get::<MySQL>().unwrap();
         match mysql
-            .get_specific_tags_mapset(GameMode::STD, included, excluded)
+            .get_specific_tags_mapset(mode, included, excluded)
             .await
         {
             Ok(mapsets) => mapsets,
@@ -168,20 +208,47 @@ async fn start(ctx: &Context, msg: &Message) -> CommandResult {
         included.join(','),
         excluded.join(',')
     );
-    _start(ctx, msg, mapsets).await
+    _start(ctx, msg, mapsets, options).await
 }
 
 #[command]
 #[aliases("m")]
-async fn mania(ctx: &Context, msg: &Message) -> CommandResult {
+async fn mania(ctx: &Context, msg: &Message, mut args: Args) -> CommandResult {
     {
         let mut data = ctx.data.write().await;
+        if data
+            .get::<SongGames>()
+            .unwrap()
+            .contains_key(&msg.channel_id)
+        {
+            drop(data);
+            msg.channel_id
+                .say(
+                    ctx,
+                    "There is already a song game running in this channel, \
+                    stop it first with `<sg stop`",
+                )
+                .await?;
+            return Ok(());
+        }
         let games = data.get_mut::<BgGames>().unwrap();
         if games.contains_key(&msg.channel_id) {
             games.get_mut(&msg.channel_id).unwrap().restart()?;
             return Ok(());
         }
     }
+    let config = channel_config(ctx, msg.channel_id).await;
+    let options = match StartOptions::parse(&mut args, config.as_ref()) {
+        Ok(options) => options,
+        Err(content) => {
+            msg.channel_id
+                .say(ctx, content)
+                .await?
+                .reaction_delete(ctx, msg.author.id)
+                .await;
+            return Ok(());
+        }
+    };
     let mapsets = {
         let data = ctx.data.read().await;
         let mysql = data.get::<MySQL>().unwrap();
@@ -197,11 +264,86 @@ async fn mania(ctx: &Context, msg: &Message) -> CommandResult {
             }
         }
     };
-    _start(ctx, msg, mapsets).await
+    _start(ctx, msg, mapsets, options).await
+}
+
+// Config of the channel, if one is stored
+async fn channel_config(ctx: &Context, channel: ChannelId) -> Option<BgConfig> {
+    let data = ctx.data.read().await;
+    let mysql = data.get::<MySQL>().unwrap();
+    match mysql.get_bg_config(channel.0).await {
+        Ok(config) => config,
+        Err(why) => {
+            warn!(
+                "Error while getting bg config of channel {}: {}",
+                channel, why
+            );
+            None
+        }
+    }
+}
+
+/// Settings of a game that can be given when starting it
+pub struct StartOptions {
+    pub rounds: Option<usize>,
+    pub reveal: RevealMode,
+    pub difficulty: Option<Difficulty>,
+    /// Seconds between two hints
+    pub hint_cooldown: u32,
+    /// Minutes without a correct guess until the game stops
+    pub auto_stop: u32,
+}
+
+impl StartOptions {
+    // Amount of rounds via `--rounds` or `-r`, reveal mode via `--reveal`,
+    // and difficulty via `--difficulty` or `-d`.
+    // Anything not given is taken from the channel's config.
+    fn parse(args: &mut Args, config: Option<&BgConfig>) -> Result<Self, &'static str> {
+        let config = config.cloned().unwrap_or_default();
+        let mut options = Self {
+            rounds: None,
+            reveal: config.reveal,
+            difficulty: None,
+            hint_cooldown: config.hint_cooldown,
+            auto_stop: config.auto_stop,
+        };
+        while !args.is_empty() {
+            let arg = args.single::<String>().unwrap();
+            if arg == "--rounds" || arg == "-r" {
+                let amount = args
+                    .single::<usize>()
+                    .map_err(|_| "After `--rounds` you need to specify a number of rounds")?;
+                if amount == 0 || amount > MAX_ROUNDS {
+                    return Err("The amount of rounds must be between 1 and 50");
+                }
+                options.rounds = Some(amount);
+            } else if arg == "--reveal" {
+                let mode = args
+                    .single::<String>()
+                    .ok()
+                    .and_then(|mode| mode.parse().ok());
+                options.reveal = mode.ok_or(
+                    "After `--reveal` you need to specify one of `circle`, `pixel`, \
+                    `blur`, `tiles`, `gray`, `invert`, `mirror`, or `random`",
+                )?;
+            } else if arg == "--difficulty" || arg == "-d" {
+                let difficulty = args.single::<String>().ok().and_then(|d| d.parse().ok());
+                options.difficulty = Some(difficulty.ok_or(
+                    "After `--difficulty` you need to specify `easy`, `medium`, or `hard`",
+                )?);
+            }
+        }
+        Ok(options)
+    }
 }
 
 #[allow(clippy::map_entry)]
-async fn _start(ctx: &Context, msg: &Message, mapsets: Vec<MapsetTagWrapper>) -> CommandResult {
+async fn _start(
+    ctx: &Context,
+    msg: &Message,
+    mapsets: Vec<MapsetTagWrapper>,
+    options: StartOptions,
+) -> CommandResult {
     let channel = msg.channel_id;
     let mut data = ctx.data.write().await;
     let games = data.get_mut::<BgGames>().unwrap();
@@ -209,7 +351,8 @@ async fn _start(ctx: &Context, msg: &Message, mapsets: Vec<MapsetTagWrapper>) ->
         games.get_mut(&channel).unwrap().restart()?;
     } else {
         let game = BackGroundGame::new().await;
-        game.start(ctx, channel, mapsets).await;
+        game.start(ctx, channel, msg.guild_id, mapsets, options)
+            .await;
         games.insert(channel, game);
     }
     Ok(())
@@ -318,15 +461,26 @@ async fn stop(ctx: &Context, msg: &Message) -> CommandResult {
 
 #[command]
 async fn stats(ctx: &Context, msg: &Message) -> CommandResult {
-    let score = {
+    let (score, season_score) = {
         let data = ctx.data.read().await;
         let mysql = data.get::<MySQL>().unwrap();
-        mysql.get_bggame_score(msg.author.id.0).await.ok()
+        let user = msg.author.id.0;
+        let season = datetime::season(&Utc::now());
+        (
+            mysql.get_bggame_score(user, None).await.ok(),
+            mysql
+                .get_bggame_score(user, Some(season))
+                .await
+                .unwrap_or(0),
+        )
     };
     let response = if let Some(score) = score {
         msg.reply(
             (&ctx.cache, &*ctx.http),
-            format!("You've guessed {} backgrounds correctly!", score),
+            format!(
+                "You've guessed {} backgrounds correctly, {} of them this season!",
+                score, season_score
+            ),
         )
         .await?
     } else {
@@ -341,42 +495,126 @@ async fn stats(ctx: &Context, msg: &Message) -> CommandResult {
 }
 
 #[command]
-#[aliases("leaderboard", "lb")]
-async fn ranking(ctx: &Context, msg: &Message, mut args: Args) -> CommandResult {
-    let global = msg.guild_id.is_none()
-        || args
-            .single::<String>()
-            .map(|arg| ["g", "global"].contains(&arg.as_str()))
-            .unwrap_or_else(|_| false);
-    let mut scores = {
+#[aliases("history")]
+async fn sessions(ctx: &Context, msg: &Message) -> CommandResult {
+    let sessions = {
         let data = ctx.data.read().await;
         let mysql = data.get::<MySQL>().unwrap();
-        mysql.all_bggame_scores().await?
+        match mysql.get_bg_sessions(msg.author.id.0, 10).await {
+            Ok(sessions) => sessions,
+            Err(why) => {
+                msg.channel_id
+                    .say(ctx, "Some database issue, blame bade")
+                    .await?
+                    .reaction_delete(ctx, msg.author.id)
+                    .await;
+                return Err(why.to_string().into());
+            }
+        }
     };
-    if !global && msg.guild_id.is_some() {
-        let guild_id = msg.guild_id.unwrap();
-        let member_ids = ctx
-            .cache
-            .guild_field(guild_id, |guild| {
-                guild.members.keys().map(|id| id.0).collect::<Vec<_>>()
-            })
+    let response = if sessions.is_empty() {
+        msg.reply(
+            (&ctx.cache, &*ctx.http),
+            "Looks like you haven't scored in any game with rounds yet, \
+            start one with `<bg s --rounds 10`",
+        )
+        .await?
+    } else {
+        let data = BGSessionsEmbed::new(&msg.author.name, &sessions);
+        msg.channel_id
+            .send_message(ctx, |m| m.embed(|e| data.build(e)))
+            .await?
+    };
+    response.reaction_delete(ctx, msg.author.id).await;
+    Ok(())
+}
+
+#[command]
+#[aliases("winners")]
+async fn seasons(ctx: &Context, msg: &Message, args: Args) -> CommandResult {
+    let global = msg.guild_id.is_none() || args.raw().any(|arg| arg == "g" || arg == "global");
+    let guild = if global {
+        None
+    } else {
+        msg.guild_id.map(|guild| guild.0)
+    };
+    let winners = {
+        let data = ctx.data.read().await;
+        let mysql = data.get::<MySQL>().unwrap();
+        mysql.get_bg_season_winners(guild).await?
+    };
+    if winners.is_empty() {
+        msg.channel_id
+            .say(ctx, "No season has been concluded yet")
+            .await?
+            .reaction_delete(ctx, msg.author.id)
             .await;
-        if let Some(members) = member_ids {
-            scores.retain(|(user, _)| members.iter().any(|member| member == user));
+        return Ok(());
+    }
+    let mut names = HashMap::with_capacity(winners.len());
+    for &(_, id, _) in winners.iter().take(SEASON_WINNERS_AMOUNT) {
+        if !names.contains_key(&id) {
+            let name = match UserId(id).to_user(ctx).await {
+                Ok(user) => user.name,
+                Err(_) => String::from("Unknown user"),
+            };
+            names.insert(id, name);
         }
+    }
+    let winners: Vec<_> = winners
+        .into_iter()
+        .take(SEASON_WINNERS_AMOUNT)
+        .map(|(season, id, score)| (season, names.get(&id).unwrap().as_str(), score))
+        .collect();
+    let data = BGSeasonWinnersEmbed::new(&winners, global);
+    msg.channel_id
+        .send_message(ctx, |m| m.embed(|e| data.build(e)))
+        .await?
+        .reaction_delete(ctx, msg.author.id)
+        .await;
+    Ok(())
+}
 
-        if scores.is_empty() {
-            msg.channel_id
-                .say(
-                    ctx,
-                    "Looks like no one on this server has played the backgroundgame yet",
-                )
-                .await?
-                .reaction_delete(ctx, msg.author.id)
-                .await;
-            return Ok(());
+#[command]
+#[aliases("leaderboard", "lb")]
+async fn ranking(ctx: &Context, msg: &Message, args: Args) -> CommandResult {
+    let mut view = BGRankingView {
+        global: msg.guild_id.is_none(),
+        season: None,
+    };
+    for arg in args.raw() {
+        match arg {
+            "g" | "global" => view.global = true,
+            "s" | "season" => view.season = Some(datetime::season(&Utc::now())),
+            _ => {}
         }
     }
+    let mut scores = {
+        let guild = if view.global {
+            None
+        } else {
+            msg.guild_id.map(|guild| guild.0)
+        };
+        let data = ctx.data.read().await;
+        let mysql = data.get::<MySQL>().unwrap();
+        mysql.get_bggame_scores(guild, view.season).await?
+    };
+    if scores.is_empty() {
+        let content = match (view.global, view.season.is_some()) {
+            (false, false) => "Looks like no one on this server has played the backgroundgame yet",
+            (false, true) => {
+                "Looks like no one on this server has played the backgroundgame this season"
+            }
+            (true, true) => "Looks like no one has played the backgroundgame this season",
+            (true, false) => "Looks like no one has played the backgroundgame yet",
+        };
+        msg.channel_id
+            .say(ctx, content)
+            .await?
+            .reaction_delete(ctx, msg.author.id)
+            .await;
+        return Ok(());
+    }
     scores.sort_by(|(_, a), (_, b)| b.cmp(&a));
     let author_idx = scores.iter().position(|(user, _)| *user == msg.author.id.0);
 
@@ -398,7 +636,7 @@ async fn ranking(ctx: &Context, msg: &Message, mut args: Args) -> CommandResult
 
     // Prepare initial page
     let pages = numbers::div_euclid(15, scores.len());
-    let data = BGRankingEmbed::new(author_idx, initial_scores, global, 1, (1, pages));
+    let data = BGRankingEmbed::new(author_idx, initial_scores, view, 1, (1, pages));
 
     // Creating the embed
     let resp = msg
@@ -414,7 +652,7 @@ async fn ranking(ctx: &Context, msg: &Message, mut args: Args) -> CommandResult
 
     // Pagination
     let pagination =
-        BGRankingPagination::new(ctx, resp, msg.author.id, author_idx, scores, global).await;
+        BGRankingPagination::new(ctx, resp, msg.author.id, author_idx, scores, view).await;
     let cache = Arc::clone(&ctx.cache);
     let http = Arc::clone(&ctx.http);
     tokio::spawn(async move {
@@ -424,3 +662,108 @@ async fn ranking(ctx: &Context, msg: &Message, mut args: Args) -> CommandResult
     });
     Ok(())
 }
+
+#[command]
+#[only_in("guild")]
+#[checks(Authority)]
+#[description = "Store the game settings of this channel so that `<bg s` \
+uses them directly instead of asking for tags.\n\
+Without arguments, the current settings are shown.\n\
+Settings: `mode` (std / mania), `include` & `exclude` (list of tags or `none`), \
+`reveal` (see `<bg`), `hintcooldown` (seconds), `autostop` (minutes).\n\
+Use `reset` to remove the settings of this channel."]
+#[usage = "[setting] [value] / reset"]
+#[example = "include easy weeb"]
+#[aliases("settings")]
+async fn config(ctx: &Context, msg: &Message, mut args: Args) -> CommandResult {
+    let channel = msg.channel_id;
+    let stored = {
+        let data = ctx.data.read().await;
+        let mysql = data.get::<MySQL>().unwrap();
+        match mysql.get_bg_config(channel.0).await {
+            Ok(config) => config,
+            Err(why) => {
+                channel
+                    .say(ctx, "Some database issue, blame bade")
+                    .await?
+                    .reaction_delete(ctx, msg.author.id)
+                    .await;
+                return Err(why.to_string().into());
+            }
+        }
+    };
+    if args.is_empty() {
+        let embed_data = match stored {
+            Some(ref config) => BGConfigEmbed::new(config, true),
+            None => BGConfigEmbed::new(&BgConfig::default(), false),
+        };
+        channel
+            .send_message(ctx, |m| m.embed(|e| embed_data.build(e)))
+            .await?
+            .reaction_delete(ctx, msg.author.id)
+            .await;
+        return Ok(());
+    }
+    let arg = args.single::<String>().unwrap().to_lowercase();
+    if arg == "reset" {
+        let data = ctx.data.read().await;
+        let mysql = data.get::<MySQL>().unwrap();
+        if let Err(why) = mysql.remove_bg_config(channel.0).await {
+            channel
+                .say(ctx, "Some database issue, blame bade")
+                .await?
+                .reaction_delete(ctx, msg.author.id)
+                .await;
+            return Err(why.to_string().into());
+        }
+        channel
+            .say(
+                ctx,
+                "Removed the settings of this channel, \
+                `<bg s` will ask for tags again",
+            )
+            .await?;
+        return Ok(());
+    }
+    let setting = match arg.parse::<ConfigSetting>() {
+        Ok(setting) => setting,
+        Err(_) => {
+            channel
+                .say(
+                    ctx,
+                    "Could not parse setting. Be sure to specify one of `mode`, `include`, \
+                    `exclude`, `reveal`, `hintcooldown`, `autostop`, or `reset`",
+                )
+                .await?
+                .reaction_delete(ctx, msg.author.id)
+                .await;
+            return Ok(());
+        }
+    };
+    let mut config = stored.unwrap_or_default();
+    if let Err(content) = config.update(setting, args.rest().trim()) {
+        channel
+            .say(ctx, content)
+            .await?
+            .reaction_delete(ctx, msg.author.id)
+            .await;
+        return Ok(());
+    }
+    {
+        let data = ctx.data.read().await;
+        let mysql = data.get::<MySQL>().unwrap();
+        if let Err(why) = mysql.set_bg_config(channel.0, &config).await {
+            channel
+                .say(ctx, "Some database issue, blame bade")
+                .await?
+                .reaction_delete(ctx, msg.author.id)
+                .await;
+            return Err(why.to_string().into());
+        }
+    }
+    let embed_data = BGConfigEmbed::new(&config, true);
+    channel
+        .send_message(ctx, |m| m.embed(|e| embed_data.build(e)))
+        .await?;
+    Ok(())
+}
@@ -0,0 +1,114 @@
+use std::{collections::HashMap, time::Duration};
+
+const MAX_POINTS: f32 = 100.0;
+// Guesses after this many seconds receive the minimal speed factor
+const SPEED_WINDOW: f32 = 120.0;
+const MIN_SPEED_FACTOR: f32 = 0.25;
+const HINT_PENALTY: f32 = 0.15;
+const BIGGER_PENALTY: f32 = 0.1;
+const MIN_REVEAL_FACTOR: f32 = 0.2;
+
+/// Points for a correct guess, depending on how long it took
+/// and how many hints and image enlargements were used
+pub fn round_points(elapsed: Duration, hints: usize, bigger: usize) -> u32 {
+    let speed = (1.0 - elapsed.as_secs_f32() / SPEED_WINDOW).max(MIN_SPEED_FACTOR);
+    let reveal =
+        (1.0 - HINT_PENALTY * hints as f32 - BIGGER_PENALTY * bigger as f32).max(MIN_REVEAL_FACTOR);
+    (MAX_POINTS * speed * reveal).round() as u32
+}
+
+#[derive(Clone)]
+pub struct SessionScore {
+    pub user_id: u64,
+    pub name: String,
+    pub points: u32,
+    pub guesses: u32,
+}
+
+/// Game with a fixed amount of rounds in which players collect points
+pub struct Session {
+    pub rounds: usize,
+    pub round: usize,
+    scores: HashMap<u64, SessionScore>,
+}
+
+impl Session {
+    pub fn new(rounds: usize) -> Self {
+        Self {
+            rounds,
+            round: 0,
+            scores: HashMap::new(),
+        }
+    }
+
+    pub fn add_points(&mut self, user_id: u64, name: String, points: u32) {
+        let score = self.scores.entry(user_id).or_insert_with(|| SessionScore {
+            user_id,
+            name: String::new(),
+            points: 0,
+            guesses: 0,
+        });
+        score.name = name;
+        score.points += points;
+        score.guesses += 1;
+    }
+
+    pub fn finish_round(&mut self) {
+        self.round += 1;
+    }
+
+    pub fn finished(&self) -> bool {
+        self.round >= self.rounds
+    }
+
+    /// Scores sorted by points, ties broken by guesses
+    pub fn standings(&self) -> Vec<SessionScore> {
+        let mut scores: Vec<_> = self.scores.values().cloned().collect();
+        scores.sort_by(|a, b| {
+            b.points
+                .cmp(&a.points)
+                .then_with(|| b.guesses.cmp(&a.guesses))
+                .then_with(|| a.name.cmp(&b.name))
+        });
+        scores
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fast_guess_without_help_gives_full_points() {
+        assert_eq!(round_points(Duration::from_secs(0), 0, 0), 100);
+        assert_eq!(round_points(Duration::from_secs(60), 0, 0), 50);
+    }
+
+    #[test]
+    fn points_never_drop_below_minimum() {
+        let points = round_points(Duration::from_secs(600), 20, 20);
+        assert_eq!(points, 5);
+    }
+
+    #[test]
+    fn hints_and_bigger_reduce_points() {
+        let plain = round_points(Duration::from_secs(10), 0, 0);
+        let hinted = round_points(Duration::from_secs(10), 1, 0);
+        let bigger = round_points(Duration::from_secs(10), 0, 1);
+        assert!(hinted < bigger && bigger < plain);
+    }
+
+    #[test]
+    fn standings_are_sorted() {
+        let mut session = Session::new(3);
+        session.add_points(1, "a".to_owned(), 40);
+        session.add_points(2, "b".to_owned(), 80);
+        session.add_points(1, "a".to_owned(), 30);
+        session.finish_round();
+        let standings = session.standings();
+        assert_eq!(standings[0].user_id, 2);
+        assert_eq!(standings[1].points, 70);
+        assert_eq!(standings[1].guesses, 2);
+        assert!(!session.finished());
+    }
+}
@@ -0,0 +1,123 @@
+use crate::{commands::utility::MapsetTags, util::globals::HOMEPAGE, BgVerified, MySQL};
+
+use failure::Error;
+use serenity::{
+    model::{
+        channel::{Message, ReactionType},
+        id::UserId,
+    },
+    prelude::Context,
+};
+use std::{convert::TryFrom, env, time::Duration};
+use tokio::stream::StreamExt;
+
+/// Votes required for a tag to become active, unless set otherwise in the env
+const DEFAULT_THRESHOLD: u32 = 3;
+const VOTING_SECS: u64 = 120;
+
+/// Lets players vote on the tags of a resolved mapset through reactions
+#[derive(Clone)]
+pub struct TagVoting {
+    ctx: Context,
+    bot_id: UserId,
+    threshold: u32,
+}
+
+impl TagVoting {
+    pub async fn new(ctx: &Context) -> Self {
+        let threshold = env::var("BG_TAG_VOTE_THRESHOLD")
+            .ok()
+            .and_then(|threshold| threshold.parse().ok())
+            .filter(|&threshold| threshold > 0)
+            .unwrap_or(DEFAULT_THRESHOLD);
+        Self {
+            ctx: ctx.clone(),
+            bot_id: ctx.cache.current_user_id().await,
+            threshold,
+        }
+    }
+
+    /// Collect votes on the given message in the background
+    pub fn start(&self, msg: Message, mapset_id: u32) {
+        let voting = self.clone();
+        tokio::spawn(async move {
+            if let Err(why) = voting.run(msg, mapset_id).await {
+                warn!("Error during bg tag voting of {}: {}", mapset_id, why);
+            }
+        });
+    }
+
+    async fn run(self, msg: Message, mapset_id: u32) -> Result<(), Error> {
+        let ctx = &self.ctx;
+        let bot_id = self.bot_id;
+        let mut collector = msg
+            .await_reactions(ctx)
+            .timeout(Duration::from_secs(VOTING_SECS))
+            .filter(move |reaction| reaction.user_id != bot_id)
+            .removed(true)
+            .await;
+        for &reaction in MapsetTags::EMOJIS.iter() {
+            let reaction = ReactionType::try_from(reaction).unwrap();
+            msg.react(ctx, reaction).await?;
+        }
+        while let Some(reaction) = collector.next().await {
+            let tag = match reaction.as_inner_ref().emoji {
+                ReactionType::Unicode(ref emoji) => match MapsetTags::from_emoji(emoji) {
+                    Some(tag) => tag,
+                    None => continue,
+                },
+                _ => continue,
+            };
+            let user = reaction.as_inner_ref().user_id;
+            let activated = if reaction.is_added() {
+                self.add_vote(mapset_id, user, tag).await
+            } else {
+                self.remove_vote(mapset_id, user, tag).await.map(|_| false)
+            };
+            match activated {
+                Ok(true) => {
+                    let content = format!(
+                        "{}beatmapsets/{} is now tagged as `{:?}`, thanks for voting",
+                        HOMEPAGE, mapset_id, tag
+                    );
+                    let _ = msg.channel_id.say(ctx, content).await;
+                }
+                Ok(false) => {}
+                Err(why) => warn!("Error while processing bg tag vote: {}", why),
+            }
+        }
+        Ok(())
+    }
+
+    // Returns whether the vote activated the tag
+    async fn add_vote(&self, mapset_id: u32, user: UserId, tag: MapsetTags) -> Result<bool, Error> {
+        let data = self.ctx.data.read().await;
+        let mysql = data.get::<MySQL>().unwrap();
+        let votes = mysql.add_bg_tag_vote(mapset_id, user.0, tag).await?;
+        let locks = mysql.get_bg_tag_locks(mapset_id).await?;
+        if locks.contains(tag) {
+            return Ok(false);
+        }
+        let current = mysql.get_tags_mapset(mapset_id).await?;
+        if current.has_tags(tag) {
+            return Ok(false);
+        }
+        let verified = data.get::<BgVerified>().unwrap().contains(&user);
+        if !verified && votes < self.threshold {
+            return Ok(false);
+        }
+        mysql.set_tags_mapset(mapset_id, tag, true).await?;
+        Ok(true)
+    }
+
+    async fn remove_vote(
+        &self,
+        mapset_id: u32,
+        user: UserId,
+        tag: MapsetTags,
+    ) -> Result<(), Error> {
+        let data = self.ctx.data.read().await;
+        let mysql = data.get::<MySQL>().unwrap();
+        mysql.remove_bg_tag_vote(mapset_id, user.0, tag).await
+    }
+}
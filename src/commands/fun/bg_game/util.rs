@@ -1,19 +1,30 @@
-use crate::{database::MapsetTagWrapper, MySQL, Osu};
+use crate::{
+    database::{AliasKind, BgAlias, MapsetTagWrapper},
+    MySQL, Osu, Scraper,
+};
 
 use failure::Error;
-use rand::RngCore;
+use rand::{
+    distributions::{Distribution, WeightedIndex},
+    RngCore,
+};
 use rosu::backend::BeatmapRequest;
 use serenity::prelude::{RwLock, TypeMap};
-use std::collections::VecDeque;
+use std::{collections::VecDeque, iter};
 
+/// Without weights, every mapset is equally likely
 pub async fn get_random_mapset<'m>(
     mapsets: &'m [MapsetTagWrapper],
+    weights: Option<&WeightedIndex<f64>>,
     previous_ids: &mut VecDeque<u32>,
 ) -> Result<&'m MapsetTagWrapper, Error> {
     let mut rng = rand::thread_rng();
     let buffer_size = mapsets.len() / 2;
     loop {
-        let random_index = rng.next_u32() as usize % mapsets.len();
+        let random_index = match weights {
+            Some(weights) => weights.sample(&mut rng),
+            None => rng.next_u32() as usize % mapsets.len(),
+        };
         let mapset = &mapsets[random_index];
         if !previous_ids.contains(&mapset.mapset_id) {
             previous_ids.push_front(mapset.mapset_id);
@@ -29,7 +40,7 @@ pub async fn get_title_artist(
     mapset_id: u32,
     data: &RwLock<TypeMap>,
 ) -> Result<(String, String), Error> {
-    let (mut title, artist) = {
+    let (title, artist) = {
         let data = data.read().await;
         let mysql = data.get::<MySQL>().unwrap();
         if let Ok(mapset) = mysql.get_beatmapset(mapset_id).await {
@@ -43,16 +54,151 @@ pub async fn get_title_artist(
             }
         }
     };
+    Ok((clean_title(title), artist.to_lowercase()))
+}
+
+// Remove content in parentheses and featured artists
+fn clean_title(mut title: String) -> String {
     if title.contains('(') && title.contains(')') {
         let idx_open = title.find('(').unwrap();
         let idx_close = title.find(')').unwrap();
-        title.replace_range(idx_open..=idx_close, "");
+        if idx_open < idx_close {
+            title.replace_range(idx_open..=idx_close, "");
+        }
     }
     if let Some(idx) = title.find("feat.").or_else(|| title.find("ft.")) {
         title.truncate(idx);
     }
-    title = title.trim().to_string().to_lowercase();
-    Ok((title, artist.to_lowercase()))
+    title.trim().to_lowercase()
+}
+
+/// Alternative titles and artists of a mapset that are accepted as answer
+#[derive(Default)]
+pub struct Aliases {
+    titles: Vec<String>,
+    artists: Vec<String>,
+}
+
+impl Aliases {
+    pub fn new(aliases: Vec<BgAlias>) -> Self {
+        let mut titles = Vec::new();
+        let mut artists = Vec::new();
+        for BgAlias { kind, alias, .. } in aliases {
+            let (list, alias) = match kind {
+                AliasKind::Title => (&mut titles, clean_title(alias)),
+                AliasKind::Artist => (&mut artists, alias.trim().to_lowercase()),
+            };
+            if !alias.is_empty() && !list.contains(&alias) {
+                list.push(alias);
+            }
+        }
+        Self { titles, artists }
+    }
+}
+
+/// Stored aliases of the mapset, on first encounter
+/// the unicode title and artist are requested and stored as well
+pub async fn get_aliases(mapset_id: u32, data: &RwLock<TypeMap>) -> Aliases {
+    let data = data.read().await;
+    let mysql = data.get::<MySQL>().unwrap();
+    let mut aliases = match mysql.get_bg_aliases(mapset_id).await {
+        Ok(aliases) => aliases,
+        Err(why) => {
+            warn!("Error while getting bg aliases of {}: {}", mapset_id, why);
+            return Aliases::default();
+        }
+    };
+    if aliases.iter().all(|alias| alias.added_by.is_some()) {
+        if let Some(api) = data.get::<Scraper>().unwrap().api_v2() {
+            match api.get_beatmapset(mapset_id).await {
+                Ok(mapset) => {
+                    let unicode = vec![
+                        (AliasKind::Title, mapset.title_unicode),
+                        (AliasKind::Artist, mapset.artist_unicode),
+                    ];
+                    if let Err(why) = mysql.add_bg_aliases(mapset_id, &unicode, None).await {
+                        warn!(
+                            "Error while adding unicode aliases of {}: {}",
+                            mapset_id, why
+                        );
+                    }
+                    let unicode = unicode.into_iter().map(|(kind, alias)| BgAlias {
+                        kind,
+                        alias,
+                        added_by: None,
+                    });
+                    aliases.extend(unicode);
+                }
+                Err(why) => warn!("Could not get unicode names of {}: {}", mapset_id, why),
+            }
+        }
+    }
+    Aliases::new(aliases)
+}
+
+pub enum ContentResult {
+    Title { exact: bool },
+    Artist { exact: bool },
+    None,
+}
+
+/// Check whether a message guesses the title or, unless it was already guessed, the artist.
+/// Aliases count just as much as the actual title and artist.
+pub fn check_guess(
+    content: &str,
+    title: &str,
+    artist: &str,
+    aliases: &Aliases,
+    artist_guessed: bool,
+) -> ContentResult {
+    let content = content.to_lowercase();
+    let titles = || iter::once(title).chain(aliases.titles.iter().map(String::as_str));
+    let artists = || iter::once(artist).chain(aliases.artists.iter().map(String::as_str));
+    // Guessed the title exactly?
+    if titles().any(|title| content == title) {
+        return ContentResult::Title { exact: true };
+    }
+    // Guessed sufficiently many words of the title?
+    if titles().any(|title| same_words(&content, title)) {
+        return ContentResult::Title { exact: false };
+    }
+    // Similar enough to the title?
+    let title_similarity = titles()
+        .map(|title| similarity(&content, title))
+        .fold(0.0, f32::max);
+    if title_similarity > 0.5 {
+        return ContentResult::Title { exact: false };
+    }
+    if !artist_guessed {
+        // Guessed the artist exactly?
+        if artists().any(|artist| content == artist) {
+            return ContentResult::Artist { exact: true };
+        // Similar enough to the artist?
+        } else if title_similarity < 0.3
+            && artists().any(|artist| similarity(&content, artist) > 0.5)
+        {
+            return ContentResult::Artist { exact: false };
+        }
+    }
+    ContentResult::None
+}
+
+fn same_words(content: &str, title: &str) -> bool {
+    if !title.contains(' ') {
+        return false;
+    }
+    let mut same_word_len = 0;
+    for title_word in title.split(' ') {
+        for content_word in content.split(' ') {
+            if title_word == content_word {
+                same_word_len += title_word.len();
+                if same_word_len > 8 {
+                    return true;
+                }
+            }
+        }
+    }
+    false
 }
 
 pub fn similarity(word_a: &str, word_b: &str) -> f32 {
@@ -83,3 +229,38 @@ fn levenshtein_distance(word_a: &str, word_b: &str) -> usize {
     }
     *costs.last().unwrap()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn alias(kind: AliasKind, alias: &str) -> BgAlias {
+        BgAlias {
+            kind,
+            alias: alias.to_owned(),
+            added_by: None,
+        }
+    }
+
+    #[test]
+    fn aliases_are_accepted() {
+        let aliases = Aliases::new(vec![
+            alias(AliasKind::Title, "千本桜 (TV Size)"),
+            alias(AliasKind::Artist, "黒うさP feat. 初音ミク"),
+        ]);
+        let guess = |content| check_guess(content, "senbonzakura", "kurousa-p", &aliases, false);
+        assert!(matches!(
+            guess("Senbonzakura"),
+            ContentResult::Title { exact: true }
+        ));
+        assert!(matches!(
+            guess("千本桜"),
+            ContentResult::Title { exact: true }
+        ));
+        assert!(matches!(
+            guess("黒うさP feat. 初音ミク"),
+            ContentResult::Artist { exact: true }
+        ));
+        assert!(matches!(guess("something else"), ContentResult::None));
+    }
+}
@@ -0,0 +1,130 @@
+use std::{fmt, str::FromStr};
+
+/// What the entries of a higher-or-lower game are compared by
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum HlVersion {
+    Pp = 0,
+    Stars = 1,
+}
+
+impl FromStr for HlVersion {
+    type Err = ();
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "pp" | "p" => Ok(HlVersion::Pp),
+            "stars" | "star" | "sr" | "s" => Ok(HlVersion::Stars),
+            _ => Err(()),
+        }
+    }
+}
+
+impl fmt::Display for HlVersion {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            HlVersion::Pp => f.write_str("pp"),
+            HlVersion::Stars => f.write_str("stars"),
+        }
+    }
+}
+
+/// A score or map that is shown to the player
+pub struct HlEntry {
+    pub name: String,
+    pub mapset_id: u32,
+    pub value: f32,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum Guess {
+    Correct,
+    Wrong,
+    /// Correct, and no entries are left; `current` stays on the last pair
+    Finished,
+}
+
+/// Compare two entries at a time, the second one becomes the first after a correct guess
+pub struct HigherLower {
+    entries: Vec<HlEntry>,
+    idx: usize,
+    pub streak: u32,
+}
+
+impl HigherLower {
+    /// `entries` must contain at least two elements
+    pub fn new(entries: Vec<HlEntry>) -> Self {
+        Self {
+            entries,
+            idx: 0,
+            streak: 0,
+        }
+    }
+
+    /// The known entry and the one to guess
+    pub fn current(&self) -> (&HlEntry, &HlEntry) {
+        (&self.entries[self.idx], &self.entries[self.idx + 1])
+    }
+
+    /// Equal values count as correct either way
+    pub fn guess(&mut self, higher: bool) -> Guess {
+        let (known, next) = self.current();
+        let correct = if higher {
+            next.value >= known.value
+        } else {
+            next.value <= known.value
+        };
+        if !correct {
+            return Guess::Wrong;
+        }
+        self.streak += 1;
+        if self.idx + 2 >= self.entries.len() {
+            Guess::Finished
+        } else {
+            self.idx += 1;
+            Guess::Correct
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn game(values: &[f32]) -> HigherLower {
+        let entries = values
+            .iter()
+            .enumerate()
+            .map(|(i, &value)| HlEntry {
+                name: i.to_string(),
+                mapset_id: i as u32,
+                value,
+            })
+            .collect();
+        HigherLower::new(entries)
+    }
+
+    #[test]
+    fn streak_until_wrong() {
+        let mut game = game(&[100.0, 200.0, 150.0, 300.0]);
+        assert_eq!(game.guess(true), Guess::Correct);
+        assert_eq!(game.guess(false), Guess::Correct);
+        assert_eq!(game.current().0.value, 150.0);
+        assert_eq!(game.guess(false), Guess::Wrong);
+        assert_eq!(game.streak, 2);
+    }
+
+    #[test]
+    fn ties_and_end() {
+        let mut game = game(&[5.0, 5.0, 6.0]);
+        assert_eq!(game.guess(false), Guess::Correct);
+        assert_eq!(game.guess(true), Guess::Finished);
+        assert_eq!(game.current().1.value, 6.0);
+        assert_eq!(game.streak, 2);
+    }
+
+    #[test]
+    fn parse_versions() {
+        assert_eq!("PP".parse(), Ok(HlVersion::Pp));
+        assert_eq!("sr".parse(), Ok(HlVersion::Stars));
+        assert!("acc".parse::<HlVersion>().is_err());
+    }
+}
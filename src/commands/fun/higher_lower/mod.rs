@@ -0,0 +1,399 @@
+mod game;
+
+use game::{Guess, HigherLower};
+pub use game::{HlEntry, HlVersion};
+
+use crate::{
+    database::MySQL,
+    embeds::{EmbedData, HLRankingEmbed, HLRoundEmbed},
+    osu_cache::OsuCache,
+    pagination::{HLRankingPagination, Pagination},
+    util::{globals::OSU_API_ISSUE, numbers, MessageExt},
+    DiscordLinks, Osu,
+};
+
+use rand::seq::SliceRandom;
+use rosu::models::{Beatmap, GameMode};
+use serenity::{
+    collector::ReactionCollector,
+    framework::standard::{macros::command, Args, CommandError, CommandResult},
+    model::{
+        channel::{Message, ReactionType},
+        id::UserId,
+    },
+    prelude::Context,
+};
+use std::{collections::HashMap, convert::TryFrom, sync::Arc, time::Duration};
+use tokio::{stream::StreamExt, time};
+
+/// Amount of scores or maps a single game consists of at most
+const MAX_ENTRIES: usize = 30;
+
+#[command]
+#[description = "Guess whether the second score is worth more or less pp than the first, \
+or whether the second map has more or less stars than the first.\n\
+React with ⬆️ for higher and ⬇️ for lower, each guess must be made within a minute.\n\
+The `pp` version uses the top scores of the given or linked user, \
+the `stars` version uses random maps.\n\
+Check `<hl lb` for the best streaks."]
+#[usage = "[pp / stars] [username]"]
+#[example = "pp badewanne3"]
+#[example = "stars"]
+#[aliases("hl")]
+#[sub_commands("leaderboard")]
+async fn higherlower(ctx: &Context, msg: &Message, mut args: Args) -> CommandResult {
+    let version = match args.current().map(str::parse) {
+        Some(Ok(version)) => {
+            args.advance();
+            version
+        }
+        _ => HlVersion::Pp,
+    };
+    let entries = match version {
+        HlVersion::Pp => pp_entries(ctx, msg, args.rest().trim()).await?,
+        HlVersion::Stars => star_entries(ctx, msg).await?,
+    };
+    let entries = match entries {
+        Some(entries) if entries.len() >= 2 => entries,
+        Some(_) => {
+            msg.channel_id
+                .say(ctx, "Not enough scores or maps to play with")
+                .await?
+                .reaction_delete(ctx, msg.author.id)
+                .await;
+            return Ok(());
+        }
+        None => return Ok(()),
+    };
+    let mut game = HigherLower::new(entries);
+
+    // Send the first round
+    let (known, next) = game.current();
+    let embed_data = HLRoundEmbed::new(known, next, version, 0, false);
+    let mut response = msg
+        .channel_id
+        .send_message(ctx, |m| m.embed(|e| embed_data.build(e)))
+        .await?;
+    for &reaction in ["⬆️", "⬇️"].iter() {
+        let reaction = ReactionType::try_from(reaction).unwrap();
+        response.react(ctx, reaction).await?;
+    }
+    let mut collector = response
+        .await_reactions(ctx)
+        .author_id(msg.author.id)
+        .removed(true)
+        .await;
+
+    // Run the game until a wrong guess or timeout
+    let content = loop {
+        let higher = match time::timeout(Duration::from_secs(60), next_guess(&mut collector)).await
+        {
+            Ok(Some(higher)) => higher,
+            _ => break "Time's up!",
+        };
+        match game.guess(higher) {
+            Guess::Correct => {
+                let (known, next) = game.current();
+                let embed_data = HLRoundEmbed::new(known, next, version, game.streak, false);
+                response
+                    .edit(ctx, |m| m.embed(|e| embed_data.build(e)))
+                    .await?;
+            }
+            Guess::Wrong => break "Wrong guess!",
+            Guess::Finished => break "No entries left, you guessed all of them!",
+        }
+    };
+    let (known, next) = game.current();
+    let embed_data = HLRoundEmbed::new(known, next, version, game.streak, true);
+    response
+        .edit(ctx, |m| m.content(content).embed(|e| embed_data.build(e)))
+        .await?;
+
+    // Store the streak
+    if game.streak > 0 {
+        let data = ctx.data.read().await;
+        let mysql = data.get::<MySQL>().unwrap();
+        match mysql
+            .update_hl_highscore(msg.author.id.0, version, game.streak)
+            .await
+        {
+            Ok(true) => {
+                let content = format!(
+                    "New personal best of {} for the {} version!",
+                    game.streak, version
+                );
+                msg.channel_id.say(ctx, content).await?;
+            }
+            Ok(false) => {}
+            Err(why) => {
+                msg.channel_id
+                    .say(ctx, "Some database issue, blame bade")
+                    .await?
+                    .reaction_delete(ctx, msg.author.id)
+                    .await;
+                return Err(why.to_string().into());
+            }
+        }
+    }
+    Ok(())
+}
+
+// Whether the next ⬆️ / ⬇️ reaction of the author guesses higher
+async fn next_guess(collector: &mut ReactionCollector) -> Option<bool> {
+    while let Some(reaction) = collector.next().await {
+        if let ReactionType::Unicode(ref r) = reaction.as_inner_ref().emoji {
+            match r.as_str() {
+                "⬆️" => return Some(true),
+                "⬇️" => return Some(false),
+                _ => {}
+            }
+        }
+    }
+    None
+}
+
+// Top scores of the given or linked user, returns None if a response was already sent
+async fn pp_entries(
+    ctx: &Context,
+    msg: &Message,
+    name: &str,
+) -> Result<Option<Vec<HlEntry>>, CommandError> {
+    let name = if !name.is_empty() {
+        name.to_owned()
+    } else {
+        let data = ctx.data.read().await;
+        let links = data.get::<DiscordLinks>().unwrap();
+        match links.get(msg.author.id.as_u64()) {
+            Some(name) => name.clone(),
+            None => {
+                msg.channel_id
+                    .say(
+                        ctx,
+                        "Either specify an osu name or link your discord \
+                        to an osu profile via `<link osuname`",
+                    )
+                    .await?
+                    .reaction_delete(ctx, msg.author.id)
+                    .await;
+                return Ok(None);
+            }
+        }
+    };
+
+    // Retrieve the user's top scores
+    let mut scores = {
+        let data = ctx.data.read().await;
+        let osu = data.get::<Osu>().unwrap();
+        let cache = data.get::<OsuCache>().unwrap();
+        let user = match cache.user(osu, &name, GameMode::STD).await {
+            Ok(Some(user)) => user,
+            Ok(None) => {
+                msg.channel_id
+                    .say(ctx, format!("User `{}` was not found", name))
+                    .await?
+                    .reaction_delete(ctx, msg.author.id)
+                    .await;
+                return Ok(None);
+            }
+            Err(why) => {
+                msg.channel_id
+                    .say(ctx, OSU_API_ISSUE)
+                    .await?
+                    .reaction_delete(ctx, msg.author.id)
+                    .await;
+                return Err(why.to_string().into());
+            }
+        };
+        match cache.best(osu, &user, GameMode::STD).await {
+            Ok(scores) => scores,
+            Err(why) => {
+                msg.channel_id
+                    .say(ctx, OSU_API_ISSUE)
+                    .await?
+                    .reaction_delete(ctx, msg.author.id)
+                    .await;
+                return Err(why.to_string().into());
+            }
+        }
+    };
+    scores.retain(|score| score.pp.is_some() && score.beatmap_id.is_some());
+    scores.shuffle(&mut rand::thread_rng());
+    scores.truncate(MAX_ENTRIES);
+
+    // Get all relevant maps from the database
+    let map_ids: Vec<u32> = scores.iter().map(|s| s.beatmap_id.unwrap()).collect();
+    let mut maps = {
+        let data = ctx.data.read().await;
+        let mysql = data.get::<MySQL>().unwrap();
+        mysql
+            .get_beatmaps(&map_ids)
+            .await
+            .unwrap_or_else(|_| HashMap::default())
+    };
+
+    // Retrieve missing maps
+    let mut entries = Vec::with_capacity(scores.len());
+    let mut missing_maps = Vec::new();
+    {
+        let data = ctx.data.read().await;
+        let osu = data.get::<Osu>().unwrap();
+        for score in scores {
+            let map = match maps.remove(&score.beatmap_id.unwrap()) {
+                Some(map) => map,
+                None => match score.get_beatmap(osu).await {
+                    Ok(map) => {
+                        missing_maps.push(map.clone());
+                        map
+                    }
+                    Err(why) => {
+                        msg.channel_id
+                            .say(ctx, OSU_API_ISSUE)
+                            .await?
+                            .reaction_delete(ctx, msg.author.id)
+                            .await;
+                        return Err(why.to_string().into());
+                    }
+                },
+            };
+            let mut name = map_name(&map);
+            if !score.enabled_mods.is_empty() {
+                name = format!("{} +{}", name, score.enabled_mods);
+            }
+            entries.push(HlEntry {
+                name,
+                mapset_id: map.beatmapset_id,
+                value: score.pp.unwrap(),
+            });
+        }
+    }
+
+    // Add missing maps to database
+    if !missing_maps.is_empty() {
+        let data = ctx.data.read().await;
+        let mysql = data.get::<MySQL>().unwrap();
+        let len = missing_maps.len();
+        match mysql.insert_beatmaps(&missing_maps).await {
+            Ok(_) if len == 1 => {}
+            Ok(_) => info!("Added {} maps to DB", len),
+            Err(why) => warn!("Error while adding maps to DB: {}", why),
+        }
+    }
+    Ok(Some(entries))
+}
+
+// Random maps of the database, returns None if a response was already sent
+async fn star_entries(ctx: &Context, msg: &Message) -> Result<Option<Vec<HlEntry>>, CommandError> {
+    let maps = {
+        let data = ctx.data.read().await;
+        let mysql = data.get::<MySQL>().unwrap();
+        match mysql
+            .get_random_beatmaps(GameMode::STD, MAX_ENTRIES as u32)
+            .await
+        {
+            Ok(maps) => maps,
+            Err(why) => {
+                msg.channel_id
+                    .say(ctx, "Some database issue, blame bade")
+                    .await?
+                    .reaction_delete(ctx, msg.author.id)
+                    .await;
+                return Err(why.to_string().into());
+            }
+        }
+    };
+    let entries = maps
+        .into_iter()
+        .map(|map| HlEntry {
+            name: map_name(&map),
+            mapset_id: map.beatmapset_id,
+            value: map.stars,
+        })
+        .collect();
+    Ok(Some(entries))
+}
+
+fn map_name(map: &Beatmap) -> String {
+    format!("{} - {} [{}]", map.artist, map.title, map.version)
+}
+
+#[command]
+#[description = "Show the best streaks of the higher-or-lower game, \
+either for the `pp` or the `stars` version"]
+#[usage = "[pp / stars]"]
+#[example = "stars"]
+#[aliases("lb", "ranking")]
+async fn leaderboard(ctx: &Context, msg: &Message, mut args: Args) -> CommandResult {
+    let version = args.single::<HlVersion>().unwrap_or(HlVersion::Pp);
+    let scores = {
+        let data = ctx.data.read().await;
+        let mysql = data.get::<MySQL>().unwrap();
+        match mysql.get_hl_highscores(version).await {
+            Ok(scores) => scores,
+            Err(why) => {
+                msg.channel_id
+                    .say(ctx, "Some database issue, blame bade")
+                    .await?
+                    .reaction_delete(ctx, msg.author.id)
+                    .await;
+                return Err(why.to_string().into());
+            }
+        }
+    };
+    if scores.is_empty() {
+        let content = format!(
+            "Looks like no one has played the {} version of higher-or-lower yet",
+            version
+        );
+        msg.channel_id
+            .say(ctx, content)
+            .await?
+            .reaction_delete(ctx, msg.author.id)
+            .await;
+        return Ok(());
+    }
+    let author_idx = scores.iter().position(|(user, _)| *user == msg.author.id.0);
+
+    // Gather usernames for initial page
+    let mut usernames = HashMap::with_capacity(15);
+    for &id in scores.iter().take(15).map(|(id, _)| id) {
+        let name = if let Ok(user) = UserId(id).to_user(ctx).await {
+            user.name
+        } else {
+            String::from("Unknown user")
+        };
+        usernames.insert(id, name);
+    }
+    let initial_scores = scores
+        .iter()
+        .take(15)
+        .map(|(id, score)| (usernames.get(&id).unwrap(), *score))
+        .collect();
+
+    // Prepare initial page
+    let pages = numbers::div_euclid(15, scores.len());
+    let data = HLRankingEmbed::new(author_idx, initial_scores, version, 1, (1, pages));
+
+    // Creating the embed
+    let resp = msg
+        .channel_id
+        .send_message(&ctx.http, |m| m.embed(|e| data.build(e)))
+        .await?;
+
+    // Skip pagination if too few entries
+    if scores.len() <= 15 {
+        resp.reaction_delete(ctx, msg.author.id).await;
+        return Ok(());
+    }
+
+    // Pagination
+    let pagination =
+        HLRankingPagination::new(ctx, resp, msg.author.id, author_idx, scores, version).await;
+    let cache = Arc::clone(&ctx.cache);
+    let http = Arc::clone(&ctx.http);
+    tokio::spawn(async move {
+        if let Err(why) = pagination.start(cache, http).await {
+            warn!("Pagination error: {}", why)
+        }
+    });
+    Ok(())
+}
@@ -1,9 +1,13 @@
 mod bg_game;
+mod higher_lower;
 mod minesweeper;
+mod song_game;
 mod songs;
 
 pub use bg_game::*;
+pub use higher_lower::{HlEntry, HlVersion, HIGHERLOWER_COMMAND};
 pub use minesweeper::*;
+pub use song_game::{SongGame, SONGGAME_COMMAND};
 pub use songs::*;
 
 use serenity::framework::standard::macros::group;
@@ -12,7 +16,9 @@ use serenity::framework::standard::macros::group;
 #[description = "Random fun commands"]
 #[commands(
     backgroundgame,
+    higherlower,
     minesweeper,
+    songgame,
     bombsaway,
     catchit,
     ding,
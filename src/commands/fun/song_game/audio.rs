@@ -0,0 +1,168 @@
+use failure::Error;
+use std::ops::Range;
+
+// Files longer than this are considered full songs instead of previews
+const PREVIEW_MAX_SECS: f32 = 20.0;
+// Where to start clips of full songs, relative to their length
+const FULL_SONG_OFFSET: f32 = 0.4;
+
+// Layer III bitrates in kbps by index
+const BITRATES_MPEG1: [u32; 15] = [
+    0, 32, 40, 48, 56, 64, 80, 96, 112, 128, 160, 192, 224, 256, 320,
+];
+const BITRATES_MPEG2: [u32; 15] = [0, 8, 16, 24, 32, 40, 48, 56, 64, 80, 96, 112, 128, 144, 160];
+
+/// MP3 audio that can be cut into clips at frame boundaries
+pub struct AudioClip {
+    bytes: Vec<u8>,
+    frames: Vec<Range<usize>>,
+    frame_secs: f32,
+    offset: usize,
+}
+
+impl AudioClip {
+    pub fn new(bytes: Vec<u8>) -> Result<Self, Error> {
+        let mut pos = id3_len(&bytes);
+        let mut frames = Vec::new();
+        let mut frame_secs = 0.0;
+        while pos + 4 <= bytes.len() {
+            match FrameHeader::parse(&bytes[pos..pos + 4]) {
+                Some(header) if pos + header.len <= bytes.len() => {
+                    frame_secs = header.samples as f32 / header.sample_rate as f32;
+                    frames.push(pos..pos + header.len);
+                    pos += header.len;
+                }
+                // Trailing tags or garbage in between frames
+                _ => pos += 1,
+            }
+        }
+        if frames.is_empty() {
+            bail!("No mp3 frames found");
+        }
+        let duration = frames.len() as f32 * frame_secs;
+        let offset = if duration > PREVIEW_MAX_SECS {
+            (frames.len() as f32 * FULL_SONG_OFFSET) as usize
+        } else {
+            0
+        };
+        Ok(Self {
+            bytes,
+            frames,
+            frame_secs,
+            offset,
+        })
+    }
+
+    /// The first `secs` seconds of the relevant part of the audio
+    pub fn clip(&self, secs: f32) -> Vec<u8> {
+        let amount = (secs / self.frame_secs).ceil() as usize;
+        let frames = &self.frames[self.offset..];
+        let end = amount.min(frames.len());
+        frames[..end]
+            .iter()
+            .flat_map(|range| self.bytes[range.clone()].iter().copied())
+            .collect()
+    }
+}
+
+struct FrameHeader {
+    len: usize,
+    samples: u32,
+    sample_rate: u32,
+}
+
+impl FrameHeader {
+    fn parse(header: &[u8]) -> Option<Self> {
+        // Frame sync
+        if header[0] != 0xFF || header[1] & 0xE0 != 0xE0 {
+            return None;
+        }
+        let version = (header[1] >> 3) & 0b11;
+        let layer = (header[1] >> 1) & 0b11;
+        // Only layer III i.e. mp3 is supported
+        if version == 1 || layer != 1 {
+            return None;
+        }
+        let mpeg1 = version == 3;
+        let bitrate_idx = (header[2] >> 4) as usize;
+        let sample_rate_idx = ((header[2] >> 2) & 0b11) as usize;
+        let padding = ((header[2] >> 1) & 1) as usize;
+        if bitrate_idx == 0 || bitrate_idx == 15 || sample_rate_idx == 3 {
+            return None;
+        }
+        let bitrate = if mpeg1 {
+            BITRATES_MPEG1[bitrate_idx]
+        } else {
+            BITRATES_MPEG2[bitrate_idx]
+        };
+        let sample_rate = [44_100, 48_000, 32_000][sample_rate_idx]
+            >> match version {
+                3 => 0,
+                2 => 1,
+                _ => 2,
+            };
+        let samples = if mpeg1 { 1152 } else { 576 };
+        let len = (samples / 8 * bitrate * 1000) as usize / sample_rate as usize + padding;
+        Some(Self {
+            len,
+            samples,
+            sample_rate,
+        })
+    }
+}
+
+// Size of the ID3v2 tag at the start of the file, if any
+fn id3_len(bytes: &[u8]) -> usize {
+    if bytes.len() < 10 || &bytes[..3] != b"ID3" {
+        return 0;
+    }
+    let size = bytes[6..10]
+        .iter()
+        .fold(0, |size, &byte| (size << 7) | (byte & 0x7F) as usize);
+    let footer = if bytes[5] & 0x10 != 0 { 10 } else { 0 };
+    10 + size + footer
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Silent MPEG-1 layer III frames at 128kbps and 44.1kHz
+    fn frames(amount: usize) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        for _ in 0..amount {
+            let mut frame = vec![0; 417];
+            frame[..4].copy_from_slice(&[0xFF, 0xFB, 0x90, 0x00]);
+            bytes.extend(frame);
+        }
+        bytes
+    }
+
+    #[test]
+    fn clips_whole_frames() {
+        let clip = AudioClip::new(frames(400)).unwrap();
+        // 1152 samples at 44.1kHz are ~26ms per frame
+        assert_eq!(clip.clip(1.0).len(), 39 * 417);
+        assert_eq!(clip.clip(100.0).len(), 400 * 417);
+    }
+
+    #[test]
+    fn skips_id3_tag() {
+        let mut bytes = vec![b'I', b'D', b'3', 4, 0, 0, 0, 0, 0, 5, 1, 2, 3, 4, 5];
+        bytes.extend(frames(10));
+        let clip = AudioClip::new(bytes).unwrap();
+        assert_eq!(clip.clip(1.0), frames(10));
+    }
+
+    #[test]
+    fn full_songs_are_clipped_from_the_middle() {
+        // ~31 seconds
+        let clip = AudioClip::new(frames(1200)).unwrap();
+        assert_eq!(clip.offset, 480);
+    }
+
+    #[test]
+    fn rejects_non_mp3() {
+        assert!(AudioClip::new(b"definitely not audio".to_vec()).is_err());
+    }
+}
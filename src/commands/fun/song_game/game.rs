@@ -0,0 +1,326 @@
+use super::AudioClip;
+use crate::{
+    commands::fun::bg_game::util::{self, Aliases, ContentResult},
+    util::globals::HOMEPAGE,
+    MySQL, SongGames,
+};
+
+use failure::Error;
+use rand::seq::SliceRandom;
+use serenity::{
+    collector::{MessageCollector, MessageCollectorBuilder},
+    framework::standard::CommandResult,
+    http::client::Http,
+    model::id::{ChannelId, GuildId},
+    prelude::{Context, RwLock, TypeMap},
+};
+use std::{collections::VecDeque, env, fmt::Write, path::PathBuf, sync::Arc};
+use tokio::{
+    fs,
+    stream::StreamExt,
+    sync::watch::{channel, Receiver, Sender},
+    time,
+};
+
+/// Seconds of audio per hint level, the last one being the whole preview
+const CLIP_SECS: [f32; 4] = [1.5, 3.0, 5.0, 10.0];
+/// Songs that are tried in a row before the game ends
+const MAX_RESTART_ATTEMPTS: usize = 5;
+
+pub struct SongGame {
+    game: Arc<RwLock<GameData>>,
+    tx: Sender<LoopResult>,
+    rx: Receiver<LoopResult>,
+}
+
+impl SongGame {
+    pub async fn new() -> Self {
+        let (tx, mut rx) = channel(LoopResult::Restart);
+        let _ = rx.recv().await;
+        Self {
+            game: Arc::new(RwLock::new(GameData::default())),
+            tx,
+            rx,
+        }
+    }
+
+    pub fn stop(&mut self) -> Result<(), Error> {
+        self.tx
+            .broadcast(LoopResult::Stop)
+            .map_err(|_| format_err!("Could not send stop message"))
+    }
+
+    pub fn restart(&mut self) -> Result<(), Error> {
+        self.tx
+            .broadcast(LoopResult::Restart)
+            .map_err(|_| format_err!("Could not send restart message"))
+    }
+
+    /// Longer clip of the current song, `None` if the whole preview is already out
+    pub async fn hint(&self) -> Option<Vec<u8>> {
+        let mut game = self.game.write().await;
+        if game.level + 1 >= CLIP_SECS.len() {
+            return None;
+        }
+        game.level += 1;
+        Some(game.clip())
+    }
+
+    /// `mapset_ids` must not be empty
+    pub async fn start(
+        &self,
+        ctx: &Context,
+        channel: ChannelId,
+        guild: Option<GuildId>,
+        mut mapset_ids: Vec<u32>,
+    ) {
+        let mut collector = MessageCollectorBuilder::new(ctx)
+            .channel_id(channel)
+            .filter(|msg| !msg.author.bot)
+            .await;
+        let game_lock = Arc::clone(&self.game);
+        let mut rx = self.rx.clone();
+        let data = Arc::clone(&ctx.data);
+        let http = Arc::clone(&ctx.http);
+        tokio::spawn(async move {
+            let mut previous_ids = VecDeque::with_capacity(mapset_ids.len() / 2 + 1);
+            loop {
+                // Initialize game
+                let clip = {
+                    let mut game = game_lock.write().await;
+                    game.restart_with_clip(&data, &mut mapset_ids, &mut previous_ids)
+                        .await
+                };
+                let clip = match clip {
+                    Ok(clip) => clip,
+                    Err(why) => {
+                        warn!("Error creating song game: {}", why);
+                        let content = "Could not load the next song, ending the game";
+                        let _ = channel.send_message(&http, |m| m.content(content)).await;
+                        data.write()
+                            .await
+                            .get_mut::<SongGames>()
+                            .unwrap()
+                            .remove(&channel);
+                        collector.stop();
+                        break;
+                    }
+                };
+                let _ = channel
+                    .send_message(&http, |m| {
+                        m.content("Which song is this?")
+                            .add_file((clip.as_slice(), "song_clip.mp3"))
+                    })
+                    .await;
+
+                let result = tokio::select! {
+                    // Listen for stop or restart invokes
+                    option = rx.recv() => option.unwrap_or(LoopResult::Stop),
+                    // Let the game run
+                    result = game_loop(&mut collector, &http, &game_lock, channel) => result,
+                    // Timeout after 3 minutes
+                    _ = time::delay_for(time::Duration::from_secs(180)) => LoopResult::Stop,
+                };
+
+                // Process the result
+                match result {
+                    LoopResult::Restart => {
+                        let game = game_lock.read().await;
+                        let content = format!(
+                            "The song was `{} - {}`\nMapset: {}beatmapsets/{}",
+                            game.artist, game.title, HOMEPAGE, game.mapset_id
+                        );
+                        let _ = game.resolve(&http, channel, content).await;
+                    }
+                    LoopResult::Stop => {
+                        {
+                            let game = game_lock.read().await;
+                            let content = format!(
+                                "The song was `{} - {}`\nMapset: {}beatmapsets/{}\n\
+                                End of game, see you next time o/",
+                                game.artist, game.title, HOMEPAGE, game.mapset_id
+                            );
+                            let _ = game.resolve(&http, channel, content).await;
+                        }
+                        data.write()
+                            .await
+                            .get_mut::<SongGames>()
+                            .unwrap()
+                            .remove(&channel);
+                        collector.stop();
+                        debug!("Song game finished");
+                        break;
+                    }
+                    LoopResult::Winner(user_id) => {
+                        if mapset_ids.len() >= 20 {
+                            let data = data.read().await;
+                            let mysql = data.get::<MySQL>().unwrap();
+                            if let Err(why) = mysql
+                                .increment_bggame_score(user_id, guild.map(|guild| guild.0))
+                                .await
+                            {
+                                error!("Error while incrementing bggame score: {}", why);
+                            }
+                        }
+                    }
+                }
+            }
+        });
+    }
+}
+
+#[derive(Clone, Copy)]
+enum LoopResult {
+    Winner(u64),
+    Restart,
+    Stop,
+}
+
+async fn game_loop(
+    collector: &mut MessageCollector,
+    http: &Http,
+    game_lock: &RwLock<GameData>,
+    channel: ChannelId,
+) -> LoopResult {
+    // Collect and evaluate messages
+    while let Some(msg) = collector.next().await {
+        let mut game = game_lock.write().await;
+        let content_result = util::check_guess(
+            &msg.content,
+            &game.title,
+            &game.artist,
+            &game.aliases,
+            game.artist_guessed,
+        );
+        match content_result {
+            ContentResult::Title { exact } => {
+                let mut content = if exact {
+                    format!("Gratz {}, you guessed it", msg.author.name)
+                } else {
+                    format!("You were close enough {}, gratz", msg.author.name)
+                };
+                let _ = write!(
+                    content,
+                    " \\:)\nThe song was `{} - {}`\nMapset: {}beatmapsets/{}",
+                    game.artist, game.title, HOMEPAGE, game.mapset_id
+                );
+                let _ = game.resolve(&http, channel, content).await;
+                return LoopResult::Winner(msg.author.id.0);
+            }
+            ContentResult::Artist { exact } => {
+                game.artist_guessed = true;
+                let content = if exact {
+                    format!(
+                        "That's the correct artist `{}`, can you get the title too?",
+                        msg.author.name
+                    )
+                } else {
+                    format!(
+                        "`{}` got the artist almost correct, \
+                        it's actually `{}` but can you get the title?",
+                        msg.author.name, game.artist
+                    )
+                };
+                let _ = channel.send_message(http, |m| m.content(content)).await;
+            }
+            ContentResult::None => {}
+        }
+    }
+    LoopResult::Stop
+}
+
+#[derive(Default)]
+struct GameData {
+    title: String,
+    artist: String,
+    mapset_id: u32,
+    aliases: Aliases,
+    artist_guessed: bool,
+    level: usize,
+    audio: Option<AudioClip>,
+}
+
+impl GameData {
+    async fn restart(&mut self, data: &RwLock<TypeMap>, mapset_id: u32) -> Result<(), Error> {
+        debug!("Next song game mapset id: {}", mapset_id);
+        let (title, artist) = util::get_title_artist(mapset_id, data).await?;
+        let aliases = util::get_aliases(mapset_id, data).await;
+        let mut path = PathBuf::from(env::var("SONG_PATH")?);
+        path.push(format!("{}.mp3", mapset_id));
+        let audio = AudioClip::new(fs::read(path).await?)?;
+        self.title = title;
+        self.artist = artist;
+        self.mapset_id = mapset_id;
+        self.aliases = aliases;
+        self.artist_guessed = false;
+        self.level = 0;
+        self.audio = Some(audio);
+        Ok(())
+    }
+
+    /// Clip of the next song, mapsets whose song can't be loaded are dropped
+    async fn restart_with_clip(
+        &mut self,
+        data: &RwLock<TypeMap>,
+        mapset_ids: &mut Vec<u32>,
+        previous_ids: &mut VecDeque<u32>,
+    ) -> Result<Vec<u8>, Error> {
+        for _ in 0..MAX_RESTART_ATTEMPTS {
+            if mapset_ids.is_empty() {
+                break;
+            }
+            let mapset_id = random_mapset(mapset_ids, previous_ids);
+            match self.restart(data, mapset_id).await {
+                Ok(_) => return Ok(self.clip()),
+                Err(why) => {
+                    warn!("Error loading song of mapset {}: {}", mapset_id, why);
+                    mapset_ids.retain(|&id| id != mapset_id);
+                    previous_ids.retain(|&id| id != mapset_id);
+                    previous_ids.truncate(mapset_ids.len() / 2);
+                }
+            }
+        }
+        bail!("Could not load a song, {} mapsets left", mapset_ids.len())
+    }
+
+    fn clip(&self) -> Vec<u8> {
+        match self.audio {
+            Some(ref audio) => audio.clip(CLIP_SECS[self.level]),
+            None => Vec::new(),
+        }
+    }
+
+    async fn resolve(&self, http: &Http, channel: ChannelId, content: String) -> CommandResult {
+        match self.audio {
+            Some(ref audio) => {
+                let preview = audio.clip(CLIP_SECS[CLIP_SECS.len() - 1]);
+                channel
+                    .send_message(http, |m| {
+                        m.content(content)
+                            .add_file((preview.as_slice(), "song_preview.mp3"))
+                    })
+                    .await?;
+            }
+            None => {
+                channel.send_message(http, |m| m.content(content)).await?;
+            }
+        }
+        Ok(())
+    }
+}
+
+// Avoid repeating recently played songs, `mapset_ids` must not be empty
+fn random_mapset(mapset_ids: &[u32], previous_ids: &mut VecDeque<u32>) -> u32 {
+    let buffer_size = mapset_ids.len() / 2;
+    let mut rng = rand::thread_rng();
+    loop {
+        let mapset_id = *mapset_ids.choose(&mut rng).unwrap();
+        if !previous_ids.contains(&mapset_id) {
+            previous_ids.push_front(mapset_id);
+            if previous_ids.len() > buffer_size {
+                previous_ids.pop_back();
+            }
+            return mapset_id;
+        }
+    }
+}
@@ -0,0 +1,193 @@
+mod audio;
+mod game;
+
+use audio::AudioClip;
+pub use game::SongGame;
+
+use crate::{
+    embeds::{EmbedData, SongGameHelpEmbed},
+    util::MessageExt,
+    BgGames, SongGames,
+};
+
+use failure::Error;
+use serenity::{
+    framework::standard::{macros::command, Args, CommandResult},
+    model::channel::Message,
+    prelude::Context,
+};
+use std::{env, path::PathBuf};
+use tokio::{fs, time};
+
+#[command]
+#[description = "Given a short clip of a map's song, try to guess its **title**.\n\
+Check `<songgame` for more help"]
+#[aliases("sg", "songguess")]
+#[sub_commands("start", "hint", "stop")]
+async fn songgame(ctx: &Context, msg: &Message, args: Args) -> CommandResult {
+    let response = if args.is_empty() {
+        let data = SongGameHelpEmbed::new();
+        msg.channel_id
+            .send_message(ctx, |m| m.embed(|e| data.build(e)))
+            .await?
+    } else {
+        msg.channel_id
+            .say(
+                ctx,
+                "That's not a valid subcommand. Check `<songgame` for more help.",
+            )
+            .await?
+    };
+    response.reaction_delete(ctx, msg.author.id).await;
+    Ok(())
+}
+
+#[command]
+#[aliases("s", "skip", "resolve", "r")]
+#[bucket = "bg_start"]
+async fn start(ctx: &Context, msg: &Message) -> CommandResult {
+    let channel = msg.channel_id;
+    {
+        let mut data = ctx.data.write().await;
+        if data.get::<BgGames>().unwrap().contains_key(&channel) {
+            drop(data);
+            msg.channel_id
+                .say(
+                    ctx,
+                    "There is already a background game running in this channel, \
+                    stop it first with `<bg stop`",
+                )
+                .await?;
+            return Ok(());
+        }
+        let games = data.get_mut::<SongGames>().unwrap();
+        if let Some(game) = games.get_mut(&channel) {
+            game.restart()?;
+            return Ok(());
+        }
+    }
+    let mapset_ids = match available_mapsets().await {
+        Ok(mapset_ids) => mapset_ids,
+        Err(why) => {
+            msg.channel_id
+                .say(ctx, "Could not load the songs, blame bade")
+                .await?;
+            return Err(why.to_string().into());
+        }
+    };
+    if mapset_ids.is_empty() {
+        msg.channel_id
+            .say(ctx, "There are no songs to guess yet")
+            .await?;
+        return Ok(());
+    }
+    _start(ctx, msg, mapset_ids).await
+}
+
+#[allow(clippy::map_entry)]
+async fn _start(ctx: &Context, msg: &Message, mapset_ids: Vec<u32>) -> CommandResult {
+    let channel = msg.channel_id;
+    let mut data = ctx.data.write().await;
+    let games = data.get_mut::<SongGames>().unwrap();
+    if games.contains_key(&channel) {
+        games.get_mut(&channel).unwrap().restart()?;
+    } else {
+        let game = SongGame::new().await;
+        game.start(ctx, channel, msg.guild_id, mapset_ids).await;
+        games.insert(channel, game);
+    }
+    Ok(())
+}
+
+#[command]
+#[aliases("h", "tip", "longer")]
+#[bucket = "bg_hint"]
+async fn hint(ctx: &Context, msg: &Message) -> CommandResult {
+    let clip = {
+        let data = match time::timeout(time::Duration::from_secs(5), ctx.data.read()).await {
+            Ok(data) => data,
+            Err(_) => {
+                msg.channel_id
+                    .say(ctx, "Error while retrieving hint, try again in a moment")
+                    .await?;
+                return Err("Timed out while waiting for data in `<sg h`".into());
+            }
+        };
+        match data.get::<SongGames>().unwrap().get(&msg.channel_id) {
+            Some(game) => Some(game.hint().await),
+            None => None,
+        }
+    };
+    match clip {
+        Some(Some(clip)) => {
+            msg.channel_id
+                .send_message(ctx, |m| {
+                    m.content("Here's a longer clip:")
+                        .add_file((clip.as_slice(), "song_clip.mp3"))
+                })
+                .await?;
+        }
+        Some(None) => {
+            msg.channel_id
+                .say(ctx, "You already got the whole preview, no more hints")
+                .await?
+                .reaction_delete(ctx, msg.author.id)
+                .await;
+        }
+        None => {
+            msg.channel_id
+                .say(
+                    ctx,
+                    "There is no running song game in this channel, \
+                    start one with `<sg s`",
+                )
+                .await?
+                .reaction_delete(ctx, msg.author.id)
+                .await;
+        }
+    }
+    Ok(())
+}
+
+#[command]
+#[aliases("end")]
+async fn stop(ctx: &Context, msg: &Message) -> CommandResult {
+    let mut data = ctx.data.write().await;
+    match data
+        .get_mut::<SongGames>()
+        .unwrap()
+        .get_mut(&msg.channel_id)
+    {
+        Some(game) => game.stop()?,
+        None => {
+            msg.channel_id
+                .say(
+                    ctx,
+                    "There is no running song game in this channel, \
+                    start one with `<sg s`",
+                )
+                .await?;
+        }
+    }
+    Ok(())
+}
+
+// Mapset ids of all `{mapset_id}.mp3` files in the song directory
+async fn available_mapsets() -> Result<Vec<u32>, Error> {
+    let mut dir = fs::read_dir(PathBuf::from(env::var("SONG_PATH")?)).await?;
+    let mut mapset_ids = Vec::new();
+    while let Some(entry) = dir.next_entry().await? {
+        let path = entry.path();
+        if path.extension().and_then(|ext| ext.to_str()) != Some("mp3") {
+            continue;
+        }
+        if let Some(id) = path
+            .file_stem()
+            .and_then(|stem| stem.to_str())
+            .and_then(|stem| stem.parse().ok())
+        {
+            mapset_ids.push(id);
+        }
+    }
+    Ok(mapset_ids)
+}
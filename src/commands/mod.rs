@@ -5,3 +5,82 @@ pub mod osu;
 pub mod owner;
 pub mod streams;
 pub mod utility;
+
+use fun::FUN_GROUP;
+use osu::{CATCHTHEBEAT_GROUP, MANIA_GROUP, OSUGENERAL_GROUP, OSU_GROUP, TAIKO_GROUP};
+use owner::OWNER_GROUP;
+use serenity::framework::standard::CommandGroup;
+use streams::STREAMTRACKING_GROUP;
+use utility::UTILITY_GROUP;
+
+/// All groups in the order they are registered in the framework
+pub static GROUPS: [&CommandGroup; 9] = [
+    &OSUGENERAL_GROUP,
+    &OSU_GROUP,
+    &MANIA_GROUP,
+    &TAIKO_GROUP,
+    &CATCHTHEBEAT_GROUP,
+    &FUN_GROUP,
+    &UTILITY_GROUP,
+    &STREAMTRACKING_GROUP,
+    &OWNER_GROUP,
+];
+
+/// Prefixes the framework listens to
+pub const PREFIXES: [&str; 2] = ["<", "!!"];
+
+/// Primary name and group of the top-level command the message invokes,
+/// sub commands are considered part of their parent command
+pub fn invoked_command(content: &str) -> Option<(&'static str, &'static str)> {
+    let content = PREFIXES
+        .iter()
+        .find_map(|prefix| content.strip_prefix(prefix))?;
+    let name = content.split_whitespace().next()?;
+    GROUPS
+        .iter()
+        .flat_map(|group| {
+            let group_name = group.name;
+            group
+                .options
+                .commands
+                .iter()
+                .map(move |&cmd| (cmd.options.names, group_name))
+        })
+        .find(|(names, _)| names.iter().any(|n| n.eq_ignore_ascii_case(name)))
+        .map(|(names, group)| (names[0], group))
+}
+
+/// Primary name of the command with the given name or alias
+pub fn find_command(name: &str) -> Option<&'static str> {
+    GROUPS
+        .iter()
+        .flat_map(|group| group.options.commands.iter())
+        .map(|cmd| cmd.options.names)
+        .find(|names| names.iter().any(|n| n.eq_ignore_ascii_case(name)))
+        .map(|names| names[0])
+}
+
+/// Name of the group with the given name
+pub fn find_group(name: &str) -> Option<&'static str> {
+    GROUPS
+        .iter()
+        .find(|group| group.name.eq_ignore_ascii_case(name))
+        .map(|group| group.name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sub_commands_belong_to_the_invoked_command() {
+        assert_eq!(invoked_command("<sg hint"), Some(("songgame", "Fun")));
+        assert_eq!(invoked_command("<bg hint"), Some(("backgroundgame", "Fun")));
+        assert_eq!(invoked_command("!!hl lb"), Some(("higherlower", "Fun")));
+        let (command, group) = invoked_command("<lb 2").unwrap();
+        assert_eq!(command, "leaderboard");
+        assert_ne!(group, "Fun");
+        assert!(invoked_command("<hint").is_none());
+        assert!(invoked_command("hello there").is_none());
+    }
+}
@@ -2,6 +2,7 @@ use crate::{
     arguments::MultNameArgs,
     database::MySQL,
     embeds::{CommonEmbed, EmbedData},
+    osu_cache::OsuCache,
     pagination::{CommonPagination, Pagination},
     util::{discord, globals::OSU_API_ISSUE, MessageExt},
     DiscordLinks, Osu,
@@ -9,10 +10,7 @@ use crate::{
 
 use itertools::Itertools;
 use rayon::prelude::*;
-use rosu::{
-    backend::requests::{BeatmapRequest, UserRequest},
-    models::{Beatmap, GameMode, Score, User},
-};
+use rosu::models::{Beatmap, GameMode, Score, User};
 use serenity::{
     framework::standard::{macros::command, Args, CommandResult},
     model::prelude::Message,
@@ -74,17 +72,14 @@ async fn common_send(mode: GameMode, ctx: &Context, msg: &Message, args: Args) -
     }
 
     // Retrieve all users and their top scores
-    let requests: HashMap<String, UserRequest> = names
-        .iter()
-        .map(|name| (name.clone(), UserRequest::with_username(name).mode(mode)))
-        .collect();
     let (users, mut all_scores): (HashMap<u32, User>, Vec<Vec<Score>>) = {
         let data = ctx.data.read().await;
         let osu = data.get::<Osu>().unwrap();
-        let mut users = HashMap::with_capacity(requests.len());
-        let mut all_scores = Vec::with_capacity(requests.len());
-        for (name, request) in requests.into_iter() {
-            let user = match request.queue_single(&osu).await {
+        let cache = data.get::<OsuCache>().unwrap();
+        let mut users = HashMap::with_capacity(names.len());
+        let mut all_scores = Vec::with_capacity(names.len());
+        for name in names.iter() {
+            let user = match cache.user(osu, name, mode).await {
                 Ok(result) => match result {
                     Some(user) => user,
                     None => {
@@ -105,7 +100,7 @@ async fn common_send(mode: GameMode, ctx: &Context, msg: &Message, args: Args) -
                     return Err(why.to_string().into());
                 }
             };
-            let scores = match user.get_top_scores(&osu, 100, mode).await {
+            let scores = match cache.best(osu, &user, mode).await {
                 Ok(scores) => scores,
                 Err(why) => {
                     msg.channel_id
@@ -185,10 +180,10 @@ async fn common_send(mode: GameMode, ctx: &Context, msg: &Message, args: Args) -
     let missing_maps = if !map_ids.is_empty() {
         let data = ctx.data.read().await;
         let osu = data.get::<Osu>().unwrap();
+        let cache = data.get::<OsuCache>().unwrap();
         let mut missing_maps = Vec::with_capacity(map_ids.len());
         for id in map_ids {
-            let req = BeatmapRequest::new().map_id(id);
-            let map = match req.queue_single(&osu).await {
+            let map = match cache.beatmap(osu, id).await {
                 Ok(result) => match result {
                     Some(map) => {
                         maps.insert(map.beatmap_id, map.clone());
@@ -2,6 +2,7 @@ use crate::{
     arguments::{MapModArgs, ModSelection},
     database::MySQL,
     embeds::{EmbedData, LeaderboardEmbed},
+    osu_cache::OsuCache,
     pagination::{LeaderboardPagination, Pagination},
     scraper::Scraper,
     util::{
@@ -12,13 +13,7 @@ use crate::{
     DiscordLinks, Osu,
 };
 
-use rosu::{
-    backend::requests::BeatmapRequest,
-    models::{
-        ApprovalStatus::{Approved, Loved, Ranked},
-        GameMods,
-    },
-};
+use rosu::models::GameMods;
 use serenity::{
     framework::standard::{macros::command, Args, CommandResult},
     model::channel::Message,
@@ -73,9 +68,9 @@ async fn leaderboard_send(
         match mysql.get_beatmap(map_id).await {
             Ok(map) => (false, map),
             Err(_) => {
-                let map_req = BeatmapRequest::new().map_id(map_id);
                 let osu = data.get::<Osu>().unwrap();
-                let map = match map_req.queue_single(&osu).await {
+                let cache = data.get::<OsuCache>().unwrap();
+                let map = match cache.beatmap(osu, map_id).await {
                     Ok(result) => match result {
                         Some(map) => map,
                         None => {
@@ -103,12 +98,7 @@ async fn leaderboard_send(
                         return Err(why.to_string().into());
                     }
                 };
-                (
-                    map.approval_status == Ranked
-                        || map.approval_status == Loved
-                        || map.approval_status == Approved,
-                    map,
-                )
+                (true, map)
             }
         }
     };
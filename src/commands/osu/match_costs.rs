@@ -1,13 +1,14 @@
 use crate::{
     arguments::MatchArgs,
     embeds::{EmbedData, MatchCostEmbed},
+    osu_cache::OsuCache,
     util::{globals::OSU_API_ISSUE, MessageExt},
     Osu,
 };
 
 use rosu::{
-    backend::requests::{MatchRequest, UserRequest},
-    models::{Match, Team, TeamType},
+    backend::requests::MatchRequest,
+    models::{GameMode, Match, Team, TeamType},
 };
 use serenity::{
     framework::standard::{macros::command, Args, CommandResult},
@@ -63,12 +64,12 @@ async fn matchcosts(ctx: &Context, msg: &Message, args: Args) -> CommandResult {
         let mut users = HashMap::new();
         let data = ctx.data.read().await;
         let osu = data.get::<Osu>().unwrap();
+        let cache = data.get::<OsuCache>().unwrap();
         for game in osu_match.games.iter() {
             #[allow(clippy::map_entry)]
             for score in game.scores.iter() {
                 if !users.contains_key(&score.user_id) {
-                    let req = UserRequest::with_user_id(score.user_id);
-                    let name = match req.queue_single(&osu).await {
+                    let name = match cache.user_by_id(osu, score.user_id, GameMode::STD).await {
                         Ok(result) => match result {
                             Some(user) => user.username,
                             None => score.user_id.to_string(),
@@ -1,12 +1,13 @@
 use crate::{
     arguments::NameArgs,
     embeds::{EmbedData, MostPlayedEmbed},
+    osu_cache::OsuCache,
     pagination::{MostPlayedPagination, Pagination},
     util::{globals::OSU_API_ISSUE, numbers, MessageExt},
     DiscordLinks, Osu, Scraper,
 };
 
-use rosu::backend::requests::UserRequest;
+use rosu::models::GameMode;
 use serenity::{
     framework::standard::{macros::command, Args, CommandResult},
     model::channel::Message,
@@ -44,11 +45,11 @@ async fn mostplayed(ctx: &Context, msg: &Message, args: Args) -> CommandResult {
 
     // Retrieve the user
     let (user, maps) = {
-        let user_req = UserRequest::with_username(&name);
         let data = ctx.data.read().await;
         let user = {
             let osu = data.get::<Osu>().unwrap();
-            match user_req.queue_single(&osu).await {
+            let cache = data.get::<OsuCache>().unwrap();
+            match cache.user(osu, &name, GameMode::STD).await {
                 Ok(result) => match result {
                     Some(user) => user,
                     None => {
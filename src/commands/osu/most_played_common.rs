@@ -1,6 +1,7 @@
 use crate::{
     arguments::MultNameArgs,
     embeds::{EmbedData, MostPlayedCommonEmbed},
+    osu_cache::OsuCache,
     pagination::{MostPlayedCommonPagination, Pagination},
     scraper::MostPlayedMap,
     util::{discord, globals::OSU_API_ISSUE, MessageExt},
@@ -8,7 +9,7 @@ use crate::{
 };
 
 use itertools::Itertools;
-use rosu::{backend::requests::UserRequest, models::User};
+use rosu::models::{GameMode, User};
 use serenity::{
     framework::standard::{macros::command, Args, CommandResult},
     model::prelude::Message,
@@ -82,10 +83,10 @@ async fn mostplayedcommon(ctx: &Context, msg: &Message, args: Args) -> CommandRe
     {
         let data = ctx.data.read().await;
         let osu = data.get::<Osu>().unwrap();
+        let cache = data.get::<OsuCache>().unwrap();
         let scraper = data.get::<Scraper>().unwrap();
         for name in names.iter() {
-            let req = UserRequest::with_username(&name);
-            let user = match req.queue_single(&osu).await {
+            let user = match cache.user(osu, name, GameMode::STD).await {
                 Ok(result) => match result {
                     Some(user) => user,
                     None => {
@@ -1,13 +1,14 @@
 use crate::{
     arguments::{ModSelection, OsuStatsArgs},
     embeds::{EmbedData, OsuStatsGlobalsEmbed},
+    osu_cache::OsuCache,
     pagination::{OsuStatsGlobalsPagination, Pagination},
     scraper::{OsuStatsScore, Scraper},
     util::{globals::OSU_API_ISSUE, numbers, MessageExt},
     DiscordLinks, Osu,
 };
 
-use rosu::{backend::requests::UserRequest, models::GameMode};
+use rosu::models::GameMode;
 use serenity::{
     framework::standard::{macros::command, Args, CommandResult},
     model::prelude::Message,
@@ -34,10 +35,10 @@ async fn osustats_send(mode: GameMode, ctx: &Context, msg: &Message, args: Args)
     };
     let params = args.params;
     let user = {
-        let req = UserRequest::with_username(&params.username).mode(mode);
         let data = ctx.data.read().await;
         let osu = data.get::<Osu>().unwrap();
-        match req.queue_single(osu).await {
+        let cache = data.get::<OsuCache>().unwrap();
+        match cache.user(osu, &params.username, mode).await {
             Ok(Some(user)) => user,
             Ok(None) => {
                 msg.channel_id
@@ -2,16 +2,14 @@ use crate::{
     arguments::NameArgs,
     database::MySQL,
     embeds::{EmbedData, ProfileEmbed},
+    osu_cache::OsuCache,
     scraper::OsuStatsParams,
     util::{globals::OSU_API_ISSUE, numbers, MessageExt},
     DiscordLinks, Osu, Scraper,
 };
 
 use rayon::prelude::*;
-use rosu::{
-    backend::requests::UserRequest,
-    models::{Beatmap, GameMode, GameMods, Score, User},
-};
+use rosu::models::{Beatmap, GameMode, GameMods, Score, User};
 use serenity::{
     framework::standard::{macros::command, Args, CommandError, CommandResult},
     model::{channel::Message, id::ChannelId},
@@ -49,10 +47,10 @@ async fn profile_send(mode: GameMode, ctx: &Context, msg: &Message, args: Args)
 
     // Retrieve the user and its top scores
     let (user, scores): (User, Vec<Score>) = {
-        let user_req = UserRequest::with_username(&name).mode(mode);
         let data = ctx.data.read().await;
         let osu = data.get::<Osu>().unwrap();
-        let user = match user_req.queue_single(&osu).await {
+        let cache = data.get::<OsuCache>().unwrap();
+        let user = match cache.user(osu, &name, mode).await {
             Ok(result) => match result {
                 Some(user) => user,
                 None => {
@@ -73,7 +71,7 @@ async fn profile_send(mode: GameMode, ctx: &Context, msg: &Message, args: Args)
                 return Err(why.to_string().into());
             }
         };
-        let scores = match user.get_top_scores(&osu, 100, mode).await {
+        let scores = match cache.best(osu, &user, mode).await {
             Ok(scores) => scores,
             Err(why) => {
                 msg.channel_id
@@ -104,8 +102,24 @@ async fn profile_send(mode: GameMode, ctx: &Context, msg: &Message, args: Args)
         }
     };
 
+    // Some stats are only available through api v2
+    let extended = {
+        let data = ctx.data.read().await;
+        let scraper = data.get::<Scraper>().unwrap();
+        match scraper.api_v2() {
+            Some(api) => match api.get_user(user.user_id, mode).await {
+                Ok(extended) => Some(extended),
+                Err(why) => {
+                    warn!("Could not get api v2 stats of {}: {}", user.username, why);
+                    None
+                }
+            },
+            None => None,
+        }
+    };
+
     // Accumulate all necessary data
-    let data = ProfileEmbed::new(user, profile_result, globals_count, &ctx.cache).await;
+    let data = ProfileEmbed::new(user, profile_result, globals_count, extended, &ctx.cache).await;
 
     if let Some(msg) = retrieving_msg {
         msg.delete(ctx).await?;
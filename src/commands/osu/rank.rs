@@ -1,15 +1,13 @@
 use crate::{
     arguments::RankArgs,
     embeds::{EmbedData, RankEmbed},
+    osu_cache::OsuCache,
     scraper::Scraper,
     util::{globals::OSU_API_ISSUE, MessageExt},
     DiscordLinks, Osu,
 };
 
-use rosu::{
-    backend::requests::UserRequest,
-    models::{GameMode, Score, User},
-};
+use rosu::models::{GameMode, Score, User};
 use serenity::{
     framework::standard::{macros::command, Args, CommandResult},
     model::prelude::Message,
@@ -72,10 +70,10 @@ async fn rank_send(mode: GameMode, ctx: &Context, msg: &Message, args: Args) ->
         }
     };
     let rank_holder = {
-        let user_req = UserRequest::with_user_id(rank_holder_id).mode(mode);
         let data = ctx.data.read().await;
         let osu = data.get::<Osu>().unwrap();
-        match user_req.queue_single(&osu).await {
+        let cache = data.get::<OsuCache>().unwrap();
+        match cache.user_by_id(osu, rank_holder_id, mode).await {
             Ok(result) => match result {
                 Some(user) => user,
                 None => {
@@ -100,10 +98,10 @@ async fn rank_send(mode: GameMode, ctx: &Context, msg: &Message, args: Args) ->
 
     // Retrieve the user (and its top scores if user has more pp than rank_holder)
     let (user, scores): (User, Vec<Score>) = {
-        let user_req = UserRequest::with_username(&name).mode(mode);
         let data = ctx.data.read().await;
         let osu = data.get::<Osu>().unwrap();
-        let user = match user_req.queue_single(&osu).await {
+        let cache = data.get::<OsuCache>().unwrap();
+        let user = match cache.user(osu, &name, mode).await {
             Ok(result) => match result {
                 Some(user) => user,
                 None => {
@@ -127,7 +125,7 @@ async fn rank_send(mode: GameMode, ctx: &Context, msg: &Message, args: Args) ->
         if user.pp_raw > rank_holder.pp_raw {
             (user, Vec::with_capacity(0))
         } else {
-            let scores = match user.get_top_scores(&osu, 100, mode).await {
+            let scores = match cache.best(osu, &user, mode).await {
                 Ok(scores) => scores,
                 Err(why) => {
                     msg.channel_id
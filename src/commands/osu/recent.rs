@@ -2,17 +2,15 @@ use crate::{
     arguments::NameArgs,
     database::MySQL,
     embeds::{EmbedData, RecentEmbed},
+    osu_cache::OsuCache,
     pagination::{Pagination, RecentPagination},
     util::{globals::OSU_API_ISSUE, MessageExt},
     DiscordLinks, Osu,
 };
 
-use rosu::{
-    backend::requests::{RecentRequest, UserRequest},
-    models::{
-        ApprovalStatus::{Approved, Loved, Qualified, Ranked},
-        GameMode,
-    },
+use rosu::models::{
+    ApprovalStatus::{Approved, Loved, Qualified, Ranked},
+    GameMode,
 };
 use serenity::{
     framework::standard::{macros::command, Args, CommandResult},
@@ -50,12 +48,13 @@ async fn recent_send(mode: GameMode, ctx: &Context, msg: &Message, args: Args) -
         }
     };
 
-    // Retrieve the recent scores
+    // Retrieve the recent scores, the user likely just set a new one so drop cached data
     let scores = {
-        let request = RecentRequest::with_username(&name).mode(mode).limit(50);
         let data = ctx.data.read().await;
         let osu = data.get::<Osu>().unwrap();
-        match request.queue(osu).await {
+        let cache = data.get::<OsuCache>().unwrap();
+        cache.invalidate_user(&name, mode);
+        match cache.recent(osu, &name, mode).await {
             Ok(scores) => scores,
             Err(why) => {
                 msg.channel_id
@@ -78,10 +77,10 @@ async fn recent_send(mode: GameMode, ctx: &Context, msg: &Message, args: Args) -
 
     // Retrieving the score's user
     let user = {
-        let req = UserRequest::with_username(&name).mode(mode);
         let data = ctx.data.read().await;
         let osu = data.get::<Osu>().unwrap();
-        match req.queue_single(&osu).await {
+        let cache = data.get::<OsuCache>().unwrap();
+        match cache.user(osu, &name, mode).await {
             Ok(Some(u)) => u,
             Ok(None) => unreachable!(),
             Err(why) => {
@@ -136,7 +135,8 @@ async fn recent_send(mode: GameMode, ctx: &Context, msg: &Message, args: Args) -
     let best = {
         let data = ctx.data.read().await;
         let osu = data.get::<Osu>().unwrap();
-        match user.get_top_scores(osu, 100, mode).await {
+        let cache = data.get::<OsuCache>().unwrap();
+        match cache.best(osu, &user, mode).await {
             Ok(scores) => scores,
             Err(why) => {
                 msg.channel_id
@@ -13,10 +13,7 @@ use crate::{
 
 use rosu::{
     backend::requests::RecentRequest,
-    models::{
-        ApprovalStatus::{Approved, Loved, Ranked},
-        GameMode, GameMods,
-    },
+    models::{GameMode, GameMods},
 };
 use serenity::{
     framework::standard::{macros::command, Args, CommandResult},
@@ -113,12 +110,7 @@ async fn recent_lb_send(
                         return Err(why.to_string().into());
                     }
                 };
-                (
-                    map.approval_status == Ranked
-                        || map.approval_status == Loved
-                        || map.approval_status == Approved,
-                    map,
-                )
+                (true, map)
             }
         }
     };
@@ -2,11 +2,12 @@ use crate::{
     arguments::NameMapArgs,
     database::MySQL,
     embeds::{EmbedData, ScoresEmbed},
+    osu_cache::OsuCache,
     util::{discord, globals::OSU_API_ISSUE, MessageExt},
     DiscordLinks, Osu,
 };
 
-use rosu::backend::requests::{BeatmapRequest, ScoreRequest, UserRequest};
+use rosu::backend::requests::ScoreRequest;
 use serenity::{
     framework::standard::{macros::command, Args, CommandResult},
     model::prelude::Message,
@@ -77,9 +78,9 @@ async fn scores(ctx: &Context, msg: &Message, args: Args) -> CommandResult {
         match mysql.get_beatmap(map_id).await {
             Ok(map) => map,
             Err(_) => {
-                let map_req = BeatmapRequest::new().map_id(map_id);
                 let osu = data.get::<Osu>().unwrap();
-                match map_req.queue_single(&osu).await {
+                let cache = data.get::<OsuCache>().unwrap();
+                match cache.beatmap(osu, map_id).await {
                     Ok(result) => match result {
                         Some(map) => map,
                         None => {
@@ -115,6 +116,7 @@ async fn scores(ctx: &Context, msg: &Message, args: Args) -> CommandResult {
     let (user, map, scores) = {
         let data = ctx.data.read().await;
         let osu = data.get::<Osu>().unwrap();
+        let cache = data.get::<OsuCache>().unwrap();
         let score_req = ScoreRequest::with_map_id(map_id)
             .username(&name)
             .mode(map.mode);
@@ -129,8 +131,7 @@ async fn scores(ctx: &Context, msg: &Message, args: Args) -> CommandResult {
                 return Err(why.to_string().into());
             }
         };
-        let user_req = UserRequest::with_username(&name).mode(map.mode);
-        let user = match user_req.queue_single(osu).await {
+        let user = match cache.user(osu, &name, map.mode).await {
             Ok(result) => match result {
                 Some(user) => user,
                 None => {
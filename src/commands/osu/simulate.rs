@@ -2,17 +2,12 @@ use crate::{
     arguments::SimulateMapArgs,
     database::MySQL,
     embeds::{EmbedData, SimulateEmbed},
+    osu_cache::OsuCache,
     util::{discord, globals::OSU_API_ISSUE, MessageExt},
     Osu,
 };
 
-use rosu::{
-    backend::requests::BeatmapRequest,
-    models::{
-        ApprovalStatus::{Approved, Loved, Ranked},
-        GameMode,
-    },
-};
+use rosu::models::GameMode;
 use serenity::{
     framework::standard::{macros::command, Args, CommandResult},
     model::prelude::Message,
@@ -73,9 +68,9 @@ async fn simulate(ctx: &Context, msg: &Message, args: Args) -> CommandResult {
         match mysql.get_beatmap(map_id).await {
             Ok(map) => (false, map),
             Err(_) => {
-                let map_req = BeatmapRequest::new().map_id(map_id);
                 let osu = data.get::<Osu>().unwrap();
-                let map = match map_req.queue_single(&osu).await {
+                let cache = data.get::<OsuCache>().unwrap();
+                let map = match cache.beatmap(osu, map_id).await {
                     Ok(result) => match result {
                         Some(map) => map,
                         None => {
@@ -103,12 +98,7 @@ async fn simulate(ctx: &Context, msg: &Message, args: Args) -> CommandResult {
                         return Err(why.to_string().into());
                     }
                 };
-                (
-                    map.approval_status == Ranked
-                        || map.approval_status == Loved
-                        || map.approval_status == Approved,
-                    map,
-                )
+                (true, map)
             }
         }
     };
@@ -6,13 +6,7 @@ use crate::{
     DiscordLinks, Osu,
 };
 
-use rosu::{
-    backend::requests::RecentRequest,
-    models::{
-        ApprovalStatus::{Approved, Loved, Ranked},
-        GameMode,
-    },
-};
+use rosu::{backend::requests::RecentRequest, models::GameMode};
 use serenity::{
     framework::standard::{macros::command, Args, CommandResult},
     model::prelude::Message,
@@ -108,12 +102,7 @@ async fn simulate_recent_send(
                         return Err(why.to_string().into());
                     }
                 };
-                (
-                    map.approval_status == Ranked
-                        || map.approval_status == Loved
-                        || map.approval_status == Approved,
-                    map,
-                )
+                (true, map)
             }
         }
     };
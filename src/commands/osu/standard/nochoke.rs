@@ -2,6 +2,7 @@ use crate::{
     arguments::NameIntArgs,
     database::MySQL,
     embeds::{EmbedData, NoChokeEmbed},
+    osu_cache::OsuCache,
     pagination::{NoChokePagination, Pagination},
     util::{
         globals::OSU_API_ISSUE,
@@ -12,10 +13,7 @@ use crate::{
     DiscordLinks, Osu,
 };
 
-use rosu::{
-    backend::requests::UserRequest,
-    models::{Beatmap, GameMode, Score, User},
-};
+use rosu::models::{Beatmap, GameMode, Score, User};
 use serenity::{
     framework::standard::{macros::command, Args, CommandResult},
     model::{channel::Message, misc::Mentionable},
@@ -58,10 +56,10 @@ async fn nochokes(ctx: &Context, msg: &Message, args: Args) -> CommandResult {
 
     // Retrieve the user and its top scores
     let (user, scores): (User, Vec<Score>) = {
-        let user_req = UserRequest::with_username(&name).mode(GameMode::STD);
         let data = ctx.data.read().await;
         let osu = data.get::<Osu>().unwrap();
-        let user = match user_req.queue_single(&osu).await {
+        let cache = data.get::<OsuCache>().unwrap();
+        let user = match cache.user(osu, &name, GameMode::STD).await {
             Ok(result) => match result {
                 Some(user) => user,
                 None => {
@@ -82,7 +80,7 @@ async fn nochokes(ctx: &Context, msg: &Message, args: Args) -> CommandResult {
                 return Err(why.to_string().into());
             }
         };
-        let scores = match user.get_top_scores(osu, 100, GameMode::STD).await {
+        let scores = match cache.best(osu, &user, GameMode::STD).await {
             Ok(scores) => scores,
             Err(why) => {
                 msg.channel_id
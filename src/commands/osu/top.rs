@@ -2,6 +2,7 @@ use crate::{
     arguments::{ModSelection, TopArgs},
     database::MySQL,
     embeds::{EmbedData, TopEmbed},
+    osu_cache::OsuCache,
     pagination::{Pagination, TopPagination},
     util::{globals::OSU_API_ISSUE, numbers, MessageExt},
     DiscordLinks, Osu,
@@ -9,10 +10,7 @@ use crate::{
 
 use rayon::prelude::*;
 use regex::Regex;
-use rosu::{
-    backend::requests::UserRequest,
-    models::{Beatmap, GameMode, GameMods, Score, User},
-};
+use rosu::models::{Beatmap, GameMode, GameMods, Score, User};
 use serenity::{
     framework::standard::{macros::command, Args, CommandResult},
     model::channel::Message,
@@ -69,10 +67,10 @@ async fn top_send(
 
     // Retrieve the user and its top scores
     let (user, scores): (User, Vec<Score>) = {
-        let user_req = UserRequest::with_username(&name).mode(mode);
         let data = ctx.data.read().await;
         let osu = data.get::<Osu>().unwrap();
-        let user = match user_req.queue_single(&osu).await {
+        let cache = data.get::<OsuCache>().unwrap();
+        let user = match cache.user(osu, &name, mode).await {
             Ok(result) => match result {
                 Some(user) => user,
                 None => {
@@ -93,7 +91,7 @@ async fn top_send(
                 return Err(why.to_string().into());
             }
         };
-        let scores = match user.get_top_scores(&osu, 100, mode).await {
+        let scores = match cache.best(osu, &user, mode).await {
             Ok(scores) => scores,
             Err(why) => {
                 msg.channel_id
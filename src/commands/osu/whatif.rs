@@ -1,14 +1,12 @@
 use crate::{
     arguments::NameFloatArgs,
     embeds::{EmbedData, WhatIfEmbed},
+    osu_cache::OsuCache,
     util::{globals::OSU_API_ISSUE, MessageExt},
     DiscordLinks, Osu,
 };
 
-use rosu::{
-    backend::requests::UserRequest,
-    models::{GameMode, Score, User},
-};
+use rosu::models::{GameMode, Score, User};
 use serenity::{
     framework::standard::{macros::command, Args, CommandResult},
     model::prelude::Message,
@@ -60,10 +58,10 @@ async fn whatif_send(mode: GameMode, ctx: &Context, msg: &Message, args: Args) -
 
     // Retrieve the user and its top scores
     let (user, scores): (User, Vec<Score>) = {
-        let user_req = UserRequest::with_username(&name).mode(mode);
         let data = ctx.data.read().await;
         let osu = data.get::<Osu>().unwrap();
-        let user = match user_req.queue_single(&osu).await {
+        let cache = data.get::<OsuCache>().unwrap();
+        let user = match cache.user(osu, &name, mode).await {
             Ok(result) => match result {
                 Some(user) => user,
                 None => {
@@ -84,7 +82,7 @@ async fn whatif_send(mode: GameMode, ctx: &Context, msg: &Message, args: Args) -
                 return Err(why.to_string().into());
             }
         };
-        let scores = match user.get_top_scores(&osu, 100, mode).await {
+        let scores = match cache.best(osu, &user, mode).await {
             Ok(scores) => scores,
             Err(why) => {
                 msg.channel_id
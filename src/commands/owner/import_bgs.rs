@@ -0,0 +1,94 @@
+use crate::{
+    util::{bg_import, MessageExt},
+    MySQL, Osu,
+};
+
+use rosu::models::GameMode;
+use serenity::{
+    framework::standard::{macros::command, Args, CommandResult},
+    model::prelude::Message,
+    prelude::Context,
+};
+use std::{path::Path, sync::Arc};
+
+#[command]
+#[description = "Import many backgrounds for the background game at once.\n\
+Either attach a zip file or give the path to a directory or zip file on the host.\n\
+All images must be named after their mapset id, e.g. 948199.png"]
+#[usage = "[std / mna] [path]"]
+#[aliases("bgimport")]
+async fn importbgs(ctx: &Context, msg: &Message, mut args: Args) -> CommandResult {
+    // Parse first argument as mode if possible
+    let mode = match args.current().map(str::to_lowercase).as_deref() {
+        Some("mna") | Some("mania") | Some("m") => {
+            args.advance();
+            GameMode::MNA
+        }
+        Some("osu") | Some("std") | Some("standard") | Some("o") => {
+            args.advance();
+            GameMode::STD
+        }
+        _ => GameMode::STD,
+    };
+    let path = args.rest().trim().to_owned();
+    let zip = msg
+        .attachments
+        .iter()
+        .find(|attachment| attachment.filename.to_lowercase().ends_with(".zip"));
+    if zip.is_none() && path.is_empty() {
+        msg.channel_id
+            .say(
+                ctx,
+                "You must either attach a zip file or give the path to a directory or zip file",
+            )
+            .await?
+            .reaction_delete(ctx, msg.author.id)
+            .await;
+        return Ok(());
+    }
+    let bytes = match zip {
+        Some(attachment) => match attachment.download().await {
+            Ok(bytes) => Some(bytes),
+            Err(why) => {
+                msg.channel_id
+                    .say(ctx, "Error while downloading the zip file")
+                    .await?
+                    .reaction_delete(ctx, msg.author.id)
+                    .await;
+                return Err(why.to_string().into());
+            }
+        },
+        None => None,
+    };
+    msg.channel_id
+        .say(ctx, format!("Importing backgrounds ({})...", mode))
+        .await?;
+    // Don't hold the data lock during the whole import
+    let (mysql, osu) = {
+        let data = ctx.data.read().await;
+        let mysql = Arc::clone(data.get::<MySQL>().unwrap());
+        let osu = Arc::clone(data.get::<Osu>().unwrap());
+        (mysql, osu)
+    };
+    let report = match bytes {
+        Some(bytes) => bg_import::import_zip(bytes, mode, &mysql, &osu).await,
+        None => bg_import::import_backgrounds(Path::new(&path), mode, &mysql, &osu).await,
+    };
+    let report = match report {
+        Ok(report) => report,
+        Err(why) => {
+            msg.channel_id
+                .say(ctx, format!("Import failed: {}", why))
+                .await?;
+            return Err(why.to_string().into());
+        }
+    };
+    let content = report.to_string();
+    msg.channel_id
+        .send_message(ctx, |m| {
+            m.content(report.summary())
+                .add_file((content.as_bytes(), "bg_import.txt"))
+        })
+        .await?;
+    Ok(())
+}
@@ -1,7 +1,8 @@
 mod add_bg;
+mod import_bgs;
 mod reload_verified;
 
-pub use self::{add_bg::*, reload_verified::*};
+pub use self::{add_bg::*, import_bgs::*, reload_verified::*};
 
 use serenity::framework::standard::macros::group;
 
@@ -9,5 +10,5 @@ use serenity::framework::standard::macros::group;
 #[owners_only]
 #[help_available(false)]
 #[description = "Commands for the owners only"]
-#[commands(reloadverified, addbg)]
+#[commands(reloadverified, addbg, importbgs)]
 struct Owner;
@@ -1,6 +1,6 @@
 use crate::{
-    commands::checks::*, database::StreamTrack, util::MessageExt, MySQL, StreamTracks, Twitch,
-    TwitchUsers,
+    commands::checks::*, database::StreamTrack, streams::Platform, util::MessageExt, MySQL,
+    StreamPlatforms, StreamTracks, StreamUsers,
 };
 
 use serenity::{
@@ -11,69 +11,92 @@ use serenity::{
 
 #[command]
 #[checks(Authority)]
-#[description = "Let me notify this channel whenever the given twitch stream comes online"]
+#[description = "Let me notify this channel whenever the given stream comes online.\n\
+The platform can be `twitch` or `youtube`, defaults to twitch.\n\
+For youtube, either give the channel id or the channel's username."]
 #[aliases("streamadd")]
-#[usage = "[stream name]"]
+#[usage = "[twitch / youtube] [stream name]"]
 #[example = "loltyler1"]
+#[example = "youtube UCt7vEeGvS9v0ygb1xyL1Dig"]
 async fn addstream(ctx: &Context, msg: &Message, mut args: Args) -> CommandResult {
     // Parse the platform and stream name
-    let name = if args.is_empty() {
-        msg.channel_id
-            .say(ctx, "The first argument must be the name of the stream.")
-            .await?
-            .reaction_delete(ctx, msg.author.id)
-            .await;
-        return Ok(());
-    } else {
-        let name = args.single::<String>()?.to_lowercase();
-        let (twitch_id, insert) = {
-            let data = ctx.data.read().await;
-            let twitch_users = data.get::<TwitchUsers>().unwrap();
-            if twitch_users.contains_key(&name) {
-                (*twitch_users.get(&name).unwrap(), false)
-            } else {
-                let twitch = data.get::<Twitch>().unwrap();
-                let twitch_id = match twitch.get_user(&name).await {
-                    Ok(user) => user.user_id,
-                    Err(_) => {
-                        msg.channel_id
-                            .say(&ctx.http, format!("Twitch user `{}` was not found", name))
-                            .await?;
-                        return Ok(());
-                    }
-                };
-                let mysql = data.get::<MySQL>().unwrap();
-                match mysql.add_twitch_user(twitch_id, &name).await {
-                    Ok(_) => debug!("Inserted into twitch_users table"),
-                    Err(why) => warn!("Error while adding twitch user: {}", why),
+    let platform = parse_platform(&mut args);
+    let name = match args.single::<String>() {
+        Ok(name) => name,
+        Err(_) => {
+            msg.channel_id
+                .say(ctx, "The first argument must be the name of the stream.")
+                .await?
+                .reaction_delete(ctx, msg.author.id)
+                .await;
+            return Ok(());
+        }
+    };
+    let key = (platform, name.to_lowercase());
+    let (user_id, insert) = {
+        let data = ctx.data.read().await;
+        let stream_users = data.get::<StreamUsers>().unwrap();
+        if let Some(user_id) = stream_users.get(&key) {
+            (user_id.clone(), false)
+        } else {
+            let client = match data.get::<StreamPlatforms>().unwrap().get(&platform) {
+                Some(client) => client,
+                None => {
+                    msg.channel_id
+                        .say(
+                            ctx,
+                            format!("Tracking {} streams is currently not available", platform),
+                        )
+                        .await?
+                        .reaction_delete(ctx, msg.author.id)
+                        .await;
+                    return Ok(());
+                }
+            };
+            let user_id = match client.get_user(&name).await {
+                Ok(user) => user.user_id,
+                Err(_) => {
+                    msg.channel_id
+                        .say(ctx, format!("Could not find {} user `{}`", platform, name))
+                        .await?;
+                    return Ok(());
                 }
-                (twitch_id, true)
+            };
+            let mysql = data.get::<MySQL>().unwrap();
+            match mysql.add_stream_user(platform, &user_id, &key.1).await {
+                Ok(_) => debug!("Inserted into stream_users table"),
+                Err(why) => warn!("Error while adding stream user: {}", why),
+            }
+            if let Err(why) = client.subscribe(&user_id).await {
+                warn!("Error while subscribing to {} stream: {}", platform, why);
             }
-        };
+            (user_id, true)
+        }
+    };
+    {
         let mut data = ctx.data.write().await;
         if insert {
-            let twitch_users = data.get_mut::<TwitchUsers>().unwrap();
-            twitch_users.insert(name.clone(), twitch_id);
+            let stream_users = data.get_mut::<StreamUsers>().unwrap();
+            stream_users.insert(key, user_id.clone());
         }
         let stream_tracks = data.get_mut::<StreamTracks>().unwrap();
-        let track = StreamTrack::new(msg.channel_id.0, twitch_id);
-        if stream_tracks.insert(track) {
+        let track = StreamTrack::new(msg.channel_id.0, user_id, platform);
+        if stream_tracks.insert(track.clone()) {
             let mysql = data.get::<MySQL>().unwrap();
-            match mysql.add_stream_track(msg.channel_id.0, twitch_id).await {
+            match mysql.add_stream_track(&track).await {
                 Ok(_) => debug!("Inserted into stream_tracks table"),
                 Err(why) => warn!("Error while adding stream track: {}", why),
             }
         }
-        name
-    };
+    }
 
     // Sending the msg
     msg.channel_id
         .say(
             ctx,
             format!(
-                "I'm now tracking `{}`'s twitch stream in this channel",
-                name
+                "I'm now tracking `{}`'s {} stream in this channel",
+                name, platform
             ),
         )
         .await?
@@ -81,3 +104,17 @@ async fn addstream(ctx: &Context, msg: &Message, mut args: Args) -> CommandResul
         .await;
     Ok(())
 }
+
+/// Parse the first argument as platform if it's followed by a name, defaults to twitch
+pub fn parse_platform(args: &mut Args) -> Platform {
+    if args.len() < 2 {
+        return Platform::Twitch;
+    }
+    match args.current().map(str::parse) {
+        Some(Ok(platform)) => {
+            args.advance();
+            platform
+        }
+        _ => Platform::Twitch,
+    }
+}
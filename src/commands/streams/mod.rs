@@ -1,16 +1,27 @@
 pub mod addstream;
 pub mod allstreams;
+pub mod osu_streams;
 pub mod removestream;
+pub mod streamconfig;
 pub mod tracked;
 
 pub use addstream::*;
 pub use allstreams::*;
+pub use osu_streams::*;
 pub use removestream::*;
+pub use streamconfig::*;
 pub use tracked::*;
 
 use serenity::framework::standard::macros::group;
 
 #[group]
-#[description = "Commands for tracking Twitch and Mixer streams"]
-#[commands(addstream, removestream, trackedstreams, allstreams)]
+#[description = "Commands for tracking Twitch and YouTube streams"]
+#[commands(
+    addstream,
+    removestream,
+    streamconfig,
+    trackedstreams,
+    allstreams,
+    osustreams
+)]
 struct StreamTracking;
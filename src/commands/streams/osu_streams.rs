@@ -0,0 +1,134 @@
+use crate::{
+    arguments::{StreamFilterArgs, StreamSortBy},
+    embeds::{EmbedData, OsuStreamsEmbed},
+    pagination::{OsuStreamsPagination, Pagination},
+    util::{globals::GENERAL_ISSUE, numbers, MessageExt},
+    StreamPlatforms, StreamTracks,
+};
+
+use serenity::{
+    framework::standard::{macros::command, Args, CommandResult},
+    model::prelude::Message,
+    prelude::Context,
+};
+use std::{cmp::Reverse, collections::HashSet, sync::Arc};
+
+#[command]
+#[only_in("guild")]
+#[description = "List the osu! streams that are currently live.\n\
+`-lang` only shows streams of the given language.\n\
+`-v` filters by viewers, either `min`, `min..max`, or `..max`.\n\
+`-t` only shows streams whose title contains the word, can be given multiple times.\n\
+`--tracked` only shows streams that are tracked in this server.\n\
+Sorted by viewers by default, `--uptime` sorts by how long the streams are live."]
+#[usage = "[-lang language] [-v viewers] [-t title word] [--tracked] [--uptime]"]
+#[example = "-lang en -v 100.. --uptime"]
+#[example = "-t tournament -t owc"]
+#[aliases("livestreams")]
+async fn osustreams(ctx: &Context, msg: &Message, args: Args) -> CommandResult {
+    let args = match StreamFilterArgs::new(args) {
+        Ok(args) => args,
+        Err(err_msg) => {
+            msg.channel_id
+                .say(ctx, err_msg)
+                .await?
+                .reaction_delete(ctx, msg.author.id)
+                .await;
+            return Ok(());
+        }
+    };
+
+    // Retrieve the streams and which of them are tracked in this server
+    let (mut streams, tracked) = {
+        let data = ctx.data.read().await;
+        let platforms = data.get::<StreamPlatforms>().unwrap();
+        if platforms.is_empty() {
+            msg.channel_id
+                .say(ctx, "Listing live streams is currently not available")
+                .await?
+                .reaction_delete(ctx, msg.author.id)
+                .await;
+            return Ok(());
+        }
+        let mut streams = Vec::new();
+        let mut failed = 0;
+        for (platform, client) in platforms.iter() {
+            match client.get_osu_streams(args.language.as_deref()).await {
+                Ok(platform_streams) => streams.extend(platform_streams),
+                // Still show the streams of the other platforms
+                Err(why) => {
+                    warn!("Error while retrieving {} streams: {}", platform, why);
+                    failed += 1;
+                }
+            }
+        }
+        if failed == platforms.len() {
+            msg.channel_id.say(ctx, GENERAL_ISSUE).await?;
+            return Ok(());
+        }
+        let tracked: HashSet<_> = if args.tracked {
+            let channels: HashSet<_> = match msg.guild_id.unwrap().to_guild_cached(ctx).await {
+                Some(guild) => guild.channels.keys().map(|channel| channel.0).collect(),
+                None => HashSet::new(),
+            };
+            data.get::<StreamTracks>()
+                .unwrap()
+                .iter()
+                .filter(|track| channels.contains(&track.channel_id))
+                .map(|track| (track.platform, track.user_id.clone()))
+                .collect()
+        } else {
+            HashSet::new()
+        };
+        (streams, tracked)
+    };
+
+    // Apply filters and sorting
+    streams.retain(|stream| {
+        args.matches(stream)
+            && (!args.tracked || tracked.contains(&(stream.platform, stream.user_id.clone())))
+    });
+    match args.sort_by {
+        StreamSortBy::Viewers => streams.sort_by_key(|stream| Reverse(stream.viewer_count)),
+        // Streams without start date come last
+        StreamSortBy::Uptime => {
+            streams.sort_by_key(|stream| (stream.started_at.is_none(), stream.started_at))
+        }
+    }
+    if streams.is_empty() {
+        msg.channel_id
+            .say(ctx, "No live osu! streams match the given filters")
+            .await?
+            .reaction_delete(ctx, msg.author.id)
+            .await;
+        return Ok(());
+    }
+
+    // Prepare initial page
+    let pages = numbers::div_euclid(10, streams.len());
+    let end = streams.len().min(10);
+    let data = OsuStreamsEmbed::new(&streams[..end], 1, (1, pages), streams.len());
+
+    // Creating the embed
+    let resp = msg
+        .channel_id
+        .send_message(&ctx.http, |m| m.embed(|e| data.build(e)))
+        .await?;
+
+    // Skip pagination if too few entries
+    if streams.len() <= 10 {
+        resp.reaction_delete(ctx, msg.author.id).await;
+        return Ok(());
+    }
+
+    // Pagination
+    let pagination = OsuStreamsPagination::new(ctx, resp, msg.author.id, streams).await;
+    let cache = Arc::clone(&ctx.cache);
+    let http = Arc::clone(&ctx.http);
+    tokio::spawn(async move {
+        if let Err(why) = pagination.start(cache, http).await {
+            warn!("Pagination error: {}", why)
+        }
+    });
+    Ok(())
+}
@@ -1,7 +1,10 @@
 use crate::{
-    commands::checks::*, database::StreamTrack, util::MessageExt, MySQL, StreamTracks, TwitchUsers,
+    commands::checks::*, database::StreamTrack, util::MessageExt, MySQL, StreamPlatforms,
+    StreamTracks, StreamUsers,
 };
 
+use super::parse_platform;
+
 use serenity::{
     framework::standard::{macros::command, Args, CommandResult},
     model::prelude::Message,
@@ -10,44 +13,70 @@ use serenity::{
 
 #[command]
 #[checks(Authority)]
-#[description = "Let me no longer notify this channel when the given twitch stream comes online"]
+#[description = "Let me no longer notify this channel when the given stream comes online.\n\
+The platform can be `twitch` or `youtube`, defaults to twitch."]
 #[aliases("streamremove")]
-#[usage = "[stream name]"]
+#[usage = "[twitch / youtube] [stream name]"]
 #[example = "loltyler1"]
 async fn removestream(ctx: &Context, msg: &Message, mut args: Args) -> CommandResult {
     // Parse the platform and stream name
-    let result = if args.is_empty() {
-        msg.channel_id
-            .say(ctx, "The first argument must be the name of the stream.")
-            .await?
-            .reaction_delete(ctx, msg.author.id)
-            .await;
-        return Ok(());
-    } else {
-        let name = args.single::<String>()?.to_lowercase();
-        let data = ctx.data.read().await;
-        let twitch_users = data.get::<TwitchUsers>().unwrap();
-        if twitch_users.contains_key(&name) {
-            let twitch_id = *twitch_users.get(&name).unwrap();
-            std::mem::drop(data);
-            let mut data = ctx.data.write().await;
-            let stream_tracks = data.get_mut::<StreamTracks>().unwrap();
-            let track = StreamTrack::new(msg.channel_id.0, twitch_id);
-            if stream_tracks.remove(&track) {
-                let mysql = data.get::<MySQL>().unwrap();
-                if let Err(why) = mysql.remove_stream_track(msg.channel_id.0, twitch_id).await {
-                    warn!("Error while removing stream track: {}", why);
+    let platform = parse_platform(&mut args);
+    let name = match args.single::<String>() {
+        Ok(name) => name.to_lowercase(),
+        Err(_) => {
+            msg.channel_id
+                .say(ctx, "The first argument must be the name of the stream.")
+                .await?
+                .reaction_delete(ctx, msg.author.id)
+                .await;
+            return Ok(());
+        }
+    };
+    // The user id if the track was removed and whether it was the user's last track
+    let removed = {
+        let mut data = ctx.data.write().await;
+        let user_id = data
+            .get::<StreamUsers>()
+            .unwrap()
+            .get(&(platform, name.clone()))
+            .cloned();
+        match user_id {
+            Some(user_id) => {
+                let stream_tracks = data.get_mut::<StreamTracks>().unwrap();
+                let track = StreamTrack::new(msg.channel_id.0, user_id, platform);
+                if stream_tracks.remove(&track) {
+                    let last_track = !stream_tracks
+                        .iter()
+                        .any(|other| other.platform == platform && other.user_id == track.user_id);
+                    let mysql = data.get::<MySQL>().unwrap();
+                    if let Err(why) = mysql.remove_stream_track(&track).await {
+                        warn!("Error while removing stream track: {}", why);
+                    }
+                    Some((track.user_id, last_track))
+                } else {
+                    None
                 }
             }
-            Some(name)
-        } else {
-            None
+            None => None,
         }
     };
-    let content = if let Some(name) = result {
+
+    // Stop receiving events of streams that are no longer tracked anywhere
+    if let Some((ref user_id, true)) = removed {
+        let data = ctx.data.read().await;
+        if let Some(client) = data.get::<StreamPlatforms>().unwrap().get(&platform) {
+            if let Err(why) = client.unsubscribe(user_id).await {
+                warn!(
+                    "Error while unsubscribing from {} stream: {}",
+                    platform, why
+                );
+            }
+        }
+    }
+    let content = if removed.is_some() {
         format!(
-            "I'm no longer tracking `{}`'s twitch stream in this channel",
-            name
+            "I'm no longer tracking `{}`'s {} stream in this channel",
+            name, platform
         )
     } else {
         "That stream wasn't tracked anyway".to_string()
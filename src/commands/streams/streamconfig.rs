@@ -0,0 +1,162 @@
+use crate::{
+    arguments::parse_role, commands::checks::*, database::StreamTrack, streams::validate_template,
+    util::MessageExt, MySQL, StreamTracks, StreamUsers,
+};
+
+use super::parse_platform;
+
+use serenity::{
+    framework::standard::{macros::command, Args, CommandResult},
+    model::{id::RoleId, prelude::Message},
+    prelude::Context,
+};
+use std::fmt::Write;
+
+#[command]
+#[only_in("guild")]
+#[checks(Authority)]
+#[description = "Customize the notification of a stream that is tracked in this channel.\n\
+Without setting, the current configuration is shown.\n\
+`role`: Role to mention in the notification, `none` to not mention any.\n\
+`template`: Message to send along the notification, `none` to only send the embed. \
+It can contain the placeholders `{name}`, `{title}`, `{game}`, `{url}`, and `{viewers}`."]
+#[usage = "[twitch / youtube] [stream name] [role / template] [value]"]
+#[example = "loltyler1 role @StreamAlerts"]
+#[example = "loltyler1 template {name} is live playing {game}: {url}"]
+#[aliases("streamconf")]
+async fn streamconfig(ctx: &Context, msg: &Message, mut args: Args) -> CommandResult {
+    // Parse the platform and stream name
+    let platform = parse_platform(&mut args);
+    let name = match args.single::<String>() {
+        Ok(name) => name.to_lowercase(),
+        Err(_) => {
+            msg.channel_id
+                .say(ctx, "The first argument must be the name of the stream.")
+                .await?
+                .reaction_delete(ctx, msg.author.id)
+                .await;
+            return Ok(());
+        }
+    };
+    let track = {
+        let data = ctx.data.read().await;
+        let user_id = data
+            .get::<StreamUsers>()
+            .unwrap()
+            .get(&(platform, name.clone()))
+            .cloned();
+        user_id.and_then(|user_id| {
+            let track = StreamTrack::new(msg.channel_id.0, user_id, platform);
+            data.get::<StreamTracks>().unwrap().get(&track).cloned()
+        })
+    };
+    let mut track = match track {
+        Some(track) => track,
+        None => {
+            msg.channel_id
+                .say(ctx, "That stream isn't tracked in this channel")
+                .await?
+                .reaction_delete(ctx, msg.author.id)
+                .await;
+            return Ok(());
+        }
+    };
+
+    // Apply the setting
+    let setting = args.single::<String>().ok().map(|arg| arg.to_lowercase());
+    let value = args.rest().trim();
+    let result = match setting.as_deref() {
+        None => Ok(false),
+        Some("role") | Some("r") => match value {
+            "" => Err("You must specify a role or `none`".to_owned()),
+            "none" => {
+                track.role_id = None;
+                Ok(true)
+            }
+            _ => {
+                let guild = msg.guild_id.unwrap().to_guild_cached(&ctx.cache).await;
+                let role = parse_role(value).filter(|role| {
+                    guild
+                        .as_ref()
+                        .map_or(false, |guild| guild.roles.contains_key(role))
+                });
+                match role {
+                    Some(role) => {
+                        track.role_id = Some(role.0);
+                        Ok(true)
+                    }
+                    None => Err(format!("I don't know what role you mean with `{}`", value)),
+                }
+            }
+        },
+        Some("template") | Some("t") => match value {
+            "" => Err("You must specify a template or `none`".to_owned()),
+            "none" => {
+                track.template = None;
+                Ok(true)
+            }
+            _ => validate_template(value).map(|_| {
+                track.template = Some(value.to_owned());
+                true
+            }),
+        },
+        Some(other) => Err(format!(
+            "Unknown setting `{}`, must be either `role` or `template`",
+            other
+        )),
+    };
+    let changed = match result {
+        Ok(changed) => changed,
+        Err(content) => {
+            msg.channel_id
+                .say(ctx, content)
+                .await?
+                .reaction_delete(ctx, msg.author.id)
+                .await;
+            return Ok(());
+        }
+    };
+
+    // Store the new configuration
+    if changed {
+        let mut data = ctx.data.write().await;
+        let mysql = data.get::<MySQL>().unwrap();
+        if let Err(why) = mysql.set_stream_track_config(&track).await {
+            msg.channel_id
+                .say(ctx, "Some database issue, blame bade")
+                .await?
+                .reaction_delete(ctx, msg.author.id)
+                .await;
+            return Err(why.to_string().into());
+        }
+        let stream_tracks = data.get_mut::<StreamTracks>().unwrap();
+        stream_tracks.replace(track.clone());
+    }
+
+    // Show the configuration without pinging the role
+    let role = match track.role_id {
+        Some(role) => {
+            let guild = msg.guild_id.unwrap().to_guild_cached(&ctx.cache).await;
+            guild
+                .and_then(|guild| guild.roles.get(&RoleId(role)).map(|r| r.name.clone()))
+                .map_or_else(|| role.to_string(), |name| format!("@{}", name))
+        }
+        None => "None".to_owned(),
+    };
+    let mut content = format!(
+        "Notification settings for `{}`'s {} stream in this channel:\nRole: `{}`\nTemplate: ",
+        name, platform, role
+    );
+    match track.template {
+        Some(ref template) => {
+            let _ = write!(content, "```\n{}\n```", template);
+        }
+        None => content.push_str("None"),
+    }
+    msg.channel_id
+        .say(ctx, content)
+        .await?
+        .reaction_delete(ctx, msg.author.id)
+        .await;
+    Ok(())
+}
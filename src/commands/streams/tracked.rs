@@ -1,4 +1,4 @@
-use crate::{util::MessageExt, StreamTracks, TwitchUsers};
+use crate::{util::MessageExt, StreamTracks, StreamUsers};
 
 use itertools::Itertools;
 use rayon::prelude::*;
@@ -12,32 +12,34 @@ use serenity::{
 #[description = "List all streams that are tracked in this channel"]
 #[aliases("tracked")]
 async fn trackedstreams(ctx: &Context, msg: &Message) -> CommandResult {
-    let mut twitch_users: Vec<_> = {
+    let mut stream_users: Vec<_> = {
         let data = ctx.data.read().await;
-        let twitch_users = data.get::<TwitchUsers>().unwrap();
+        let stream_users = data.get::<StreamUsers>().unwrap();
         let tracks = data.get::<StreamTracks>().unwrap();
-        twitch_users
+        stream_users
             .par_iter()
-            .filter(|(_, &twitch_id)| {
-                tracks
-                    .iter()
-                    .any(|track| track.user_id == twitch_id && track.channel_id == msg.channel_id.0)
+            .filter(|((platform, _), user_id)| {
+                tracks.iter().any(|track| {
+                    track.platform == *platform
+                        && &track.user_id == *user_id
+                        && track.channel_id == msg.channel_id.0
+                })
             })
-            .map(|(name, _)| name.clone())
+            .map(|((platform, name), _)| format!("{} ({})", name, platform))
             .collect()
     };
-    twitch_users.sort();
-    let user_str = if twitch_users.is_empty() {
+    stream_users.sort();
+    let user_str = if stream_users.is_empty() {
         "None".to_string()
     } else {
-        twitch_users.into_iter().join("`, `")
+        stream_users.into_iter().join("`, `")
     };
 
     // Sending the msg
     msg.channel_id
         .say(
             ctx,
-            format!("Tracked twitch streams in this channel:\n`{}`", user_str),
+            format!("Tracked streams in this channel:\n`{}`", user_str),
         )
         .await?
         .reaction_delete(ctx, msg.author.id)
@@ -1,4 +1,9 @@
-use crate::{commands::checks::*, database::MySQL, util::MessageExt, Guilds};
+use crate::{
+    commands::checks::*,
+    database::{ModAction, ModLogEntry, MySQL},
+    util::{discord::mod_log, MessageExt},
+    Guilds,
+};
 
 use itertools::Itertools;
 use regex::Regex;
@@ -175,6 +180,9 @@ async fn authorities(ctx: &Context, msg: &Message, mut args: Args) -> CommandRes
             .map(|role| format!("`{}`", role))
             .join(", ")
     };
+    let details = format!("Authority roles: {}", content);
+    let entry = ModLogEntry::new(guild_id.0, ModAction::Authorities, msg.author.id.0, details);
+    mod_log(ctx, entry).await;
     msg.channel_id
         .say(
             ctx,
@@ -1,6 +1,7 @@
 use crate::{
     arguments::{DiscordUserArgs, NameArgs},
     embeds::{AvatarEmbed, EmbedData},
+    osu_cache::OsuCache,
     util::{
         globals::{AVATAR_URL, OSU_API_ISSUE},
         MessageExt,
@@ -8,7 +9,7 @@ use crate::{
     Osu,
 };
 
-use rosu::backend::UserRequest;
+use rosu::models::GameMode;
 use serenity::{
     framework::standard::{macros::command, Args, CommandResult},
     model::prelude::Message,
@@ -72,10 +73,10 @@ pub async fn osu(ctx: &Context, msg: &Message, args: Args) -> CommandResult {
         }
     };
     let user = {
-        let req = UserRequest::with_username(&name);
         let data = ctx.data.read().await;
         let osu = data.get::<Osu>().unwrap();
-        match req.queue_single(&osu).await {
+        let cache = data.get::<OsuCache>().unwrap();
+        match cache.user(osu, &name, GameMode::STD).await {
             Ok(user) => match user {
                 Some(user) => user,
                 None => {
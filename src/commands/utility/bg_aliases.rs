@@ -0,0 +1,165 @@
+use crate::{
+    commands::checks::*,
+    database::{AliasKind, BgAlias},
+    util::{globals::HOMEPAGE, MessageExt},
+    MySQL,
+};
+
+use serenity::{
+    framework::standard::{macros::command, Args, CommandResult},
+    model::channel::Message,
+    prelude::Context,
+};
+use std::fmt::Write;
+
+#[command]
+#[description = "Show the alternative titles and artists of a background for the bg game.\n\
+These are accepted as answer just like the actual title and artist.\n\
+To add one, use `<bgalias add`."]
+#[usage = "[mapset id]"]
+#[example = "1111"]
+#[aliases("bga", "bgaliases")]
+#[sub_commands("add")]
+async fn bgalias(ctx: &Context, msg: &Message, mut args: Args) -> CommandResult {
+    let mapset_id = match args.single::<u32>() {
+        Ok(id) => id,
+        Err(_) => {
+            msg.channel_id
+                .say(
+                    ctx,
+                    "Could not parse mapset id. Be sure to specify it as first argument",
+                )
+                .await?
+                .reaction_delete(ctx, msg.author.id)
+                .await;
+            return Ok(());
+        }
+    };
+    send_aliases(ctx, msg, mapset_id).await
+}
+
+#[command]
+#[checks(BgVerified)]
+#[description = "Add an alternative title or artist to a background for the bg game.\n\
+First argument must be the mapset id, second argument must be either \
+`t` or `title`, or `a` or `artist`. Everything after that is the alias."]
+#[usage = "[mapset id] [title/t/artist/a] [alias]"]
+#[example = "1111 title senbonzakura"]
+async fn add(ctx: &Context, msg: &Message, mut args: Args) -> CommandResult {
+    if args.is_empty() {
+        msg.channel_id
+            .say(
+                ctx,
+                "Arguments: `[mapset id] [title/t/artist/a] [alias]`\n\
+                Example: `1111 title senbonzakura`",
+            )
+            .await?
+            .reaction_delete(ctx, msg.author.id)
+            .await;
+        return Ok(());
+    }
+    // Parse mapset id
+    let mapset_id = match args.single::<u32>() {
+        Ok(id) => id,
+        Err(_) => {
+            msg.channel_id
+                .say(
+                    ctx,
+                    "Could not parse mapset id. Be sure to specify it as first argument",
+                )
+                .await?
+                .reaction_delete(ctx, msg.author.id)
+                .await;
+            return Ok(());
+        }
+    };
+    // Parse alias kind
+    let kind = match args.single::<AliasKind>() {
+        Ok(kind) => kind,
+        Err(_) => {
+            msg.channel_id
+                .say(
+                    ctx,
+                    "Could not parse alias kind. \
+                    Be sure to specify `t`, `title`, `a`, or `artist` as second argument",
+                )
+                .await?
+                .reaction_delete(ctx, msg.author.id)
+                .await;
+            return Ok(());
+        }
+    };
+    let alias = args.rest().trim();
+    if alias.is_empty() {
+        msg.channel_id
+            .say(ctx, "You must provide an alias after the alias kind")
+            .await?
+            .reaction_delete(ctx, msg.author.id)
+            .await;
+        return Ok(());
+    }
+    {
+        let data = ctx.data.read().await;
+        let mysql = data.get::<MySQL>().unwrap();
+        if mysql.get_tags_mapset(mapset_id).await.is_err() {
+            msg.channel_id
+                .say(ctx, "No background entry found with this id")
+                .await?
+                .reaction_delete(ctx, msg.author.id)
+                .await;
+            return Ok(());
+        }
+        let aliases = [(kind, alias.to_owned())];
+        if let Err(why) = mysql
+            .add_bg_aliases(mapset_id, &aliases, Some(msg.author.id.0))
+            .await
+        {
+            msg.channel_id
+                .say(ctx, "Some database issue, blame bade")
+                .await?
+                .reaction_delete(ctx, msg.author.id)
+                .await;
+            return Err(why.to_string().into());
+        }
+    }
+    send_aliases(ctx, msg, mapset_id).await
+}
+
+async fn send_aliases(ctx: &Context, msg: &Message, mapset_id: u32) -> CommandResult {
+    let aliases = {
+        let data = ctx.data.read().await;
+        let mysql = data.get::<MySQL>().unwrap();
+        match mysql.get_bg_aliases(mapset_id).await {
+            Ok(aliases) => aliases,
+            Err(why) => {
+                msg.channel_id
+                    .say(ctx, "Some database issue, blame bade")
+                    .await?
+                    .reaction_delete(ctx, msg.author.id)
+                    .await;
+                return Err(why.to_string().into());
+            }
+        }
+    };
+    let mut content = format!("Aliases of {}beatmapsets/{}:", HOMEPAGE, mapset_id);
+    if aliases.is_empty() {
+        content.push_str("\nNone");
+    }
+    for BgAlias {
+        kind,
+        alias,
+        added_by,
+    } in aliases
+    {
+        let _ = write!(content, "\n`{}` {}", kind, alias);
+        if added_by.is_none() {
+            content.push_str(" *(osu!api)*");
+        }
+    }
+    msg.channel_id
+        .say(ctx, content)
+        .await?
+        .reaction_delete(ctx, msg.author.id)
+        .await;
+    Ok(())
+}
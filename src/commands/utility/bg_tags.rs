@@ -26,17 +26,18 @@ use tokio::{fs, stream::StreamExt};
 #[checks(BgVerified)]
 #[description = "Manage the tags of a background for the bg game.\n\
 First argument must be the mapset id, second argument must be either \
-`a` or `add` to add tags, or `r` or `remove` to remove them. \n\
+`a` or `add` to add tags, `r` or `remove` to remove them, \
+`l` or `lock` to prevent votes from changing them, or `u` or `unlock` to allow it again. \n\
 After that provide any of these pre-selected keywords:\n\
 `farm, streams, alternate, old, meme, hardname, easy, hard, tech, weeb, bluesky, english`\n\
 By default, all tags are marked as **true**, so removing them will be more important."]
-#[usage = "[mapset id] [add/a/remove/r] [list of tags]"]
+#[usage = "[mapset id] [add/a/remove/r/lock/l/unlock/u] [list of tags]"]
 #[example = "21662 r hard farm streams alternate hardname tech weeb bluesky"]
 #[aliases("bgtm", "bgtagmanual")]
 async fn bgtagsmanual(ctx: &Context, msg: &Message, mut args: Args) -> CommandResult {
     if args.is_empty() {
         msg.channel_id
-            .say(ctx, "Arguments: `[mapset id] [add/a/remove/r] [list of tags]`\n\
+            .say(ctx, "Arguments: `[mapset id] [add/a/remove/r/lock/l/unlock/u] [list of tags]`\n\
             Example: `21662 r hard farm streams alternate hardname tech weeb bluesky`\n\
             Tags: `farm, streams, alternate, old, meme, hardname, easy, hard, tech, weeb, bluesky, english`")
             .await?
@@ -79,7 +80,8 @@ async fn bgtagsmanual(ctx: &Context, msg: &Message, mut args: Args) -> CommandRe
                 .say(
                     ctx,
                     "Could not parse action. \
-                    Be sure to specify `r`, `remove`, `a`, or `add` as second argument",
+                    Be sure to specify `r`, `remove`, `a`, `add`, \
+                    `l`, `lock`, `u`, or `unlock` as second argument",
                 )
                 .await?
                 .reaction_delete(ctx, msg.author.id)
@@ -113,26 +115,38 @@ async fn bgtagsmanual(ctx: &Context, msg: &Message, mut args: Args) -> CommandRe
     }
     let data = ctx.data.read().await;
     let mysql = data.get::<MySQL>().unwrap();
-    let result = if tags.is_empty() {
-        mysql.get_tags_mapset(mapset_id).await
+    let update = if tags.is_empty() {
+        Ok(())
     } else {
-        match mysql
-            .set_tags_mapset(mapset_id, tags, action == Action::Add)
-            .await
-        {
-            Ok(_) => mysql.get_tags_mapset(mapset_id).await,
-            Err(why) => Err(why),
+        match action {
+            Action::Add => mysql.set_tags_mapset(mapset_id, tags, true).await,
+            Action::Remove => mysql.set_tags_mapset(mapset_id, tags, false).await,
+            Action::Lock => mysql.lock_bg_tags(mapset_id, tags, msg.author.id.0).await,
+            Action::Unlock => mysql.unlock_bg_tags(mapset_id, tags).await,
         }
     };
+    let result = match update {
+        Ok(_) => match mysql.get_tags_mapset(mapset_id).await {
+            Ok(tags) => mysql
+                .get_bg_tag_locks(mapset_id)
+                .await
+                .map(|locks| (tags, locks)),
+            Err(why) => Err(why),
+        },
+        Err(why) => Err(why),
+    };
     // Then show the final tags
     let response = match result {
-        Ok(tags) => {
+        Ok((tags, locks)) => {
             msg.channel_id
                 .say(
                     ctx,
                     format!(
-                        "{}beatmapsets/{} is now tagged as:\n{}",
-                        HOMEPAGE, mapset_id, tags,
+                        "{}beatmapsets/{} is now tagged as:\n{}\nLocked tags: {}",
+                        HOMEPAGE,
+                        mapset_id,
+                        tags,
+                        locks.join(", "),
                     ),
                 )
                 .await?
@@ -266,24 +280,8 @@ async fn bgtags(ctx: &Context, msg: &Message, mut args: Args) -> CommandResult {
             .removed(true)
             .await;
         // Add reactions
-        let reactions = [
-            "🍋",
-            "🤓",
-            "🤡",
-            "🎨",
-            "🍨",
-            "👨‍🌾",
-            "😱",
-            "🪀",
-            "🟦",
-            "🗽",
-            "🌀",
-            "👴",
-            "💯",
-            "✅",
-            "❌",
-        ];
-        for &reaction in reactions.iter() {
+        let reactions = MapsetTags::EMOJIS.iter().chain(["✅", "❌"].iter());
+        for &reaction in reactions {
             let reaction = ReactionType::try_from(reaction).unwrap();
             response.react(ctx, reaction).await?;
         }
@@ -293,26 +291,16 @@ async fn bgtags(ctx: &Context, msg: &Message, mut args: Args) -> CommandResult {
         while let Some(reaction) = collector.next().await {
             let tag = if let ReactionType::Unicode(ref r) = reaction.as_inner_ref().emoji {
                 match r.as_str() {
-                    "🍋" => MapsetTags::Easy,
-                    "🤓" => MapsetTags::Hard,
-                    "🤡" => MapsetTags::Meme,
-                    "👴" => MapsetTags::Old,
-                    "😱" => MapsetTags::HardName,
-                    "🟦" => MapsetTags::BlueSky,
-                    "🪀" => MapsetTags::Alternate,
-                    "🗽" => MapsetTags::English,
-                    "👨‍🌾" => MapsetTags::Farm,
-                    "💯" => MapsetTags::Tech,
-                    "🎨" => MapsetTags::Weeb,
-                    "🌀" => MapsetTags::Streams,
-                    "🍨" => MapsetTags::Kpop,
                     "✅" => {
                         owner = reaction.as_inner_ref().user_id;
                         break_loop = false;
                         break;
                     }
                     "❌" => break,
-                    _ => continue,
+                    emoji => match MapsetTags::from_emoji(emoji) {
+                        Some(tag) => tag,
+                        None => continue,
+                    },
                 }
             } else {
                 continue;
@@ -392,6 +380,8 @@ async fn get_random_image(mut mapsets: Vec<MapsetTagWrapper>, mode: GameMode) ->
 enum Action {
     Add,
     Remove,
+    Lock,
+    Unlock,
 }
 
 impl FromStr for Action {
@@ -400,6 +390,8 @@ impl FromStr for Action {
         match value.to_lowercase().as_str() {
             "r" | "remove" => Ok(Self::Remove),
             "a" | "add" => Ok(Self::Add),
+            "l" | "lock" => Ok(Self::Lock),
+            "u" | "unlock" => Ok(Self::Unlock),
             _ => Err(()),
         }
     }
@@ -452,6 +444,44 @@ impl FromStr for MapsetTags {
 }
 
 impl MapsetTags {
+    /// Reactions for the tags in the order they are added to messages, see `from_emoji`
+    pub const EMOJIS: [&'static str; 13] = [
+        "🍋",
+        "🤓",
+        "🤡",
+        "🎨",
+        "🍨",
+        "👨‍🌾",
+        "😱",
+        "🪀",
+        "🟦",
+        "🗽",
+        "🌀",
+        "👴",
+        "💯",
+    ];
+
+    /// Tag belonging to the reaction emoji used throughout the bg commands
+    pub fn from_emoji(emoji: &str) -> Option<Self> {
+        let tag = match emoji {
+            "🍋" => Self::Easy,
+            "🤓" => Self::Hard,
+            "🤡" => Self::Meme,
+            "👴" => Self::Old,
+            "😱" => Self::HardName,
+            "🟦" => Self::BlueSky,
+            "🪀" => Self::Alternate,
+            "🗽" => Self::English,
+            "👨‍🌾" => Self::Farm,
+            "💯" => Self::Tech,
+            "🎨" => Self::Weeb,
+            "🌀" => Self::Streams,
+            "🍨" => Self::Kpop,
+            _ => return None,
+        };
+        Some(tag)
+    }
+
     pub fn join(self, separator: impl std::fmt::Display) -> String {
         let mut tags = self.into_iter();
         let first_tag = match tags.next() {
@@ -502,3 +532,21 @@ impl IntoIterator for MapsetTags {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn every_emoji_has_its_own_tag() {
+        let tags = MapsetTags::EMOJIS
+            .iter()
+            .map(|emoji| MapsetTags::from_emoji(emoji).unwrap())
+            .fold(MapsetTags::empty(), |tags, tag| {
+                assert!(!tags.contains(tag));
+                tags | tag
+            });
+        assert_eq!(tags, MapsetTags::all());
+        assert!(MapsetTags::from_emoji("✅").is_none());
+    }
+}
@@ -0,0 +1,104 @@
+use crate::{
+    arguments::parse_channel,
+    commands::checks::*,
+    database::{ModAction, ModLogEntry, MySQL},
+    util::{discord::mod_log, MessageExt},
+    Guilds,
+};
+
+use serenity::{
+    framework::standard::{macros::command, Args, CommandResult},
+    model::{misc::Mentionable, prelude::Message},
+    prelude::Context,
+};
+
+#[command]
+#[only_in("guild")]
+#[checks(Authority)]
+#[description = "Set the channel in which I log administrative actions like `<prune`.\n\
+Without argument the current log channel is shown, `none` stops logging."]
+#[usage = "[channel / none]"]
+#[example = "#mod-log"]
+#[example = "none"]
+#[aliases("modlogchannel")]
+async fn logchannel(ctx: &Context, msg: &Message, args: Args) -> CommandResult {
+    let guild_id = msg.guild_id.unwrap();
+    let arg = args.rest().trim();
+    let channel = match arg {
+        "" => {
+            let data = ctx.data.read().await;
+            let guilds = data.get::<Guilds>().unwrap();
+            // Entry is necessarily available due to authority check, hence unwrap()
+            let content = match guilds.get(&guild_id).unwrap().mod_log {
+                Some(channel) => {
+                    format!("Administrative actions are logged in {}", channel.mention())
+                }
+                None => "There is no log channel in this server".to_owned(),
+            };
+            msg.channel_id
+                .say(ctx, content)
+                .await?
+                .reaction_delete(ctx, msg.author.id)
+                .await;
+            return Ok(());
+        }
+        "none" => None,
+        _ => {
+            let guild = guild_id.to_guild_cached(&ctx.cache).await;
+            let channel = parse_channel(arg).filter(|channel| {
+                guild
+                    .as_ref()
+                    .map_or(false, |guild| guild.channels.contains_key(channel))
+            });
+            match channel {
+                Some(channel) => Some(channel),
+                None => {
+                    msg.channel_id
+                        .say(ctx, format!("There is no channel `{}` in this server", arg))
+                        .await?
+                        .reaction_delete(ctx, msg.author.id)
+                        .await;
+                    return Ok(());
+                }
+            }
+        }
+    };
+    {
+        let mut data = ctx.data.write().await;
+        let mysql = data.get::<MySQL>().unwrap();
+        if let Err(why) = mysql
+            .update_guild_mod_log(guild_id.0, channel.map(|channel| channel.0))
+            .await
+        {
+            msg.channel_id
+                .say(ctx, "Some database issue, blame bade")
+                .await?
+                .reaction_delete(ctx, msg.author.id)
+                .await;
+            return Err(why.to_string().into());
+        }
+        let guilds = data.get_mut::<Guilds>().unwrap();
+        if let Some(guild) = guilds.get_mut(&guild_id) {
+            guild.mod_log = channel;
+        }
+    }
+    let details = match channel {
+        Some(channel) => format!("Actions are logged in {}", channel.mention()),
+        None => "Actions are no longer logged".to_owned(),
+    };
+    let entry = ModLogEntry::new(guild_id.0, ModAction::LogChannel, msg.author.id.0, details);
+    mod_log(ctx, entry).await;
+    let content = match channel {
+        Some(channel) => format!(
+            "Administrative actions will be logged in {}",
+            channel.mention()
+        ),
+        None => "Administrative actions are no longer logged".to_owned(),
+    };
+    msg.channel_id
+        .say(ctx, content)
+        .await?
+        .reaction_delete(ctx, msg.author.id)
+        .await;
+    Ok(())
+}
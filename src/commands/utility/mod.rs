@@ -1,17 +1,24 @@
 mod about;
 mod authorities;
 mod avatar;
+mod bg_aliases;
 mod bg_tags;
 mod command_count;
 mod echo;
+mod log_channel;
 mod lyrics;
+mod mod_log;
+mod permissions;
 mod ping;
 mod prune;
+mod rank_roles;
 mod role_assign;
+mod role_menu;
 
 pub use self::{
-    about::*, authorities::*, avatar::*, bg_tags::*, command_count::*, echo::*, lyrics::*, ping::*,
-    prune::*, role_assign::*,
+    about::*, authorities::*, avatar::*, bg_aliases::*, bg_tags::*, command_count::*, echo::*,
+    log_channel::*, lyrics::*, mod_log::*, permissions::*, ping::*, prune::*, rank_roles::*,
+    role_assign::*, role_menu::*,
 };
 
 use serenity::framework::standard::macros::group;
@@ -25,10 +32,16 @@ use serenity::framework::standard::macros::group;
     avatar,
     echo,
     prune,
+    logchannel,
+    modlog,
     authorities,
+    permissions,
     roleassign,
+    rolemenu,
+    rankroles,
     lyrics,
     bgtagsmanual,
-    bgtags
+    bgtags,
+    bgalias
 )]
 struct Utility;
@@ -0,0 +1,108 @@
+use crate::{
+    arguments::parse_user,
+    commands::checks::*,
+    database::MySQL,
+    embeds::{EmbedData, ModLogHistoryEmbed},
+    pagination::{ModLogPagination, Pagination},
+    util::{numbers, MessageExt},
+};
+
+use serenity::{
+    framework::standard::{macros::command, Args, CommandResult},
+    model::prelude::Message,
+    prelude::Context,
+};
+use std::sync::Arc;
+
+#[command]
+#[only_in("guild")]
+#[checks(Authority)]
+#[description = "Show the most recent administrative actions and role changes of this server.\n\
+If a user is given, only actions performed by or affecting the user are shown.\n\
+To also have them posted in a channel, use `<logchannel`."]
+#[usage = "[user mention / user id]"]
+#[example = "@badewanne3"]
+#[aliases("modlogs", "auditlog")]
+async fn modlog(ctx: &Context, msg: &Message, args: Args) -> CommandResult {
+    let guild_id = msg.guild_id.unwrap();
+    let user = match args.rest().trim() {
+        "" => None,
+        arg => match parse_user(arg) {
+            Some(user) => Some(user),
+            None => {
+                msg.channel_id
+                    .say(ctx, "The argument must be a user mention or a user id")
+                    .await?
+                    .reaction_delete(ctx, msg.author.id)
+                    .await;
+                return Ok(());
+            }
+        },
+    };
+
+    // Retrieve the entries
+    let entries = {
+        let data = ctx.data.read().await;
+        let mysql = data.get::<MySQL>().unwrap();
+        match mysql
+            .get_mod_log(guild_id.0, user.map(|user| user.0), 100)
+            .await
+        {
+            Ok(entries) => entries,
+            Err(why) => {
+                msg.channel_id
+                    .say(ctx, "Some database issue, blame bade")
+                    .await?
+                    .reaction_delete(ctx, msg.author.id)
+                    .await;
+                return Err(why.to_string().into());
+            }
+        }
+    };
+    let name = match user {
+        Some(user) => match user.to_user(ctx).await {
+            Ok(user) => user.tag(),
+            Err(_) => user.to_string(),
+        },
+        None => match guild_id.to_guild_cached(&ctx.cache).await {
+            Some(guild) => guild.name,
+            None => "this server".to_owned(),
+        },
+    };
+    if entries.is_empty() {
+        msg.channel_id
+            .say(ctx, format!("The mod log of {} is empty", name))
+            .await?
+            .reaction_delete(ctx, msg.author.id)
+            .await;
+        return Ok(());
+    }
+
+    // Prepare initial page
+    let pages = numbers::div_euclid(10, entries.len());
+    let end = entries.len().min(10);
+    let data = ModLogHistoryEmbed::new(&name, &entries[..end], 1, (1, pages));
+
+    // Creating the embed
+    let resp = msg
+        .channel_id
+        .send_message(&ctx.http, |m| m.embed(|e| data.build(e)))
+        .await?;
+
+    // Skip pagination if too few entries
+    if entries.len() <= 10 {
+        resp.reaction_delete(ctx, msg.author.id).await;
+        return Ok(());
+    }
+
+    // Pagination
+    let pagination = ModLogPagination::new(ctx, resp, msg.author.id, name, entries).await;
+    let cache = Arc::clone(&ctx.cache);
+    let http = Arc::clone(&ctx.http);
+    tokio::spawn(async move {
+        if let Err(why) = pagination.start(cache, http).await {
+            warn!("Pagination error: {}", why)
+        }
+    });
+    Ok(())
+}
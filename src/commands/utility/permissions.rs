@@ -0,0 +1,278 @@
+use crate::{
+    arguments::PermissionArgs,
+    commands::{checks::*, find_command, find_group},
+    database::{CommandPermission, ModAction, ModLogEntry, MySQL},
+    util::{discord::mod_log, MessageExt},
+    CommandPermissions,
+};
+
+use serenity::{
+    framework::standard::{macros::command, Args, CommandResult},
+    model::{guild::Guild, id::RoleId, prelude::Message},
+    prelude::Context,
+};
+use std::fmt::Write;
+
+#[command]
+#[only_in("guild")]
+#[checks(Authority)]
+#[description = "Enable or disable commands or whole groups like `Fun` or `StreamTracking` \
+for this server or, if a channel is given, only for that channel.\n\
+Without arguments the current settings are shown.\n\
+`roles`: Only members with one of the roles can use the command or group.\n\
+`reset`: Remove the setting e.g. to undo an exception for a channel.\n\
+The most specific setting applies: channel settings before server settings \
+and command settings before group settings."]
+#[usage = "[enable / disable / roles / reset] [command / group] [#channel] [roles]"]
+#[example = "disable fun"]
+#[example = "enable fun #bot-spam"]
+#[example = "roles streamtracking @Mod @Streamer"]
+#[example = "reset fun #bot-spam"]
+#[aliases("permission", "perms")]
+async fn permissions(ctx: &Context, msg: &Message, mut args: Args) -> CommandResult {
+    let guild_id = msg.guild_id.unwrap();
+    let guild = guild_id.to_guild_cached(&ctx.cache).await;
+    let setting = match args.single::<String>().ok().map(|arg| arg.to_lowercase()) {
+        None => None,
+        Some(arg) if arg == "list" => None,
+        Some(arg) if ["enable", "disable", "roles", "reset"].contains(&arg.as_str()) => Some(arg),
+        Some(other) => {
+            let content = format!(
+                "Unknown setting `{}`, must be either `enable`, `disable`, `roles`, or `reset`",
+                other
+            );
+            msg.channel_id
+                .say(ctx, content)
+                .await?
+                .reaction_delete(ctx, msg.author.id)
+                .await;
+            return Ok(());
+        }
+    };
+
+    // Show the current settings
+    let setting = match setting {
+        Some(setting) => setting,
+        None => {
+            let content = {
+                let data = ctx.data.read().await;
+                let permissions = data.get::<CommandPermissions>().unwrap();
+                match permissions.get(&guild_id).filter(|rules| !rules.is_empty()) {
+                    Some(rules) => list(guild.as_ref(), rules),
+                    None => "All commands can be used everywhere in this server, \
+                        restrict them with `<permissions disable` or `<permissions roles`"
+                        .to_owned(),
+                }
+            };
+            msg.channel_id
+                .say(ctx, content)
+                .await?
+                .reaction_delete(ctx, msg.author.id)
+                .await;
+            return Ok(());
+        }
+    };
+    let args = match PermissionArgs::new(args, setting == "roles") {
+        Ok(args) => args,
+        Err(err_msg) => {
+            msg.channel_id
+                .say(ctx, err_msg)
+                .await?
+                .reaction_delete(ctx, msg.author.id)
+                .await;
+            return Ok(());
+        }
+    };
+
+    // Groups first since some command aliases are also group names
+    let name = match find_group(&args.name).or_else(|| find_command(&args.name)) {
+        Some(name) if name == "Owner" || name == "permissions" => {
+            Err(format!("`{}` can't be restricted", name))
+        }
+        Some(name) => Ok(name.to_lowercase()),
+        None => Err(format!("There is no command or group `{}`", args.name)),
+    };
+    let channel_id = args.channel_id.map(|channel| channel.0);
+    let error = if let Err(err_msg) = name.as_ref() {
+        Some(err_msg.to_owned())
+    } else if args.channel_id.map_or(false, |channel| {
+        guild
+            .as_ref()
+            .map_or(true, |guild| !guild.channels.contains_key(&channel))
+    }) {
+        Some(format!(
+            "There is no channel with id {} in this server",
+            channel_id.unwrap()
+        ))
+    } else if setting == "roles" && args.roles.is_empty() {
+        Some(
+            "After the command or group you must specify at least one role, \
+            use `reset` to remove the restriction"
+                .to_owned(),
+        )
+    } else {
+        None
+    };
+    if let Some(content) = error {
+        msg.channel_id
+            .say(ctx, content)
+            .await?
+            .reaction_delete(ctx, msg.author.id)
+            .await;
+        return Ok(());
+    }
+    let name = name.unwrap();
+    let scope = match channel_id {
+        Some(channel) => format!("in <#{}>", channel),
+        None => "in this server".to_owned(),
+    };
+
+    // Apply the setting to the current rule of the command or group
+    let current = {
+        let data = ctx.data.read().await;
+        let permissions = data.get::<CommandPermissions>().unwrap();
+        permissions.get(&guild_id).and_then(|rules| {
+            rules
+                .iter()
+                .find(|rule| rule.channel_id == channel_id && rule.name == name)
+                .cloned()
+        })
+    };
+    if setting == "reset" && current.is_none() {
+        let content = format!("There is no setting for `{}` {}", name, scope);
+        msg.channel_id
+            .say(ctx, content)
+            .await?
+            .reaction_delete(ctx, msg.author.id)
+            .await;
+        return Ok(());
+    }
+    let rule = if setting == "reset" {
+        None
+    } else {
+        let mut rule = current.unwrap_or_else(|| CommandPermission {
+            guild_id: guild_id.0,
+            channel_id,
+            name: name.clone(),
+            enabled: true,
+            roles: Vec::new(),
+        });
+        match setting.as_str() {
+            "enable" => rule.enabled = true,
+            "disable" => rule.enabled = false,
+            _ => rule.roles = args.roles.iter().map(|role| role.0).collect(),
+        }
+        Some(rule)
+    };
+
+    // Save in database
+    {
+        let data = ctx.data.read().await;
+        let mysql = data.get::<MySQL>().unwrap();
+        let result = match rule {
+            Some(ref rule) => mysql.set_command_permission(rule).await,
+            None => {
+                mysql
+                    .remove_command_permission(guild_id.0, channel_id, &name)
+                    .await
+            }
+        };
+        if let Err(why) = result {
+            msg.channel_id
+                .say(ctx, "Some database issue, blame bade")
+                .await?
+                .reaction_delete(ctx, msg.author.id)
+                .await;
+            return Err(why.to_string().into());
+        }
+    }
+
+    // Save in CommandPermissions data
+    {
+        let mut data = ctx.data.write().await;
+        let permissions = data.get_mut::<CommandPermissions>().unwrap();
+        let rules = permissions.entry(guild_id).or_default();
+        rules.retain(|rule| rule.channel_id != channel_id || rule.name != name);
+        if let Some(ref rule) = rule {
+            rules.push(rule.clone());
+        }
+    }
+    let (details, content) = match rule {
+        Some(ref rule) => {
+            let roles: Vec<_> = rule
+                .roles
+                .iter()
+                .map(|role| format!("<@&{}>", role))
+                .collect();
+            (
+                format!("`{}` {}: {}", name, scope, describe(rule, roles)),
+                format!(
+                    "`{}` {}: {}",
+                    name,
+                    scope,
+                    describe(rule, role_names(guild.as_ref(), &rule.roles))
+                ),
+            )
+        }
+        None => (
+            format!("`{}` {}: reset", name, scope),
+            format!("Removed the setting for `{}` {}", name, scope),
+        ),
+    };
+    let entry = ModLogEntry::new(guild_id.0, ModAction::Permissions, msg.author.id.0, details);
+    mod_log(ctx, entry).await;
+    msg.channel_id
+        .say(ctx, content)
+        .await?
+        .reaction_delete(ctx, msg.author.id)
+        .await;
+    Ok(())
+}
+
+fn list(guild: Option<&Guild>, rules: &[CommandPermission]) -> String {
+    let mut rules: Vec<_> = rules.iter().collect();
+    rules.sort_unstable_by(|a, b| a.name.cmp(&b.name).then(a.channel_id.cmp(&b.channel_id)));
+    let mut content = String::from("Command permissions of this server:\n");
+    for (i, rule) in rules.iter().enumerate() {
+        let scope = match rule.channel_id {
+            Some(channel) => format!("in <#{}>", channel),
+            None => "in this server".to_owned(),
+        };
+        let line = format!(
+            "`{}` {}: {}\n",
+            rule.name,
+            scope,
+            describe(rule, role_names(guild, &rule.roles))
+        );
+        // Stay within discord's message limit
+        if content.len() + line.len() > 1900 {
+            let _ = write!(content, "...and {} more", rules.len() - i);
+            break;
+        }
+        content.push_str(&line);
+    }
+    content
+}
+
+fn describe(rule: &CommandPermission, roles: Vec<String>) -> String {
+    if !rule.enabled {
+        "disabled".to_owned()
+    } else if roles.is_empty() {
+        "enabled".to_owned()
+    } else {
+        format!("only for {}", roles.join(", "))
+    }
+}
+
+// Role names instead of mentions to not ping anyone
+fn role_names(guild: Option<&Guild>, roles: &[u64]) -> Vec<String> {
+    roles
+        .iter()
+        .map(|&role| {
+            let name = guild
+                .and_then(|guild| guild.roles.get(&RoleId(role)))
+                .map_or_else(|| role.to_string(), |role| role.name.clone());
+            format!("`@{}`", name)
+        })
+        .collect()
+}
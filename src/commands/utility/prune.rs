@@ -1,61 +1,169 @@
-use crate::commands::checks::*;
+use crate::{
+    arguments::PruneArgs,
+    commands::checks::*,
+    database::{ModAction, ModLogEntry},
+    util::{discord::mod_log, MessageExt},
+};
 
+use chrono::{DateTime, Duration as ChronoDuration, TimeZone, Utc};
 use serenity::{
     framework::standard::{macros::command, Args, CommandResult},
-    model::prelude::Message,
+    model::{id::MessageId, misc::Mentionable, prelude::Message},
     prelude::Context,
 };
+use std::{cmp::Reverse, collections::HashMap, fmt::Write};
 use tokio::time::{self, Duration};
 
+/// Most messages that are looked through to find the ones matching the filters
+const MAX_SCANNED: usize = 5000;
+
 #[command]
+#[only_in("guild")]
 #[checks(Authority)]
-#[description = "Optionally provide a number to delete this \
-                 many of the latest messages of a channel, defaults to 1. \
-                 Amount must be between 1 and 99."]
-#[usage = "[number]"]
+#[description = "Delete the latest messages of this channel, optionally filtered.\n\
+The amount defaults to 1 without filters and to 100 with filters, it can be at most 1000.\n\
+`-u`: Only messages of the given user.\n\
+`--bots`: Only messages of bots.\n\
+`-c`: Only messages containing the text.\n\
+`-r`: Only messages matching the regex.\n\
+`--attachments` / `--embeds`: Only messages with attachments / embeds.\n\
+`-before` / `-after`: Only messages before / after the given message id.\n\
+Messages older than two weeks are deleted one by one so they take a while.\n\
+The deletion is written to the mod log, see `<logchannel` and `<modlog`."]
+#[usage = "[amount] [-u user] [--bots] [-c text] [-r regex] [--attachments] [--embeds] \
+[-before message id] [-after message id]"]
 #[example = "3"]
+#[example = "50 -u @badewanne3 -c \"good morning\""]
+#[example = "--bots -after 745263914580705290"]
 #[aliases("purge")]
-async fn prune(ctx: &Context, msg: &Message, mut args: Args) -> CommandResult {
-    let amount = if args.remaining() > 0 {
-        match args.trimmed().single::<u64>() {
-            Ok(val) => {
-                if val < 1 || val > 99 {
-                    msg.channel_id
-                        .say(
-                            &ctx.http,
-                            "First argument must be an integer between 1 and 99",
-                        )
-                        .await?;
-                    return Ok(());
-                } else {
-                    val + 1
-                }
+async fn prune(ctx: &Context, msg: &Message, args: Args) -> CommandResult {
+    let args = match PruneArgs::new(args) {
+        Ok(args) => args,
+        Err(err_msg) => {
+            msg.channel_id
+                .say(ctx, err_msg)
+                .await?
+                .reaction_delete(ctx, msg.author.id)
+                .await;
+            return Ok(());
+        }
+    };
+
+    // Look through the channel history until enough messages match
+    let channel = msg.channel_id;
+    let mut before = args.before.unwrap_or(msg.id);
+    let mut ids = Vec::with_capacity(args.amount);
+    let mut authors = HashMap::new();
+    let mut scanned = 0;
+    'scan: while ids.len() < args.amount && scanned < MAX_SCANNED {
+        let messages = channel
+            .messages(ctx, |retriever| retriever.before(before).limit(100))
+            .await?;
+        // Messages are sorted from newest to oldest
+        before = match messages.last() {
+            Some(last) => last.id,
+            None => break,
+        };
+        scanned += messages.len();
+        for message in messages {
+            if args.after.map_or(false, |after| message.id <= after) {
+                break 'scan;
             }
-            Err(_) => {
-                msg.channel_id
-                    .say(
-                        &ctx.http,
-                        "First argument must be a number between 1 and 99",
-                    )
-                    .await?;
-                return Ok(());
+            if args.matches(&message) {
+                ids.push(message.id);
+                *authors.entry(message.author.id).or_insert(0) += 1;
+                if ids.len() == args.amount {
+                    break 'scan;
+                }
             }
         }
-    } else {
-        2
-    };
-    let messages = msg
-        .channel_id
-        .messages(&ctx.http, |retriever| retriever.limit(amount))
-        .await?;
-    msg.channel_id.delete_messages(&ctx.http, messages).await?;
-    let msg = msg
-        .channel_id
-        .send_message(&ctx.http, |m| {
-            m.content(format!("Deleted the last {} messages", amount - 1))
-        })
-        .await?;
+    }
+    if ids.is_empty() {
+        msg.channel_id
+            .say(ctx, "No messages match the given filters")
+            .await?
+            .reaction_delete(ctx, msg.author.id)
+            .await;
+        return Ok(());
+    }
+
+    // Bulk delete recent messages in batches, older ones one by one
+    let now = Utc::now();
+    let (recent, old): (Vec<_>, Vec<_>) = ids
+        .iter()
+        .copied()
+        .chain(std::iter::once(msg.id))
+        .partition(|&id| bulk_deletable(id, now));
+    let mut failed = 0;
+    for batch in recent.chunks(100) {
+        if let Err(why) = channel.delete_messages(ctx, batch).await {
+            warn!("Error while bulk deleting messages: {}", why);
+            failed += batch.iter().filter(|&&id| id != msg.id).count();
+        }
+    }
+    for id in old {
+        if channel.delete_message(ctx, id).await.is_err() {
+            failed += 1;
+        }
+    }
+    let deleted = ids.len() - failed;
+
+    // Log the deletion
+    let mut details = format!("Deleted {} messages in {}", deleted, channel.mention());
+    if failed > 0 {
+        let _ = write!(details, ", {} could not be deleted", failed);
+    }
+    let _ = write!(details, "\n**Filters:** {}", args.filters());
+    let mut authors: Vec<_> = authors.into_iter().collect();
+    authors.sort_unstable_by_key(|&(_, count)| Reverse(count));
+    details.push_str("\n**Authors:** ");
+    for (i, (author, count)) in authors.iter().take(10).enumerate() {
+        if i > 0 {
+            details.push_str(", ");
+        }
+        let _ = write!(details, "{} ({})", author.mention(), count);
+    }
+    if authors.len() > 10 {
+        let _ = write!(details, " and {} more", authors.len() - 10);
+    }
+    let mut entry = ModLogEntry::new(
+        msg.guild_id.unwrap().0,
+        ModAction::Prune,
+        msg.author.id.0,
+        details,
+    );
+    if let Some(author) = args.author {
+        entry = entry.target(author.0);
+    }
+    mod_log(ctx, entry).await;
+    let mut content = format!("Deleted the last {} messages", deleted);
+    if failed > 0 {
+        let _ = write!(content, ", {} messages could not be deleted", failed);
+    }
+    let response = channel.say(ctx, content).await?;
     time::delay_for(Duration::from_secs(6)).await;
-    msg.delete(ctx).await?;
+    response.delete(ctx).await?;
     Ok(())
 }
+
+/// Discord only bulk deletes messages that are less than two weeks old
+fn bulk_deletable(id: MessageId, now: DateTime<Utc>) -> bool {
+    // Snowflakes contain the milliseconds since the start of 2015
+    let created_at = Utc.timestamp_millis((id.0 >> 22) as i64 + 1_420_070_400_000);
+    // Leave some leeway for clock differences
+    now - created_at < ChronoDuration::days(14) - ChronoDuration::minutes(5)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bulk_delete_age() {
+        // Created at 2016-04-30 11:18:25.796 UTC
+        let id = MessageId(175_928_847_299_117_063);
+        let created_at = Utc.ymd(2016, 4, 30).and_hms_milli(11, 18, 25, 796);
+        assert!(bulk_deletable(id, created_at + ChronoDuration::days(13)));
+        assert!(!bulk_deletable(id, created_at + ChronoDuration::days(14)));
+    }
+}
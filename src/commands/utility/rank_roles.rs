@@ -0,0 +1,235 @@
+use crate::{
+    arguments::{parse_role, RankRoleArgs},
+    commands::checks::*,
+    database::{ModAction, ModLogEntry, RankRole},
+    util::{discord::mod_log, rank_roles, MessageExt},
+    MySQL,
+};
+
+use serenity::{
+    framework::standard::{macros::command, Args, CommandResult},
+    model::{
+        guild::Guild,
+        id::{RoleId, UserId},
+        prelude::Message,
+    },
+    prelude::Context,
+};
+use std::fmt::Write;
+
+#[command]
+#[only_in("guild")]
+#[checks(Authority)]
+#[description = "Give linked members roles based on their osu! rank, updated once a day.\n\
+Without arguments the rules of this server are shown.\n\
+`add`: Give the role to linked members whose rank is within the range. \
+The mode defaults to osu! and the rank to the global rank. \
+Ranges are of the form `a..b`, `a..`, or `..b` e.g. `..1000` for the top 1000.\n\
+`remove`: Stop updating the role, members keep it until it's removed manually.\n\
+`dryrun`: Show which roles would be added and removed without changing anything.\n\
+`update`: Update the roles right now instead of waiting for the daily update."]
+#[usage = "[add / remove / dryrun / update] [role] [osu / taiko / ctb / mania] [global / country] [range]"]
+#[example = "add @Top1000 ..1000"]
+#[example = "add @4digit mania 1000..9999"]
+#[example = "add @TopGermany country ..50"]
+#[example = "dryrun"]
+#[aliases("rankrole")]
+async fn rankroles(ctx: &Context, msg: &Message, mut args: Args) -> CommandResult {
+    let guild_id = msg.guild_id.unwrap();
+    let setting = args.single::<String>().ok().map(|arg| arg.to_lowercase());
+    let rules = {
+        let data = ctx.data.read().await;
+        let mysql = data.get::<MySQL>().unwrap();
+        match mysql.get_guild_rank_roles(guild_id.0).await {
+            Ok(rules) => rules,
+            Err(why) => {
+                msg.channel_id
+                    .say(ctx, "Some database issue, blame bade")
+                    .await?
+                    .reaction_delete(ctx, msg.author.id)
+                    .await;
+                return Err(why.to_string().into());
+            }
+        }
+    };
+    let guild = guild_id.to_guild_cached(&ctx.cache).await;
+    let content = match setting.as_deref() {
+        None | Some("list") => {
+            if rules.is_empty() {
+                "There are no rank roles in this server, add one with `<rankroles add`".to_owned()
+            } else {
+                let mut content = String::from("Rank roles of this server:\n");
+                for rule in rules.iter() {
+                    let role = role_name(guild.as_ref(), rule.role_id);
+                    let _ = writeln!(content, "`@{}`: {}", role, rule);
+                }
+                content
+            }
+        }
+        Some("add") => {
+            let args = match RankRoleArgs::new(args) {
+                Ok(args) => args,
+                Err(err_msg) => {
+                    msg.channel_id
+                        .say(ctx, err_msg)
+                        .await?
+                        .reaction_delete(ctx, msg.author.id)
+                        .await;
+                    return Ok(());
+                }
+            };
+            if guild
+                .as_ref()
+                .map_or(true, |guild| !guild.roles.contains_key(&args.role_id))
+            {
+                format!("There is no role with id {} in this server", args.role_id)
+            } else {
+                let rule = RankRole {
+                    guild_id: guild_id.0,
+                    role_id: args.role_id.0,
+                    mode: args.mode,
+                    country: args.country,
+                    rank_min: args.rank_min,
+                    rank_max: args.rank_max,
+                };
+                {
+                    let data = ctx.data.read().await;
+                    let mysql = data.get::<MySQL>().unwrap();
+                    if let Err(why) = mysql.set_rank_role(&rule).await {
+                        msg.channel_id
+                            .say(ctx, "Some issue while inserting into DB, blame bade")
+                            .await?
+                            .reaction_delete(ctx, msg.author.id)
+                            .await;
+                        return Err(why.to_string().into());
+                    }
+                }
+                let details = format!("<@&{}> for linked members with {}", rule.role_id, rule);
+                let entry =
+                    ModLogEntry::new(guild_id.0, ModAction::RankRoles, msg.author.id.0, details);
+                mod_log(ctx, entry).await;
+                let role = role_name(guild.as_ref(), rule.role_id);
+                format!(
+                    "Linked members with {} will get the role `@{}`.\n\
+                    Use `<rankroles dryrun` to see who would be affected.",
+                    rule, role
+                )
+            }
+        }
+        Some("remove") => match parse_role(args.rest().trim()) {
+            Some(role) if rules.iter().any(|rule| rule.role_id == role.0) => {
+                {
+                    let data = ctx.data.read().await;
+                    let mysql = data.get::<MySQL>().unwrap();
+                    if let Err(why) = mysql.remove_rank_role(guild_id.0, role.0).await {
+                        msg.channel_id
+                            .say(ctx, "Some database issue, blame bade")
+                            .await?
+                            .reaction_delete(ctx, msg.author.id)
+                            .await;
+                        return Err(why.to_string().into());
+                    }
+                }
+                let details = format!("<@&{}> is no longer a rank role", role);
+                let entry =
+                    ModLogEntry::new(guild_id.0, ModAction::RankRoles, msg.author.id.0, details);
+                mod_log(ctx, entry).await;
+                format!(
+                    "The role `@{}` is no longer updated based on rank",
+                    role_name(guild.as_ref(), role.0)
+                )
+            }
+            Some(_) => "That role is not a rank role of this server".to_owned(),
+            None => "After `remove` you must specify a role".to_owned(),
+        },
+        Some(setting @ "dryrun") | Some(setting @ "update") => {
+            if rules.is_empty() {
+                "There are no rank roles in this server".to_owned()
+            } else {
+                let changes =
+                    rank_roles::guild_changes(&ctx.cache, &ctx.data, guild_id, &rules).await;
+                if setting == "dryrun" {
+                    dry_run_report(guild.as_ref(), &changes)
+                } else {
+                    let failed = rank_roles::apply_changes(&ctx.http, guild_id, &changes).await;
+                    let details = format!("Updated the rank roles of {} members", changes.len());
+                    let entry = ModLogEntry::new(
+                        guild_id.0,
+                        ModAction::RankRoles,
+                        msg.author.id.0,
+                        details.clone(),
+                    );
+                    mod_log(ctx, entry).await;
+                    let mut content = details;
+                    if failed > 0 {
+                        let _ = write!(
+                            content,
+                            ", {} roles could not be updated. \
+                            Make sure my role is above the rank roles.",
+                            failed
+                        );
+                    }
+                    content
+                }
+            }
+        }
+        Some(other) => format!(
+            "Unknown setting `{}`, must be either `add`, `remove`, `dryrun`, or `update`",
+            other
+        ),
+    };
+    msg.channel_id
+        .say(ctx, content)
+        .await?
+        .reaction_delete(ctx, msg.author.id)
+        .await;
+    Ok(())
+}
+
+fn dry_run_report(guild: Option<&Guild>, changes: &[rank_roles::RankRoleChange]) -> String {
+    if changes.is_empty() {
+        return "All linked members already have the correct rank roles".to_owned();
+    }
+    let mut content = format!(
+        "The rank roles of {} members would change:\n",
+        changes.len()
+    );
+    for (i, change) in changes.iter().enumerate() {
+        let member = guild
+            .and_then(|guild| guild.members.get(&UserId(change.user_id)))
+            .map_or_else(
+                || change.user_id.to_string(),
+                |member| member.user.name.clone(),
+            );
+        let roles: Vec<_> = change
+            .add
+            .iter()
+            .map(|&role| format!("+@{}", role_name(guild, role)))
+            .chain(
+                change
+                    .remove
+                    .iter()
+                    .map(|&role| format!("-@{}", role_name(guild, role))),
+            )
+            .collect();
+        let line = format!(
+            "`{}` ({}): `{}`\n",
+            member,
+            change.osu_name,
+            roles.join("`, `")
+        );
+        // Stay within discord's message limit
+        if content.len() + line.len() > 1900 {
+            let _ = write!(content, "...and {} more", changes.len() - i);
+            break;
+        }
+        content.push_str(&line);
+    }
+    content
+}
+
+fn role_name(guild: Option<&Guild>, role: u64) -> String {
+    guild
+        .and_then(|guild| guild.roles.get(&RoleId(role)))
+        .map_or_else(|| role.to_string(), |role| role.name.clone())
+}
@@ -1,20 +1,18 @@
 use crate::{
     arguments::RoleAssignArgs,
     commands::checks::*,
-    database::MySQL,
+    database::{ModAction, ModLogEntry, MySQL, RoleMenuEntry},
     embeds::{EmbedData, RoleAssignEmbed},
-    util::MessageExt,
+    util::{discord::mod_log, MessageExt},
     ReactionTracker,
 };
 
 use serenity::{
     framework::standard::{macros::command, Args, CommandResult},
-    model::{
-        channel::Message,
-        id::{ChannelId, MessageId, RoleId},
-    },
+    model::channel::Message,
     prelude::Context,
 };
+use std::fmt::Write;
 
 #[command]
 #[only_in("guild")]
@@ -24,9 +22,14 @@ use serenity::{
                  gain that role and and if they remove a reaction, \
                  they lose the role\n\
                  The first argument must be the channel that contains the message, \
-                 the second must be the message id, and the third must be the role."]
-#[usage = "[channel mention / channel id] [message id] [role mention / role id]"]
+                 the second must be the message id, and the third must be the role.\n\
+                 Optionally, an emoji can be given so that only that reaction assigns the role, \
+                 this way a message can assign multiple roles. \
+                 After the emoji, a group name can be given; \
+                 members can only have one role of the same group of a message."]
+#[usage = "[channel mention / channel id] [message id] [role mention / role id] [emoji] [group]"]
 #[example = "#general 681871156168753193 @Meetup"]
+#[example = "#roles 681871156168753193 @Europe 🇪🇺 region"]
 async fn roleassign(ctx: &Context, msg: &Message, args: Args) -> CommandResult {
     let args = match RoleAssignArgs::new(args) {
         Ok(args) => args,
@@ -42,10 +45,25 @@ async fn roleassign(ctx: &Context, msg: &Message, args: Args) -> CommandResult {
     let channel = args.channel_id;
     let msg_id = args.message_id;
     let role = args.role_id;
+    let entry = RoleMenuEntry {
+        emoji: args.emoji,
+        role: role.0,
+        group: args.group,
+    };
+    if let Some(emoji) = entry.reaction() {
+        if let Err(why) = channel.create_reaction(ctx, msg_id, emoji).await {
+            msg.channel_id
+                .say(ctx, format!("Could not react with that emoji: {}", why))
+                .await?
+                .reaction_delete(ctx, msg.author.id)
+                .await;
+            return Ok(());
+        }
+    }
     {
         let data = ctx.data.read().await;
         let mysql = data.get::<MySQL>().unwrap();
-        match mysql.add_role_assign(channel.0, msg_id.0, role.0).await {
+        match mysql.add_role_assign(channel.0, msg_id.0, &entry).await {
             Ok(_) => debug!("Inserted into role_assign table"),
             Err(why) => {
                 msg.channel_id
@@ -57,13 +75,29 @@ async fn roleassign(ctx: &Context, msg: &Message, args: Args) -> CommandResult {
             }
         }
     }
+    let emoji = entry.emoji.clone();
     {
         let mut data = ctx.data.write().await;
         let reaction_tracker = data.get_mut::<ReactionTracker>().unwrap();
-        reaction_tracker.insert((ChannelId(channel.0), MessageId(msg_id.0)), RoleId(role.0));
+        reaction_tracker
+            .entry((channel, msg_id))
+            .or_default()
+            .entries
+            .push(entry);
+    }
+    let guild = msg.guild_id.unwrap();
+    let mut details = format!(
+        "<@&{}> through reactions on [this message]\
+        (https://discordapp.com/channels/{}/{}/{})",
+        role, guild, channel, msg_id
+    );
+    if let Some(ref emoji) = emoji {
+        let _ = write!(details, " with {}", emoji);
     }
+    let entry = ModLogEntry::new(guild.0, ModAction::RoleAssign, msg.author.id.0, details);
+    mod_log(ctx, entry).await;
     let message = channel.message(ctx, msg_id).await?;
-    let data = RoleAssignEmbed::new(message, msg.guild_id.unwrap(), role, &ctx.cache).await;
+    let data = RoleAssignEmbed::new(message, guild, role, emoji.as_deref(), &ctx.cache).await;
     msg.channel_id
         .send_message(ctx, |m| m.embed(|e| data.build(e)))
         .await?
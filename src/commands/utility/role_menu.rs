@@ -0,0 +1,155 @@
+use crate::{
+    arguments::RoleMenuArgs,
+    commands::checks::*,
+    database::{ModAction, ModLogEntry, MySQL, RoleMenu, RoleMenuEntry},
+    embeds::{EmbedData, RoleMenuEmbed},
+    util::{discord::mod_log, MessageExt},
+    ReactionTracker,
+};
+
+use failure::Error;
+use serenity::{
+    framework::standard::{macros::command, Args, CommandResult},
+    model::channel::{Message, ReactionType},
+    prelude::Context,
+};
+use std::convert::TryFrom;
+
+#[command]
+#[only_in("guild")]
+#[checks(Authority)]
+#[description = "Post a message that assigns a role for each of its reactions.\n\
+                 The first argument is the title of the message, followed by pairs \
+                 of emoji and role.\n\
+                 With `--exclusive` members can only pick one of the roles, \
+                 `-max` limits how many of the roles a member can pick, and \
+                 `-required` only gives roles to members that have the given role."]
+#[usage = "[title] [emoji] [role] [emoji] [role] ... [--exclusive] [-max number] [-required role]"]
+#[example = "\"Pick your region\" 🇪🇺 @Europe 🇺🇸 @America --exclusive"]
+#[example = "Games 🎵 @osu!mania 🥁 @osu!taiko -max 1 -required @Member"]
+async fn rolemenu(ctx: &Context, msg: &Message, args: Args) -> CommandResult {
+    let args = match RoleMenuArgs::new(args) {
+        Ok(args) => args,
+        Err(err_msg) => {
+            msg.channel_id
+                .say(ctx, err_msg)
+                .await?
+                .reaction_delete(ctx, msg.author.id)
+                .await;
+            return Ok(());
+        }
+    };
+
+    // Check that all roles exist
+    let guild = msg.guild_id.unwrap().to_guild_cached(&ctx.cache).await;
+    let unknown = args
+        .roles
+        .iter()
+        .map(|(_, role)| role)
+        .chain(args.required_role.iter())
+        .find(|role| guild.as_ref().map_or(true, |g| !g.roles.contains_key(role)));
+    if let Some(role) = unknown {
+        let content = format!("There is no role with id {} in this server", role);
+        msg.channel_id
+            .say(ctx, content)
+            .await?
+            .reaction_delete(ctx, msg.author.id)
+            .await;
+        return Ok(());
+    }
+
+    // Post the menu and its reactions
+    let data = RoleMenuEmbed::new(
+        args.title,
+        &args.roles,
+        args.required_role,
+        args.max_roles,
+        args.exclusive,
+    );
+    let menu_msg = msg
+        .channel_id
+        .send_message(ctx, |m| m.embed(|e| data.build(e)))
+        .await?;
+    for (emoji, _) in args.roles.iter() {
+        let result = match ReactionType::try_from(emoji.as_str()) {
+            Ok(reaction) => menu_msg
+                .react(ctx, reaction)
+                .await
+                .map_err(|why| why.to_string()),
+            Err(_) => Err("invalid emoji".to_owned()),
+        };
+        if let Err(why) = result {
+            menu_msg.delete(ctx).await?;
+            msg.channel_id
+                .say(ctx, format!("Could not react with {}: {}", emoji, why))
+                .await?
+                .reaction_delete(ctx, msg.author.id)
+                .await;
+            return Ok(());
+        }
+    }
+
+    // Store the menu
+    let group = if args.exclusive {
+        Some(String::from("menu"))
+    } else {
+        None
+    };
+    let menu = RoleMenu {
+        entries: args
+            .roles
+            .into_iter()
+            .map(|(emoji, role)| RoleMenuEntry {
+                emoji: Some(emoji),
+                role: role.0,
+                group: group.clone(),
+            })
+            .collect(),
+        required_role: args.required_role.map(|role| role.0),
+        max_roles: args.max_roles,
+    };
+    let channel = menu_msg.channel_id;
+    let message = menu_msg.id;
+    {
+        let data = ctx.data.read().await;
+        let mysql = data.get::<MySQL>().unwrap();
+        let result = async {
+            for entry in menu.entries.iter() {
+                mysql.add_role_assign(channel.0, message.0, entry).await?;
+            }
+            if menu.required_role.is_some() || menu.max_roles.is_some() {
+                mysql.set_role_menu(channel.0, message.0, &menu).await?;
+            }
+            Ok::<_, Error>(())
+        }
+        .await;
+        if let Err(why) = result {
+            msg.channel_id
+                .say(ctx, "Some issue while inserting into DB, blame bade")
+                .await?
+                .reaction_delete(ctx, msg.author.id)
+                .await;
+            return Err(why.to_string().into());
+        }
+    }
+    debug!("Inserted role menu into role_assign table");
+    let guild = msg.guild_id.unwrap();
+    let roles: Vec<_> = menu
+        .entries
+        .iter()
+        .map(|entry| format!("<@&{}>", entry.role))
+        .collect();
+    let details = format!(
+        "[Role menu](https://discordapp.com/channels/{}/{}/{}) for {}",
+        guild,
+        channel,
+        message,
+        roles.join(", ")
+    );
+    let entry = ModLogEntry::new(guild.0, ModAction::RoleMenu, msg.author.id.0, details);
+    mod_log(ctx, entry).await;
+    let mut data = ctx.data.write().await;
+    let reaction_tracker = data.get_mut::<ReactionTracker>().unwrap();
+    reaction_tracker.insert((channel, message), menu);
+    Ok(())
+}
@@ -1,13 +1,26 @@
 mod models;
 
 use models::BeatmapWrapper;
-pub use models::{DBMapSet, MapsetTagWrapper, Ratios, StreamTrack, TwitchUser};
+pub use models::{
+    check_permission, rank_role_changes, AliasKind, BgAlias, BgMapsetStats, BgSessionEntry,
+    CommandPermission, DBMapSet, MapsetTagWrapper, MenuDenial, ModAction, ModLogEntry,
+    PermissionDenial, RankRole, Ratios, RoleMenu, RoleMenuEntry, StreamNotification, StreamTrack,
+};
 
-use crate::{commands::utility::MapsetTags, util::globals::AUTHORITY_ROLES, Guild};
+use crate::{
+    commands::{
+        fun::{BgConfig, HlVersion},
+        utility::MapsetTags,
+    },
+    streams::{LiveStream, Platform},
+    util::{datetime, globals::AUTHORITY_ROLES},
+    Guild,
+};
 
+use chrono::{DateTime, Duration, Utc};
 use failure::Error;
 use rosu::models::{
-    ApprovalStatus::{Approved, Loved, Ranked},
+    ApprovalStatus::{self, Approved, Graveyard, Loved, Qualified, Ranked},
     Beatmap, GameMode, GameMods,
 };
 use serenity::model::id::{GuildId, UserId};
@@ -15,11 +28,19 @@ use sqlx::mysql::{MySql, MySqlPool};
 use std::{
     collections::{HashMap, HashSet},
     fmt::Write,
+    sync::Mutex,
+};
+use tokio::{
+    stream::StreamExt,
+    sync::mpsc::{self, UnboundedReceiver, UnboundedSender},
 };
-use tokio::stream::StreamExt;
 
 pub struct MySQL {
     pool: MySqlPool,
+    // Stale maps are sent to a background worker which re-requests them
+    refresh_tx: UnboundedSender<u32>,
+    refresh_rx: Mutex<Option<UnboundedReceiver<u32>>>,
+    refreshing: Mutex<HashSet<u32>>,
 }
 
 type DBResult<T> = Result<T, Error>;
@@ -27,7 +48,13 @@ type DBResult<T> = Result<T, Error>;
 impl MySQL {
     pub async fn new(database_url: &str) -> DBResult<Self> {
         let pool = MySqlPool::connect(database_url).await?;
-        Ok(Self { pool })
+        let (refresh_tx, refresh_rx) = mpsc::unbounded_channel();
+        Ok(Self {
+            pool,
+            refresh_tx,
+            refresh_rx: Mutex::new(Some(refresh_rx)),
+            refreshing: Mutex::new(HashSet::new()),
+        })
     }
 
     // ---------------------
@@ -52,6 +79,9 @@ FROM
             .bind(map_id)
             .fetch_one(&self.pool)
             .await?;
+        if needs_refresh(map.map().approval_status, map.last_checked) && !self.refresh_map(map_id) {
+            bail!("Beatmap {} is outdated", map_id);
+        }
         Ok(map.into())
     }
 
@@ -76,6 +106,12 @@ FROM
             .fetch(&self.pool)
             .filter_map(|result| match result {
                 Ok(map_wrapper) => {
+                    let map: &Beatmap = map_wrapper.map();
+                    if needs_refresh(map.approval_status, map_wrapper.last_checked)
+                        && !self.refresh_map(map.beatmap_id)
+                    {
+                        return None;
+                    }
                     let map: Beatmap = map_wrapper.into();
                     Some((map.beatmap_id, map))
                 }
@@ -91,15 +127,44 @@ FROM
         Ok(beatmaps)
     }
 
+    /// Random maps of the mode, only those that don't need a refresh
+    pub async fn get_random_beatmaps(&self, mode: GameMode, amount: u32) -> DBResult<Vec<Beatmap>> {
+        let query = r#"
+SELECT
+    *
+FROM
+    (
+        SELECT
+            *
+        FROM
+            maps
+        WHERE
+            mode = ?
+        ORDER BY
+            RAND()
+        LIMIT
+            ?
+    ) as m
+    JOIN mapsets as ms ON m.beatmapset_id = ms.beatmapset_id"#;
+        let maps = sqlx::query_as::<_, BeatmapWrapper>(query)
+            .bind(mode as u8)
+            .bind(amount)
+            .fetch_all(&self.pool)
+            .await?
+            .into_iter()
+            .filter(|map_wrapper| {
+                let map: &Beatmap = map_wrapper.map();
+                !needs_refresh(map.approval_status, map_wrapper.last_checked)
+            })
+            .map(|map_wrapper| map_wrapper.into())
+            .collect();
+        Ok(maps)
+    }
+
     pub async fn insert_beatmap(&self, map: &Beatmap) -> DBResult<()> {
-        match map.approval_status {
-            Loved | Ranked | Approved => {
-                // Important to do mapsets first for foreign key constrain
-                _insert_beatmapset(&self.pool, map).await?;
-                _insert_beatmap(&self.pool, map).await?;
-            }
-            _ => {}
-        }
+        // Important to do mapsets first for foreign key constrain
+        _insert_beatmapset(&self.pool, map).await?;
+        _insert_beatmap(&self.pool, map).await?;
         Ok(())
     }
 
@@ -109,18 +174,41 @@ FROM
         }
         let mut tx = self.pool.begin().await?;
         for map in maps.iter() {
-            match map.approval_status {
-                Loved | Ranked | Approved => {
-                    _insert_beatmapset(&mut tx, map).await?;
-                    _insert_beatmap(&mut tx, map).await?;
-                }
-                _ => {}
-            }
+            _insert_beatmapset(&mut tx, map).await?;
+            _insert_beatmap(&mut tx, map).await?;
         }
         tx.commit().await?;
         Ok(())
     }
 
+    /// Receiver of map ids whose DB entry is stale, can only be taken once
+    pub fn take_refresh_receiver(&self) -> Option<UnboundedReceiver<u32>> {
+        self.refresh_rx.lock().unwrap().take()
+    }
+
+    /// Mark the refresh of a map as finished, regardless of its success
+    pub fn refresh_done(&self, map_id: u32) {
+        self.refreshing.lock().unwrap().remove(&map_id);
+    }
+
+    // Returns whether a refresh of the map is running.
+    // Stale entries may only be used as long as that's the case.
+    fn refresh_map(&self, map_id: u32) -> bool {
+        let mut refreshing = self.refreshing.lock().unwrap();
+        if refreshing.contains(&map_id) {
+            return true;
+        }
+        // No worker took the receiver yet so no one would handle the request
+        if self.refresh_rx.lock().unwrap().is_some() {
+            return false;
+        }
+        if self.refresh_tx.send(map_id).is_err() {
+            return false;
+        }
+        refreshing.insert(map_id);
+        true
+    }
+
     // --------------------
     // Table: discord_users
     // --------------------
@@ -311,27 +399,118 @@ UPDATE
     // Table: role_assign
     // ------------------
 
-    pub async fn get_role_assigns(&self) -> DBResult<HashMap<(u64, u64), u64>> {
-        let assigns = sqlx::query_as::<_, (u32, u64, u64, u64)>("SELECT * FROM role_assign")
-            .fetch(&self.pool)
-            .filter_map(|result| match result {
-                Ok((_, c, m, r)) => Some(((c, m), r)),
-                Err(why) => {
-                    warn!("Error while getting roleassigns from DB: {}", why);
-                    None
+    pub async fn get_role_menus(&self) -> DBResult<HashMap<(u64, u64), RoleMenu>> {
+        let query = "SELECT channel, message, role, emoji, role_group FROM role_assign";
+        let mut menus: HashMap<_, RoleMenu> = HashMap::new();
+        let mut rows = sqlx::query_as::<_, (u64, u64, u64, Option<String>, Option<String>)>(query)
+            .fetch(&self.pool);
+        while let Some(row) = rows.next().await {
+            match row {
+                Ok((channel, message, role, emoji, group)) => {
+                    let entry = RoleMenuEntry { emoji, role, group };
+                    menus
+                        .entry((channel, message))
+                        .or_default()
+                        .entries
+                        .push(entry);
                 }
-            })
-            .collect::<Vec<_>>()
-            .await
-            .into_iter()
-            .collect();
-        Ok(assigns)
+                Err(why) => warn!("Error while getting roleassigns from DB: {}", why),
+            }
+        }
+        let query = "SELECT channel, message, required_role, max_roles FROM role_menus";
+        let settings = sqlx::query_as::<_, (u64, u64, Option<u64>, Option<u8>)>(query)
+            .fetch_all(&self.pool)
+            .await?;
+        for (channel, message, required_role, max_roles) in settings {
+            if let Some(menu) = menus.get_mut(&(channel, message)) {
+                menu.required_role = required_role;
+                menu.max_roles = max_roles;
+            }
+        }
+        Ok(menus)
+    }
+
+    pub async fn add_role_assign(
+        &self,
+        channel: u64,
+        message: u64,
+        entry: &RoleMenuEntry,
+    ) -> DBResult<()> {
+        sqlx::query(
+            "INSERT INTO role_assign(channel,message,role,emoji,role_group) VALUES (?,?,?,?,?)",
+        )
+        .bind(channel)
+        .bind(message)
+        .bind(entry.role)
+        .bind(entry.emoji.as_deref())
+        .bind(entry.group.as_deref())
+        .execute(&self.pool)
+        .await?;
+        Ok(())
     }
 
-    pub async fn add_role_assign(&self, channel: u64, message: u64, role: u64) -> DBResult<()> {
-        sqlx::query("INSERT INTO role_assign(channel,message,role) VALUES (?,?,?)")
+    pub async fn set_role_menu(&self, channel: u64, message: u64, menu: &RoleMenu) -> DBResult<()> {
+        let query = r#"
+INSERT INTO
+    role_menus(channel, message, required_role, max_roles)
+VALUES
+    (?,?,?,?) ON DUPLICATE KEY
+UPDATE
+    required_role = VALUES(required_role),
+    max_roles = VALUES(max_roles)"#;
+        sqlx::query(query)
             .bind(channel)
             .bind(message)
+            .bind(menu.required_role)
+            .bind(menu.max_roles)
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    // -----------------
+    // Table: rank_roles
+    // -----------------
+
+    pub async fn get_rank_roles(&self) -> DBResult<HashMap<u64, Vec<RankRole>>> {
+        let mut rank_roles: HashMap<_, Vec<_>> = HashMap::new();
+        let mut rows = sqlx::query_as::<_, RankRole>("SELECT * FROM rank_roles").fetch(&self.pool);
+        while let Some(row) = rows.next().await {
+            match row {
+                Ok(rule) => rank_roles.entry(rule.guild_id).or_default().push(rule),
+                Err(why) => warn!("Error while getting rank roles from DB: {}", why),
+            }
+        }
+        Ok(rank_roles)
+    }
+
+    pub async fn get_guild_rank_roles(&self, guild: u64) -> DBResult<Vec<RankRole>> {
+        let rules = sqlx::query_as("SELECT * FROM rank_roles WHERE guild_id=?")
+            .bind(guild)
+            .fetch_all(&self.pool)
+            .await?;
+        Ok(rules)
+    }
+
+    pub async fn set_rank_role(&self, rule: &RankRole) -> DBResult<()> {
+        let query = "REPLACE INTO rank_roles \
+            (guild_id, role_id, mode, country, rank_min, rank_max) \
+            VALUES (?,?,?,?,?,?)";
+        sqlx::query(query)
+            .bind(rule.guild_id)
+            .bind(rule.role_id)
+            .bind(rule.mode as u8)
+            .bind(rule.country)
+            .bind(rule.rank_min)
+            .bind(rule.rank_max)
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    pub async fn remove_rank_role(&self, guild: u64, role: u64) -> DBResult<()> {
+        sqlx::query("DELETE FROM rank_roles WHERE guild_id=? AND role_id=?")
+            .bind(guild)
             .bind(role)
             .execute(&self.pool)
             .await?;
@@ -339,11 +518,12 @@ UPDATE
     }
 
     // -----------------------------------
-    // Table: stream_tracks / twitch_users
+    // Table: stream_tracks / stream_users
     // -----------------------------------
 
-    pub async fn add_twitch_user(&self, id: u64, name: &str) -> DBResult<()> {
-        sqlx::query("INSERT INTO twitch_users(user_id,name) VALUES (?,?)")
+    pub async fn add_stream_user(&self, platform: Platform, id: &str, name: &str) -> DBResult<()> {
+        sqlx::query("INSERT IGNORE INTO stream_users(platform,user_id,name) VALUES (?,?,?)")
+            .bind(platform as u8)
             .bind(id)
             .bind(name)
             .execute(&self.pool)
@@ -351,32 +531,51 @@ UPDATE
         Ok(())
     }
 
-    pub async fn add_stream_track(&self, channel: u64, user: u64) -> DBResult<()> {
-        sqlx::query("INSERT INTO stream_tracks(channel_id,user_id) VALUES (?,?)")
-            .bind(channel)
-            .bind(user)
+    pub async fn add_stream_track(&self, track: &StreamTrack) -> DBResult<()> {
+        sqlx::query("INSERT INTO stream_tracks(channel_id,user_id,platform) VALUES (?,?,?)")
+            .bind(track.channel_id)
+            .bind(&track.user_id)
+            .bind(track.platform as u8)
             .execute(&self.pool)
             .await?;
         Ok(())
     }
 
-    pub async fn get_twitch_users(&self) -> DBResult<HashMap<String, u64>> {
-        let users = sqlx::query_as::<_, (u64, String)>("SELECT * FROM twitch_users")
-            .fetch(&self.pool)
-            .filter_map(|result| match result {
-                Ok((id, name)) => Some((name, id)),
-                Err(why) => {
-                    warn!("Error while getting twitch users from DB: {}", why);
-                    None
-                }
-            })
-            .collect::<Vec<_>>()
-            .await
-            .into_iter()
-            .collect();
+    /// Maps lowercase names of each platform to their user id
+    pub async fn get_stream_users(&self) -> DBResult<HashMap<(Platform, String), String>> {
+        let users = sqlx::query_as::<_, (u8, String, String)>(
+            "SELECT platform, user_id, name FROM stream_users",
+        )
+        .fetch(&self.pool)
+        .filter_map(|result| match result {
+            Ok((platform, id, name)) => Some(((Platform::from_u8(platform), name), id)),
+            Err(why) => {
+                warn!("Error while getting stream users from DB: {}", why);
+                None
+            }
+        })
+        .collect::<Vec<_>>()
+        .await
+        .into_iter()
+        .collect();
         Ok(users)
     }
 
+    /// Store the role and template of the track
+    pub async fn set_stream_track_config(&self, track: &StreamTrack) -> DBResult<()> {
+        let query = "UPDATE stream_tracks SET role_id=?, template=? \
+            WHERE channel_id=? AND user_id=? AND platform=?";
+        sqlx::query(query)
+            .bind(track.role_id)
+            .bind(&track.template)
+            .bind(track.channel_id)
+            .bind(&track.user_id)
+            .bind(track.platform as u8)
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
     pub async fn get_stream_tracks(&self) -> DBResult<HashSet<StreamTrack>> {
         let tracks = sqlx::query_as::<_, StreamTrack>("SELECT * FROM stream_tracks")
             .fetch_all(&self.pool)
@@ -386,22 +585,112 @@ UPDATE
         Ok(tracks)
     }
 
-    pub async fn remove_stream_track(&self, channel: u64, user: u64) -> DBResult<()> {
+    pub async fn remove_stream_track(&self, track: &StreamTrack) -> DBResult<()> {
         sqlx::query(
             r#"
 DELETE FROM
     stream_tracks
 WHERE
     channel_id=?
-    AND user_id=?"#,
+    AND user_id=?
+    AND platform=?"#,
         )
-        .bind(channel)
-        .bind(user)
+        .bind(track.channel_id)
+        .bind(&track.user_id)
+        .bind(track.platform as u8)
         .execute(&self.pool)
         .await?;
         Ok(())
     }
 
+    // ----------------------------
+    // Table: stream_notifications
+    // ----------------------------
+
+    pub async fn add_stream_notification(
+        &self,
+        channel: u64,
+        message: u64,
+        live: &LiveStream,
+    ) -> DBResult<()> {
+        let query = "INSERT INTO stream_notifications \
+            (channel_id, message_id, platform, user_id, username, title, \
+            url, image_url, started_at, peak_viewers) \
+            VALUES (?,?,?,?,?,?,?,?,?,?)";
+        sqlx::query(query)
+            .bind(channel)
+            .bind(message)
+            .bind(live.stream.platform as u8)
+            .bind(&live.stream.user_id)
+            .bind(&live.stream.username)
+            .bind(&live.stream.title)
+            .bind(&live.stream.url)
+            .bind(&live.image_url)
+            .bind(live.started_at)
+            .bind(live.peak_viewers)
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    /// Store the current title and peak viewers of all notifications of the stream
+    pub async fn update_stream_notifications(&self, live: &LiveStream) -> DBResult<()> {
+        let query = "UPDATE stream_notifications SET title=?, peak_viewers=? \
+            WHERE platform=? AND user_id=?";
+        sqlx::query(query)
+            .bind(&live.stream.title)
+            .bind(live.peak_viewers)
+            .bind(live.stream.platform as u8)
+            .bind(&live.stream.user_id)
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    pub async fn remove_stream_notifications(
+        &self,
+        platform: Platform,
+        user_id: &str,
+    ) -> DBResult<()> {
+        sqlx::query("DELETE FROM stream_notifications WHERE platform=? AND user_id=?")
+            .bind(platform as u8)
+            .bind(user_id)
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    /// Streams that were live when the bot stopped, with their notifications
+    pub async fn get_stream_notifications(
+        &self,
+    ) -> DBResult<HashMap<(Platform, String), LiveStream>> {
+        let notifications =
+            sqlx::query_as::<_, StreamNotification>("SELECT * FROM stream_notifications")
+                .fetch_all(&self.pool)
+                .await?;
+        let mut streams: HashMap<_, LiveStream> = HashMap::new();
+        for notification in notifications {
+            let StreamNotification {
+                channel_id,
+                message_id,
+                stream,
+                image_url,
+                started_at,
+                peak_viewers,
+            } = notification;
+            let live = streams
+                .entry((stream.platform, stream.user_id.clone()))
+                .or_insert_with(|| {
+                    let mut live = LiveStream::new(stream, image_url);
+                    live.started_at = started_at;
+                    live.peak_viewers = peak_viewers;
+                    live
+                });
+            live.notifications.push((channel_id, message_id));
+        }
+        Ok(streams)
+    }
+
     // -------------
     // Table: guilds
     // -------------
@@ -451,36 +740,388 @@ WHERE
         Ok(())
     }
 
-    // -------------------
-    // Table: bggame_stats
-    // -------------------
+    pub async fn update_guild_mod_log(&self, guild: u64, channel: Option<u64>) -> DBResult<()> {
+        sqlx::query("UPDATE guilds SET mod_log=? WHERE guild_id=?")
+            .bind(channel)
+            .bind(guild)
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    // --------------
+    // Table: mod_log
+    // --------------
 
-    pub async fn increment_bggame_score(&self, user: u64) -> DBResult<()> {
+    pub async fn insert_mod_log(&self, entry: &ModLogEntry) -> DBResult<()> {
+        let query = "INSERT INTO mod_log \
+            (guild_id, action, user_id, target_id, details, timestamp) \
+            VALUES (?,?,?,?,?,?)";
+        sqlx::query(query)
+            .bind(entry.guild_id)
+            .bind(entry.action.to_u8())
+            .bind(entry.user_id)
+            .bind(entry.target_id)
+            .bind(&entry.details)
+            .bind(entry.timestamp)
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    /// Most recent entries of the guild, only those performed by
+    /// or affecting `user` if specified
+    pub async fn get_mod_log(
+        &self,
+        guild: u64,
+        user: Option<u64>,
+        amount: u32,
+    ) -> DBResult<Vec<ModLogEntry>> {
+        let entries = match user {
+            Some(user) => {
+                let query = "SELECT * FROM mod_log \
+                    WHERE guild_id=? AND (user_id=? OR target_id=?) \
+                    ORDER BY timestamp DESC, entry_id DESC LIMIT ?";
+                sqlx::query_as(query)
+                    .bind(guild)
+                    .bind(user)
+                    .bind(user)
+                    .bind(amount)
+                    .fetch_all(&self.pool)
+                    .await?
+            }
+            None => {
+                let query = "SELECT * FROM mod_log WHERE guild_id=? \
+                    ORDER BY timestamp DESC, entry_id DESC LIMIT ?";
+                sqlx::query_as(query)
+                    .bind(guild)
+                    .bind(amount)
+                    .fetch_all(&self.pool)
+                    .await?
+            }
+        };
+        Ok(entries)
+    }
+
+    // --------------------------
+    // Table: command_permissions
+    // --------------------------
+
+    pub async fn get_command_permissions(
+        &self,
+    ) -> DBResult<HashMap<GuildId, Vec<CommandPermission>>> {
+        let mut permissions: HashMap<_, Vec<_>> = HashMap::new();
+        let mut rows = sqlx::query_as::<_, CommandPermission>("SELECT * FROM command_permissions")
+            .fetch(&self.pool);
+        while let Some(row) = rows.next().await {
+            match row {
+                Ok(rule) => permissions
+                    .entry(GuildId(rule.guild_id))
+                    .or_default()
+                    .push(rule),
+                Err(why) => warn!("Error while getting command permissions from DB: {}", why),
+            }
+        }
+        Ok(permissions)
+    }
+
+    pub async fn set_command_permission(&self, rule: &CommandPermission) -> DBResult<()> {
+        let roles = rule
+            .roles
+            .iter()
+            .map(|role| role.to_string())
+            .collect::<Vec<_>>()
+            .join(" ");
+        let query = "REPLACE INTO command_permissions \
+            (guild_id, channel_id, name, enabled, roles) \
+            VALUES (?,?,?,?,?)";
+        sqlx::query(query)
+            .bind(rule.guild_id)
+            .bind(rule.channel_id.unwrap_or(0))
+            .bind(&rule.name)
+            .bind(rule.enabled)
+            .bind(roles)
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    pub async fn remove_command_permission(
+        &self,
+        guild: u64,
+        channel: Option<u64>,
+        name: &str,
+    ) -> DBResult<()> {
+        let query = "DELETE FROM command_permissions \
+            WHERE guild_id=? AND channel_id=? AND name=?";
+        sqlx::query(query)
+            .bind(guild)
+            .bind(channel.unwrap_or(0))
+            .bind(name)
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    // ----------------------------------------
+    // Table: bggame_scores / bg_season_winners
+    // ----------------------------------------
+
+    /// Increment the user's score for the guild in the current season
+    pub async fn increment_bggame_score(&self, user: u64, guild: Option<u64>) -> DBResult<()> {
         let query = r#"
 INSERT INTO
-    bggame_stats(discord_id, score)
+    bggame_scores(discord_id, guild_id, season, score)
 VALUES
-    (?,1) ON DUPLICATE KEY
+    (?,?,?,1) ON DUPLICATE KEY
 UPDATE
     score = score + 1"#;
-        sqlx::query(&query).bind(user).execute(&self.pool).await?;
+        sqlx::query(&query)
+            .bind(user)
+            .bind(guild.unwrap_or(0))
+            .bind(datetime::season(&Utc::now()))
+            .execute(&self.pool)
+            .await?;
         Ok(())
     }
 
-    pub async fn get_bggame_score(&self, user: u64) -> DBResult<u32> {
-        let (_, score): (u64, u32) =
-            sqlx::query_as("SELECT * FROM bggame_stats WHERE discord_id=?")
-                .bind(user)
-                .fetch_one(&self.pool)
+    /// Total score of the user across all guilds, either of all time or of a season
+    pub async fn get_bggame_score(&self, user: u64, season: Option<u32>) -> DBResult<u32> {
+        let mut query = String::from(
+            "SELECT CAST(SUM(score) AS UNSIGNED) FROM bggame_scores WHERE discord_id=?",
+        );
+        if season.is_some() {
+            query.push_str(" AND season=?");
+        }
+        let mut query = sqlx::query_as::<_, (Option<u64>,)>(&query).bind(user);
+        if let Some(season) = season {
+            query = query.bind(season);
+        }
+        let (score,) = query.fetch_one(&self.pool).await?;
+        match score {
+            Some(score) => Ok(score as u32),
+            None => bail!("No bggame score for user {}", user),
+        }
+    }
+
+    /// Summed up scores of all users, optionally restricted to a guild and a season
+    pub async fn get_bggame_scores(
+        &self,
+        guild: Option<u64>,
+        season: Option<u32>,
+    ) -> DBResult<Vec<(u64, u32)>> {
+        let mut query = String::from(
+            "SELECT discord_id, CAST(SUM(score) AS UNSIGNED) FROM bggame_scores WHERE 1",
+        );
+        if guild.is_some() {
+            query.push_str(" AND guild_id=?");
+        }
+        if season.is_some() {
+            query.push_str(" AND season=?");
+        }
+        query.push_str(" GROUP BY discord_id");
+        let mut query = sqlx::query_as::<_, (u64, u64)>(&query);
+        if let Some(guild) = guild {
+            query = query.bind(guild);
+        }
+        if let Some(season) = season {
+            query = query.bind(season);
+        }
+        let scores = query
+            .fetch_all(&self.pool)
+            .await?
+            .into_iter()
+            .map(|(user, score)| (user, score as u32))
+            .collect();
+        Ok(scores)
+    }
+
+    /// Store the global winner and the winner of each guild for a finished season.
+    /// Does nothing if the season has been archived already.
+    pub async fn archive_bg_season(&self, season: u32) -> DBResult<()> {
+        let archived: Option<(u32,)> =
+            sqlx::query_as("SELECT season FROM bg_season_winners WHERE season=? LIMIT 1")
+                .bind(season)
+                .fetch_optional(&self.pool)
                 .await?;
-        Ok(score)
+        if archived.is_some() {
+            return Ok(());
+        }
+        let scores: Vec<(u64, u64, u32)> =
+            sqlx::query_as("SELECT discord_id, guild_id, score FROM bggame_scores WHERE season=?")
+                .bind(season)
+                .fetch_all(&self.pool)
+                .await?;
+        let mut global = HashMap::new();
+        let mut winners: HashMap<u64, (u64, u32)> = HashMap::new();
+        for (user, guild, score) in scores {
+            *global.entry(user).or_insert(0) += score;
+            // Guild id 0 contains games outside of guilds
+            if guild == 0 {
+                continue;
+            }
+            let winner = winners.entry(guild).or_insert((user, score));
+            if (score, winner.0) > (winner.1, user) {
+                *winner = (user, score);
+            }
+        }
+        let global_winner = global
+            .into_iter()
+            .max_by(|(user_a, a), (user_b, b)| a.cmp(b).then_with(|| user_b.cmp(user_a)));
+        if let Some((user, score)) = global_winner {
+            winners.insert(0, (user, score));
+        }
+        let mut tx = self.pool.begin().await?;
+        for (guild, (user, score)) in winners {
+            sqlx::query(
+                "INSERT IGNORE INTO bg_season_winners(season,guild_id,discord_id,score) \
+                VALUES (?,?,?,?)",
+            )
+            .bind(season)
+            .bind(guild)
+            .bind(user)
+            .bind(score)
+            .execute(&mut tx)
+            .await?;
+        }
+        tx.commit().await?;
+        Ok(())
+    }
+
+    /// Season, user id, and score of past season winners, newest first.
+    /// Without guild, the global winners are returned.
+    pub async fn get_bg_season_winners(
+        &self,
+        guild: Option<u64>,
+    ) -> DBResult<Vec<(u32, u64, u32)>> {
+        let winners = sqlx::query_as(
+            "SELECT season, discord_id, score FROM bg_season_winners \
+            WHERE guild_id=? ORDER BY season DESC",
+        )
+        .bind(guild.unwrap_or(0))
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(winners)
+    }
+
+    // ----------------------
+    // Table: bg_mapset_stats
+    // ----------------------
+
+    /// Record a round of the background game. `guess_secs` is
+    /// the time it took to guess the mapset, if it was guessed at all.
+    pub async fn record_bg_round(
+        &self,
+        mapset_id: u32,
+        guess_secs: Option<f32>,
+        hints: usize,
+    ) -> DBResult<()> {
+        let query = r#"
+INSERT INTO
+    bg_mapset_stats(mapset_id, shown, guessed, guess_secs, hints)
+VALUES
+    (?,1,?,?,?) ON DUPLICATE KEY
+UPDATE
+    shown = shown + 1,
+    guessed = guessed + VALUES(guessed),
+    guess_secs = guess_secs + VALUES(guess_secs),
+    hints = hints + VALUES(hints)"#;
+        sqlx::query(&query)
+            .bind(mapset_id)
+            .bind(guess_secs.is_some() as u32)
+            .bind(guess_secs.unwrap_or(0.0) as f64)
+            .bind(hints as u32)
+            .execute(&self.pool)
+            .await?;
+        Ok(())
     }
 
-    pub async fn all_bggame_scores(&self) -> DBResult<Vec<(u64, u32)>> {
-        let scores = sqlx::query_as("SELECT * FROM bggame_stats")
+    pub async fn get_bg_mapset_stats(&self) -> DBResult<HashMap<u32, BgMapsetStats>> {
+        let query = "SELECT mapset_id, shown, guessed, guess_secs, hints FROM bg_mapset_stats";
+        let stats = sqlx::query_as::<_, (u32, u32, u32, f64, u32)>(query)
             .fetch_all(&self.pool)
+            .await?
+            .into_iter()
+            .map(|(mapset_id, shown, guessed, guess_secs, hints)| {
+                let stats = BgMapsetStats {
+                    shown,
+                    guessed,
+                    guess_secs,
+                    hints,
+                };
+                (mapset_id, stats)
+            })
+            .collect();
+        Ok(stats)
+    }
+
+    // -------------------------------------
+    // Table: bg_sessions / bg_session_scores
+    // -------------------------------------
+
+    /// Store a finished bg game session, `scores` consists of
+    /// user id, points, and amount of correct guesses
+    pub async fn insert_bg_session(
+        &self,
+        channel: u64,
+        mode: GameMode,
+        rounds: usize,
+        scores: &[(u64, u32, u32)],
+    ) -> DBResult<()> {
+        let mut tx = self.pool.begin().await?;
+        sqlx::query("INSERT INTO bg_sessions(channel_id,mode,rounds) VALUES (?,?,?)")
+            .bind(channel)
+            .bind(mode as u8)
+            .bind(rounds as u32)
+            .execute(&mut tx)
             .await?;
-        Ok(scores)
+        let (session_id,): (u64,) = sqlx::query_as("SELECT LAST_INSERT_ID()")
+            .fetch_one(&mut tx)
+            .await?;
+        for (user, points, guesses) in scores {
+            sqlx::query(
+                "INSERT INTO bg_session_scores(session_id,user_id,points,guesses) VALUES (?,?,?,?)",
+            )
+            .bind(session_id)
+            .bind(user)
+            .bind(points)
+            .bind(guesses)
+            .execute(&mut tx)
+            .await?;
+        }
+        tx.commit().await?;
+        Ok(())
+    }
+
+    /// Most recent sessions the user scored in, newest first.
+    /// Placements are ranked by points and then guesses, just like `Session::standings`
+    pub async fn get_bg_sessions(&self, user: u64, amount: u32) -> DBResult<Vec<BgSessionEntry>> {
+        let query = r#"
+SELECT
+    s.ended_at,
+    s.rounds,
+    sc.points,
+    sc.guesses,
+    (SELECT COUNT(*) FROM bg_session_scores WHERE session_id = s.session_id) AS players,
+    (
+        SELECT COUNT(*) + 1 FROM bg_session_scores
+        WHERE session_id = s.session_id
+            AND (points > sc.points OR (points = sc.points AND guesses > sc.guesses))
+    ) AS placement
+FROM
+    bg_sessions AS s
+    JOIN bg_session_scores AS sc ON s.session_id = sc.session_id
+WHERE
+    sc.user_id = ?
+ORDER BY
+    s.ended_at DESC
+LIMIT ?"#;
+        let sessions = sqlx::query_as::<_, BgSessionEntry>(query)
+            .bind(user)
+            .bind(amount)
+            .fetch_all(&self.pool)
+            .await?;
+        Ok(sessions)
     }
 
     // ------------------
@@ -543,6 +1184,246 @@ VALUES
         Ok(())
     }
 
+    // --------------------------
+    // Table: higherlower_scores
+    // --------------------------
+
+    /// Store the streak if it's the user's best, returns whether it was
+    pub async fn update_hl_highscore(
+        &self,
+        user: u64,
+        version: HlVersion,
+        streak: u32,
+    ) -> DBResult<bool> {
+        let previous: Option<(u32,)> = sqlx::query_as(
+            "SELECT highscore FROM higherlower_scores WHERE discord_id=? AND version=?",
+        )
+        .bind(user)
+        .bind(version as u8)
+        .fetch_optional(&self.pool)
+        .await?;
+        if previous.map_or(false, |(highscore,)| highscore >= streak) {
+            return Ok(false);
+        }
+        // Keeps the higher score even if another game finished in the meantime
+        let query = "INSERT INTO higherlower_scores(discord_id,version,highscore) VALUES (?,?,?) \
+            ON DUPLICATE KEY UPDATE highscore = GREATEST(highscore, VALUES(highscore))";
+        sqlx::query(query)
+            .bind(user)
+            .bind(version as u8)
+            .bind(streak)
+            .execute(&self.pool)
+            .await?;
+        Ok(true)
+    }
+
+    /// Highscores of all users, best first
+    pub async fn get_hl_highscores(&self, version: HlVersion) -> DBResult<Vec<(u64, u32)>> {
+        let query = "SELECT discord_id, highscore FROM higherlower_scores \
+            WHERE version=? ORDER BY highscore DESC";
+        let scores = sqlx::query_as(query)
+            .bind(version as u8)
+            .fetch_all(&self.pool)
+            .await?;
+        Ok(scores)
+    }
+
+    // -------------------------
+    // Table: bg_channel_config
+    // -------------------------
+
+    pub async fn get_bg_config(&self, channel: u64) -> DBResult<Option<BgConfig>> {
+        let query = "SELECT mode, included, excluded, reveal, hint_cooldown, auto_stop \
+            FROM bg_channel_config WHERE channel_id=?";
+        let config = sqlx::query_as::<_, (u8, u32, u32, String, u32, u32)>(query)
+            .bind(channel)
+            .fetch_optional(&self.pool)
+            .await?
+            .map(
+                |(mode, included, excluded, reveal, hint_cooldown, auto_stop)| BgConfig {
+                    mode: GameMode::from(mode),
+                    included: MapsetTags::from_bits_truncate(included),
+                    excluded: MapsetTags::from_bits_truncate(excluded),
+                    reveal: reveal.parse().unwrap_or_default(),
+                    hint_cooldown,
+                    auto_stop,
+                },
+            );
+        Ok(config)
+    }
+
+    pub async fn set_bg_config(&self, channel: u64, config: &BgConfig) -> DBResult<()> {
+        let query = "REPLACE INTO bg_channel_config \
+            (channel_id, mode, included, excluded, reveal, hint_cooldown, auto_stop) \
+            VALUES (?,?,?,?,?,?,?)";
+        sqlx::query(query)
+            .bind(channel)
+            .bind(config.mode as u8)
+            .bind(config.included.bits())
+            .bind(config.excluded.bits())
+            .bind(config.reveal.to_string())
+            .bind(config.hint_cooldown)
+            .bind(config.auto_stop)
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    pub async fn remove_bg_config(&self, channel: u64) -> DBResult<()> {
+        sqlx::query("DELETE FROM bg_channel_config WHERE channel_id=?")
+            .bind(channel)
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    // -----------------
+    // Table: bg_hashes
+    // -----------------
+
+    /// Perceptual hashes of the stored backgrounds
+    pub async fn get_bg_hashes(&self) -> DBResult<HashMap<u32, u64>> {
+        let hashes = sqlx::query_as::<_, (u32, u64)>("SELECT mapset_id, hash FROM bg_hashes")
+            .fetch_all(&self.pool)
+            .await?
+            .into_iter()
+            .collect();
+        Ok(hashes)
+    }
+
+    pub async fn set_bg_hash(&self, mapset_id: u32, hash: u64) -> DBResult<()> {
+        sqlx::query("REPLACE INTO bg_hashes(mapset_id,hash) VALUES (?,?)")
+            .bind(mapset_id)
+            .bind(hash)
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    // ------------------
+    // Table: bg_aliases
+    // ------------------
+
+    pub async fn get_bg_aliases(&self, mapset_id: u32) -> DBResult<Vec<BgAlias>> {
+        let query = "SELECT kind, alias, added_by FROM bg_aliases WHERE mapset_id=?";
+        let aliases = sqlx::query_as::<_, (u8, String, Option<u64>)>(query)
+            .bind(mapset_id)
+            .fetch_all(&self.pool)
+            .await?
+            .into_iter()
+            .map(|(kind, alias, added_by)| BgAlias {
+                kind: AliasKind::from_u8(kind),
+                alias,
+                added_by,
+            })
+            .collect();
+        Ok(aliases)
+    }
+
+    /// Already existing aliases are ignored
+    pub async fn add_bg_aliases(
+        &self,
+        mapset_id: u32,
+        aliases: &[(AliasKind, String)],
+        added_by: Option<u64>,
+    ) -> DBResult<()> {
+        let mut tx = self.pool.begin().await?;
+        for (kind, alias) in aliases {
+            sqlx::query(
+                "INSERT IGNORE INTO bg_aliases(mapset_id,kind,alias,added_by) VALUES (?,?,?,?)",
+            )
+            .bind(mapset_id)
+            .bind(kind.to_u8())
+            .bind(alias)
+            .bind(added_by)
+            .execute(&mut tx)
+            .await?;
+        }
+        tx.commit().await?;
+        Ok(())
+    }
+
+    // ---------------------------------
+    // Table: bg_tag_votes / bg_tag_locks
+    // ---------------------------------
+
+    /// Store the user's vote and return the amount of votes for the tag on the mapset
+    pub async fn add_bg_tag_vote(
+        &self,
+        mapset_id: u32,
+        user: u64,
+        tag: MapsetTags,
+    ) -> DBResult<u32> {
+        sqlx::query("INSERT IGNORE INTO bg_tag_votes(mapset_id,user_id,tag) VALUES (?,?,?)")
+            .bind(mapset_id)
+            .bind(user)
+            .bind(tag.bits())
+            .execute(&self.pool)
+            .await?;
+        let (votes,): (i64,) =
+            sqlx::query_as("SELECT COUNT(*) FROM bg_tag_votes WHERE mapset_id=? AND tag=?")
+                .bind(mapset_id)
+                .bind(tag.bits())
+                .fetch_one(&self.pool)
+                .await?;
+        Ok(votes as u32)
+    }
+
+    pub async fn remove_bg_tag_vote(
+        &self,
+        mapset_id: u32,
+        user: u64,
+        tag: MapsetTags,
+    ) -> DBResult<()> {
+        sqlx::query("DELETE FROM bg_tag_votes WHERE mapset_id=? AND user_id=? AND tag=?")
+            .bind(mapset_id)
+            .bind(user)
+            .bind(tag.bits())
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    /// Tags of the mapset that can not be changed through votes
+    pub async fn get_bg_tag_locks(&self, mapset_id: u32) -> DBResult<MapsetTags> {
+        let locks = sqlx::query_as::<_, (u32,)>("SELECT tag FROM bg_tag_locks WHERE mapset_id=?")
+            .bind(mapset_id)
+            .fetch_all(&self.pool)
+            .await?
+            .into_iter()
+            .fold(MapsetTags::empty(), |locks, (tag,)| {
+                locks | MapsetTags::from_bits_truncate(tag)
+            });
+        Ok(locks)
+    }
+
+    pub async fn lock_bg_tags(&self, mapset_id: u32, tags: MapsetTags, user: u64) -> DBResult<()> {
+        let mut tx = self.pool.begin().await?;
+        for tag in tags {
+            sqlx::query("REPLACE INTO bg_tag_locks(mapset_id,tag,locked_by) VALUES (?,?,?)")
+                .bind(mapset_id)
+                .bind(tag.bits())
+                .bind(user)
+                .execute(&mut tx)
+                .await?;
+        }
+        tx.commit().await?;
+        Ok(())
+    }
+
+    pub async fn unlock_bg_tags(&self, mapset_id: u32, tags: MapsetTags) -> DBResult<()> {
+        let mut tx = self.pool.begin().await?;
+        for tag in tags {
+            sqlx::query("DELETE FROM bg_tag_locks WHERE mapset_id=? AND tag=?")
+                .bind(mapset_id)
+                .bind(tag.bits())
+                .execute(&mut tx)
+                .await?;
+        }
+        tx.commit().await?;
+        Ok(())
+    }
+
     pub async fn set_tags_mapset(
         &self,
         mapset_id: u32,
@@ -741,12 +1622,23 @@ fn mania_stars_mods_column(mods: GameMods) -> DBResult<&'static str> {
     Ok(m)
 }
 
+/// Ranked maps never change, others are re-checked periodically
+fn needs_refresh(status: ApprovalStatus, last_checked: DateTime<Utc>) -> bool {
+    let interval = match status {
+        Ranked | Approved | Loved => return false,
+        Qualified => Duration::hours(6),
+        Graveyard => Duration::weeks(4),
+        _ => Duration::days(1),
+    };
+    Utc::now() - last_checked > interval
+}
+
 async fn _insert_beatmap<'c, E>(executor: E, map: &Beatmap) -> DBResult<()>
 where
     E: sqlx::prelude::Executor<'c, Database = MySql>,
 {
     let query = r#"
-INSERT IGNORE INTO maps (
+INSERT INTO maps (
     beatmap_id,
     beatmapset_id,
     mode,
@@ -763,7 +1655,21 @@ INSERT IGNORE INTO maps (
     count_slider,
     count_spinner,
     max_combo
-) VALUES (?,?,?,?,?,?,?,?,?,?,?,?,?,?,?,?)"#;
+) VALUES (?,?,?,?,?,?,?,?,?,?,?,?,?,?,?,?)
+ON DUPLICATE KEY UPDATE
+    version=VALUES(version),
+    seconds_drain=VALUES(seconds_drain),
+    seconds_total=VALUES(seconds_total),
+    bpm=VALUES(bpm),
+    stars=VALUES(stars),
+    diff_cs=VALUES(diff_cs),
+    diff_od=VALUES(diff_od),
+    diff_ar=VALUES(diff_ar),
+    diff_hp=VALUES(diff_hp),
+    count_circle=VALUES(count_circle),
+    count_slider=VALUES(count_slider),
+    count_spinner=VALUES(count_spinner),
+    max_combo=VALUES(max_combo)"#;
     sqlx::query(query)
         .bind(map.beatmap_id)
         .bind(map.beatmapset_id)
@@ -791,7 +1697,7 @@ where
     E: sqlx::prelude::Executor<'c, Database = MySql>,
 {
     let query = r#"
-INSERT IGNORE INTO mapsets (
+INSERT INTO mapsets (
     beatmapset_id,
     artist,
     title,
@@ -801,7 +1707,15 @@ INSERT IGNORE INTO mapsets (
     language,
     approval_status,
     approved_date
-) VALUES (?,?,?,?,?,?,?,?,?)"#;
+) VALUES (?,?,?,?,?,?,?,?,?)
+ON DUPLICATE KEY UPDATE
+    artist=VALUES(artist),
+    title=VALUES(title),
+    genre=VALUES(genre),
+    language=VALUES(language),
+    approval_status=VALUES(approval_status),
+    approved_date=VALUES(approved_date),
+    last_checked=CURRENT_TIMESTAMP"#;
     sqlx::query(query)
         .bind(map.beatmapset_id)
         .bind(&map.artist)
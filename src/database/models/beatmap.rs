@@ -1,4 +1,4 @@
-use chrono::NaiveDateTime;
+use chrono::{DateTime, NaiveDateTime, Utc};
 use rosu::models::Beatmap;
 use sqlx::{mysql::MySqlRow, FromRow, Row};
 
@@ -35,17 +35,20 @@ pub struct DBMapSet {
     approved_date: Option<NaiveDateTime>,
 }
 
-pub struct BeatmapWrapper(Beatmap);
+pub struct BeatmapWrapper {
+    map: Beatmap,
+    pub last_checked: DateTime<Utc>,
+}
 
-impl From<Beatmap> for BeatmapWrapper {
-    fn from(map: Beatmap) -> Self {
-        Self(map)
+impl BeatmapWrapper {
+    pub fn map(&self) -> &Beatmap {
+        &self.map
     }
 }
 
 impl Into<Beatmap> for BeatmapWrapper {
     fn into(self) -> Beatmap {
-        self.0
+        self.map
     }
 }
 
@@ -80,6 +83,9 @@ impl<'c> FromRow<'c, MySqlRow> for BeatmapWrapper {
         map.language = language.into();
         map.approval_status = status.into();
         map.approved_date = row.get("approved_date");
-        Ok(Self(map))
+        Ok(Self {
+            map,
+            last_checked: row.get("last_checked"),
+        })
     }
 }
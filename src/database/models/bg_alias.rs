@@ -0,0 +1,52 @@
+use std::{fmt, str::FromStr};
+
+/// Whether an alias stands for the title or the artist of a mapset
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum AliasKind {
+    Title,
+    Artist,
+}
+
+impl AliasKind {
+    pub fn from_u8(kind: u8) -> Self {
+        match kind {
+            1 => AliasKind::Artist,
+            _ => AliasKind::Title,
+        }
+    }
+
+    pub fn to_u8(self) -> u8 {
+        match self {
+            AliasKind::Title => 0,
+            AliasKind::Artist => 1,
+        }
+    }
+}
+
+impl FromStr for AliasKind {
+    type Err = ();
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "title" | "t" => Ok(AliasKind::Title),
+            "artist" | "a" => Ok(AliasKind::Artist),
+            _ => Err(()),
+        }
+    }
+}
+
+impl fmt::Display for AliasKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            AliasKind::Title => f.write_str("title"),
+            AliasKind::Artist => f.write_str("artist"),
+        }
+    }
+}
+
+/// Alternative name of a mapset that counts as correct answer in the bg game,
+/// `added_by` is `None` for names that came from the osu!api
+pub struct BgAlias {
+    pub kind: AliasKind,
+    pub alias: String,
+    pub added_by: Option<u64>,
+}
@@ -0,0 +1,35 @@
+// Below this amount of rounds, the stats are not meaningful
+const MIN_SHOWN: u32 = 5;
+// Guesses taking this long count as maximally difficult
+const MAX_GUESS_SECS: f64 = 120.0;
+// Average amount of hints per round that counts as maximally difficult
+const MAX_HINTS: f64 = 3.0;
+
+/// How a mapset performed in the background game
+#[derive(Debug)]
+pub struct BgMapsetStats {
+    pub shown: u32,
+    pub guessed: u32,
+    pub guess_secs: f64,
+    pub hints: u32,
+}
+
+impl BgMapsetStats {
+    /// Difficulty between 0 (easiest) and 1 (hardest),
+    /// `None` if the mapset has not been shown often enough
+    pub fn difficulty(&self) -> Option<f64> {
+        if self.shown < MIN_SHOWN {
+            return None;
+        }
+        let shown = self.shown as f64;
+        let miss_rate = 1.0 - self.guessed as f64 / shown;
+        let avg_secs = if self.guessed > 0 {
+            self.guess_secs / self.guessed as f64
+        } else {
+            MAX_GUESS_SECS
+        };
+        let time = (avg_secs / MAX_GUESS_SECS).min(1.0);
+        let hints = (self.hints as f64 / shown / MAX_HINTS).min(1.0);
+        Some(0.5 * miss_rate + 0.3 * time + 0.2 * hints)
+    }
+}
@@ -0,0 +1,27 @@
+use chrono::{DateTime, Utc};
+use sqlx::{mysql::MySqlRow, FromRow, Row};
+
+/// Result of a single user within a finished bg game session
+pub struct BgSessionEntry {
+    pub ended_at: DateTime<Utc>,
+    pub rounds: u32,
+    pub players: u32,
+    pub placement: u32,
+    pub points: u32,
+    pub guesses: u32,
+}
+
+impl<'c> FromRow<'c, MySqlRow> for BgSessionEntry {
+    fn from_row(row: &MySqlRow) -> Result<BgSessionEntry, sqlx::Error> {
+        let players: i64 = row.get("players");
+        let placement: i64 = row.get("placement");
+        Ok(BgSessionEntry {
+            ended_at: row.get("ended_at"),
+            rounds: row.get("rounds"),
+            players: players as u32,
+            placement: placement as u32,
+            points: row.get("points"),
+            guesses: row.get("guesses"),
+        })
+    }
+}
@@ -0,0 +1,126 @@
+use sqlx::{mysql::MySqlRow, FromRow, Row};
+
+/// Whether and for whom a command or a whole group is available
+#[derive(Clone, Debug, PartialEq)]
+pub struct CommandPermission {
+    pub guild_id: u64,
+    /// Channel the rule is restricted to, the whole guild if `None`
+    pub channel_id: Option<u64>,
+    /// Lowercase name of the command or group
+    pub name: String,
+    pub enabled: bool,
+    /// Members need one of these roles, anyone can use it if empty
+    pub roles: Vec<u64>,
+}
+
+/// Why a member can't use a command
+#[derive(Debug, PartialEq)]
+pub enum PermissionDenial {
+    /// Disabled for the channel or for the whole guild
+    Disabled {
+        channel: bool,
+    },
+    MissingRole(Vec<u64>),
+}
+
+/// Check the rule that applies to the command, channel rules take precedence
+/// over guild rules and command rules over group rules
+pub fn check_permission(
+    rules: &[CommandPermission],
+    channel: u64,
+    command: &str,
+    group: &str,
+    member_roles: &[u64],
+) -> Result<(), PermissionDenial> {
+    let scopes = [Some(channel), None];
+    let names = [command, group];
+    let rule = scopes
+        .iter()
+        .flat_map(|scope| names.iter().map(move |name| (scope, name)))
+        .find_map(|(scope, name)| {
+            rules
+                .iter()
+                .find(|rule| rule.channel_id == *scope && rule.name.eq_ignore_ascii_case(name))
+        });
+    match rule {
+        Some(rule) if !rule.enabled => Err(PermissionDenial::Disabled {
+            channel: rule.channel_id.is_some(),
+        }),
+        Some(rule)
+            if !rule.roles.is_empty()
+                && !rule.roles.iter().any(|role| member_roles.contains(role)) =>
+        {
+            Err(PermissionDenial::MissingRole(rule.roles.clone()))
+        }
+        _ => Ok(()),
+    }
+}
+
+impl<'c> FromRow<'c, MySqlRow> for CommandPermission {
+    fn from_row(row: &MySqlRow) -> Result<CommandPermission, sqlx::Error> {
+        let channel_id: u64 = row.get("channel_id");
+        let roles: &str = row.get("roles");
+        Ok(CommandPermission {
+            guild_id: row.get("guild_id"),
+            channel_id: Some(channel_id).filter(|&id| id != 0),
+            name: row.get("name"),
+            enabled: row.get("enabled"),
+            roles: roles
+                .split_whitespace()
+                .filter_map(|role| role.parse().ok())
+                .collect(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rule(
+        channel_id: Option<u64>,
+        name: &str,
+        enabled: bool,
+        roles: &[u64],
+    ) -> CommandPermission {
+        CommandPermission {
+            guild_id: 1,
+            channel_id,
+            name: name.to_owned(),
+            enabled,
+            roles: roles.to_vec(),
+        }
+    }
+
+    #[test]
+    fn precedence() {
+        let rules = vec![
+            rule(None, "fun", false, &[]),
+            rule(Some(5), "fun", true, &[]),
+            rule(Some(5), "minesweeper", false, &[]),
+        ];
+        assert_eq!(
+            check_permission(&rules, 4, "ding", "Fun", &[]),
+            Err(PermissionDenial::Disabled { channel: false })
+        );
+        assert_eq!(check_permission(&rules, 5, "ding", "Fun", &[]), Ok(()));
+        assert_eq!(
+            check_permission(&rules, 5, "minesweeper", "Fun", &[]),
+            Err(PermissionDenial::Disabled { channel: true })
+        );
+        assert_eq!(check_permission(&rules, 4, "recent", "Osu", &[]), Ok(()));
+    }
+
+    #[test]
+    fn role_restrictions() {
+        let rules = vec![rule(None, "streamtracking", true, &[7, 8])];
+        assert_eq!(
+            check_permission(&rules, 4, "addstream", "StreamTracking", &[1]),
+            Err(PermissionDenial::MissingRole(vec![7, 8]))
+        );
+        assert_eq!(
+            check_permission(&rules, 4, "addstream", "StreamTracking", &[1, 8]),
+            Ok(())
+        );
+    }
+}
@@ -1,9 +1,23 @@
 mod beatmap;
+mod bg_alias;
+mod bg_mapset_stats;
+mod bg_session;
+mod command_permission;
 mod map_tags;
+mod mod_log;
+mod rank_role;
 mod ratios;
+mod role_menu;
 mod streams;
 
 pub use beatmap::{BeatmapWrapper, DBMap, DBMapSet};
+pub use bg_alias::{AliasKind, BgAlias};
+pub use bg_mapset_stats::BgMapsetStats;
+pub use bg_session::BgSessionEntry;
+pub use command_permission::{check_permission, CommandPermission, PermissionDenial};
 pub use map_tags::MapsetTagWrapper;
+pub use mod_log::{ModAction, ModLogEntry};
+pub use rank_role::{rank_role_changes, RankRole};
 pub use ratios::Ratios;
-pub use streams::{StreamTrack, TwitchUser};
+pub use role_menu::{MenuDenial, RoleMenu, RoleMenuEntry};
+pub use streams::{StreamNotification, StreamTrack};
@@ -0,0 +1,108 @@
+use chrono::{DateTime, Utc};
+use sqlx::{mysql::MySqlRow, FromRow, Row};
+use std::fmt;
+
+/// Kind of an action that is written to the mod log
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ModAction {
+    Prune,
+    RoleAssign,
+    RoleMenu,
+    RoleAdded,
+    RoleRemoved,
+    Authorities,
+    RankRoles,
+    LogChannel,
+    Permissions,
+}
+
+impl ModAction {
+    pub fn from_u8(action: u8) -> Self {
+        match action {
+            1 => ModAction::RoleAssign,
+            2 => ModAction::RoleMenu,
+            3 => ModAction::RoleAdded,
+            4 => ModAction::RoleRemoved,
+            5 => ModAction::Authorities,
+            6 => ModAction::RankRoles,
+            7 => ModAction::LogChannel,
+            8 => ModAction::Permissions,
+            _ => ModAction::Prune,
+        }
+    }
+
+    pub fn to_u8(self) -> u8 {
+        match self {
+            ModAction::Prune => 0,
+            ModAction::RoleAssign => 1,
+            ModAction::RoleMenu => 2,
+            ModAction::RoleAdded => 3,
+            ModAction::RoleRemoved => 4,
+            ModAction::Authorities => 5,
+            ModAction::RankRoles => 6,
+            ModAction::LogChannel => 7,
+            ModAction::Permissions => 8,
+        }
+    }
+}
+
+impl fmt::Display for ModAction {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let action = match self {
+            ModAction::Prune => "Messages pruned",
+            ModAction::RoleAssign => "Role assign added",
+            ModAction::RoleMenu => "Role menu created",
+            ModAction::RoleAdded => "Role added",
+            ModAction::RoleRemoved => "Role removed",
+            ModAction::Authorities => "Authorities changed",
+            ModAction::RankRoles => "Rank roles changed",
+            ModAction::LogChannel => "Log channel changed",
+            ModAction::Permissions => "Command permissions changed",
+        };
+        f.write_str(action)
+    }
+}
+
+/// Administrative action or role change within a guild
+#[derive(Clone, Debug)]
+pub struct ModLogEntry {
+    pub guild_id: u64,
+    pub action: ModAction,
+    /// User that performed the action
+    pub user_id: u64,
+    /// User that was affected by the action
+    pub target_id: Option<u64>,
+    pub details: String,
+    pub timestamp: DateTime<Utc>,
+}
+
+impl ModLogEntry {
+    pub fn new(guild_id: u64, action: ModAction, user_id: u64, details: String) -> Self {
+        Self {
+            guild_id,
+            action,
+            user_id,
+            target_id: None,
+            details,
+            timestamp: Utc::now(),
+        }
+    }
+
+    pub fn target(mut self, target_id: u64) -> Self {
+        self.target_id = Some(target_id);
+        self
+    }
+}
+
+impl<'c> FromRow<'c, MySqlRow> for ModLogEntry {
+    fn from_row(row: &MySqlRow) -> Result<ModLogEntry, sqlx::Error> {
+        Ok(ModLogEntry {
+            guild_id: row.get("guild_id"),
+            action: ModAction::from_u8(row.get("action")),
+            user_id: row.get("user_id"),
+            target_id: row.get("target_id"),
+            details: row.get("details"),
+            timestamp: row.get("timestamp"),
+        })
+    }
+}
@@ -0,0 +1,138 @@
+use crate::util::numbers::with_comma_u64;
+
+use rosu::models::GameMode;
+use sqlx::{mysql::MySqlRow, FromRow, Row};
+use std::fmt;
+
+/// Role that linked members of a guild have while their rank is within the range
+#[derive(Clone, Debug)]
+pub struct RankRole {
+    pub guild_id: u64,
+    pub role_id: u64,
+    pub mode: GameMode,
+    /// Whether the range applies to the country rank instead of the global rank
+    pub country: bool,
+    pub rank_min: u32,
+    pub rank_max: u32,
+}
+
+impl RankRole {
+    /// Whether a user with the given global and country rank should have the role.
+    /// Inactive users have rank 0 and never qualify.
+    pub fn matches(&self, global: u32, country: u32) -> bool {
+        let rank = if self.country { country } else { global };
+        rank > 0 && self.rank_min <= rank && rank <= self.rank_max
+    }
+}
+
+impl fmt::Display for RankRole {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mode = match self.mode {
+            GameMode::STD => "osu!",
+            GameMode::TKO => "taiko",
+            GameMode::CTB => "ctb",
+            GameMode::MNA => "mania",
+        };
+        let kind = if self.country { "country" } else { "global" };
+        let min = with_comma_u64(self.rank_min as u64);
+        if self.rank_max == u32::MAX {
+            write!(f, "{} {} rank #{} or worse", mode, kind, min)
+        } else {
+            let max = with_comma_u64(self.rank_max as u64);
+            write!(f, "{} {} rank #{} to #{}", mode, kind, min, max)
+        }
+    }
+}
+
+impl<'c> FromRow<'c, MySqlRow> for RankRole {
+    fn from_row(row: &MySqlRow) -> Result<RankRole, sqlx::Error> {
+        Ok(RankRole {
+            guild_id: row.get("guild_id"),
+            role_id: row.get("role_id"),
+            mode: GameMode::from(row.get::<u8, _>("mode")),
+            country: row.get("country"),
+            rank_min: row.get("rank_min"),
+            rank_max: row.get("rank_max"),
+        })
+    }
+}
+
+/// Roles of `rules` that a member gains and loses given the ranks per mode.
+/// Modes whose ranks are unknown are skipped so that failed requests don't strip roles.
+pub fn rank_role_changes(
+    rules: &[RankRole],
+    ranks: &[(GameMode, Option<(u32, u32)>)],
+    member_roles: &[u64],
+) -> (Vec<u64>, Vec<u64>) {
+    let mut add = Vec::new();
+    let mut remove = Vec::new();
+    for rule in rules {
+        let ranks = ranks
+            .iter()
+            .find(|(mode, _)| *mode == rule.mode)
+            .and_then(|(_, ranks)| *ranks);
+        let (global, country) = match ranks {
+            Some(ranks) => ranks,
+            None => continue,
+        };
+        let has_role = member_roles.contains(&rule.role_id);
+        if rule.matches(global, country) {
+            if !has_role {
+                add.push(rule.role_id);
+            }
+        } else if has_role {
+            remove.push(rule.role_id);
+        }
+    }
+    (add, remove)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rule(role_id: u64, mode: GameMode, country: bool, min: u32, max: u32) -> RankRole {
+        RankRole {
+            guild_id: 0,
+            role_id,
+            mode,
+            country,
+            rank_min: min,
+            rank_max: max,
+        }
+    }
+
+    #[test]
+    fn rank_ranges() {
+        let top = rule(1, GameMode::STD, false, 1, 1000);
+        assert!(top.matches(1, 500));
+        assert!(top.matches(1000, 500));
+        assert!(!top.matches(1001, 1));
+        assert!(!top.matches(0, 0));
+        let country = rule(2, GameMode::STD, true, 1, 50);
+        assert!(country.matches(20_000, 50));
+        assert!(!country.matches(1, 51));
+    }
+
+    #[test]
+    fn member_changes() {
+        let rules = [
+            rule(1, GameMode::STD, false, 1, 999),
+            rule(2, GameMode::STD, false, 1000, 9999),
+            rule(3, GameMode::MNA, false, 1, 9999),
+        ];
+        let ranks = [(GameMode::STD, Some((1234, 50))), (GameMode::MNA, None)];
+        // Mania ranks are unknown so role 3 stays
+        assert_eq!(
+            rank_role_changes(&rules, &ranks, &[1, 3]),
+            (vec![2], vec![1])
+        );
+        assert_eq!(rank_role_changes(&rules, &ranks, &[2]), (vec![], vec![]));
+        // Inactive users lose their roles
+        let ranks = [(GameMode::STD, Some((0, 0)))];
+        assert_eq!(
+            rank_role_changes(&rules, &ranks, &[2, 3]),
+            (vec![], vec![2])
+        );
+    }
+}
@@ -0,0 +1,189 @@
+use serenity::model::channel::ReactionType;
+use std::{collections::HashSet, convert::TryFrom};
+
+/// A role that is assigned through reactions on a message
+#[derive(Clone, Debug)]
+pub struct RoleMenuEntry {
+    /// Emoji as it was given, if `None` any reaction assigns the role
+    pub emoji: Option<String>,
+    pub role: u64,
+    /// Members can only have one role of the same group
+    pub group: Option<String>,
+}
+
+impl RoleMenuEntry {
+    pub fn reaction(&self) -> Option<ReactionType> {
+        self.emoji
+            .as_deref()
+            .and_then(|emoji| ReactionType::try_from(emoji).ok())
+    }
+
+    fn matches(&self, key: &str) -> bool {
+        self.reaction()
+            .and_then(|reaction| emoji_key(&reaction))
+            .map_or(false, |emoji| emoji == key)
+    }
+}
+
+/// Why a member can't get a role of a menu
+#[derive(Debug, PartialEq)]
+pub enum MenuDenial {
+    MissingRole(u64),
+    TooManyRoles(u8),
+}
+
+/// All roles of a reaction role message
+#[derive(Clone, Debug, Default)]
+pub struct RoleMenu {
+    pub entries: Vec<RoleMenuEntry>,
+    /// Role a member needs to get any role of the menu
+    pub required_role: Option<u64>,
+    /// Maximum amount of roles of the menu a member can have
+    pub max_roles: Option<u8>,
+}
+
+impl RoleMenu {
+    /// Entry that is assigned through the given reaction
+    pub fn entry(&self, emoji: &ReactionType) -> Option<&RoleMenuEntry> {
+        let key = emoji_key(emoji)?;
+        // Entries without emoji only catch reactions that no other entry uses
+        self.entries
+            .iter()
+            .find(|entry| entry.matches(&key))
+            .or_else(|| self.entries.iter().find(|entry| entry.emoji.is_none()))
+    }
+
+    /// Roles of the menu that must be removed when the member gets `role`,
+    /// or why the member can't get it
+    pub fn roles_to_replace(
+        &self,
+        role: u64,
+        member_roles: &[u64],
+    ) -> Result<Vec<u64>, MenuDenial> {
+        if let Some(required) = self.required_role {
+            if !member_roles.contains(&required) {
+                return Err(MenuDenial::MissingRole(required));
+            }
+        }
+        let group = self
+            .entries
+            .iter()
+            .find(|entry| entry.role == role)
+            .and_then(|entry| entry.group.as_deref());
+        let mut replaced: Vec<_> = match group {
+            Some(group) => self
+                .entries
+                .iter()
+                .filter(|entry| entry.role != role && entry.group.as_deref() == Some(group))
+                .map(|entry| entry.role)
+                .filter(|role| member_roles.contains(role))
+                .collect(),
+            None => Vec::new(),
+        };
+        replaced.sort_unstable();
+        replaced.dedup();
+        if let Some(max) = self.max_roles {
+            let kept = self
+                .entries
+                .iter()
+                .map(|entry| entry.role)
+                .filter(|r| *r != role && member_roles.contains(r) && !replaced.contains(r))
+                .collect::<HashSet<_>>()
+                .len();
+            if kept >= max as usize {
+                return Err(MenuDenial::TooManyRoles(max));
+            }
+        }
+        Ok(replaced)
+    }
+}
+
+// Custom emojis are identified by their id since their name can change
+fn emoji_key(emoji: &ReactionType) -> Option<String> {
+    if let ReactionType::Custom { id, .. } = emoji {
+        Some(id.0.to_string())
+    } else if let ReactionType::Unicode(emoji) = emoji {
+        Some(emoji.to_owned())
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn menu(entries: &[(&str, u64, Option<&str>)]) -> RoleMenu {
+        let entries = entries
+            .iter()
+            .map(|&(emoji, role, group)| RoleMenuEntry {
+                emoji: Some(emoji.to_owned()),
+                role,
+                group: group.map(str::to_owned),
+            })
+            .collect();
+        RoleMenu {
+            entries,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn entry_by_emoji() {
+        let menu = menu(&[("🍎", 1, None), ("<:osu:123>", 2, None)]);
+        let unicode = ReactionType::Unicode("🍎".to_owned());
+        assert_eq!(menu.entry(&unicode).map(|entry| entry.role), Some(1));
+        let custom = ReactionType::try_from("<:renamed:123>").unwrap();
+        assert_eq!(menu.entry(&custom).map(|entry| entry.role), Some(2));
+        let other = ReactionType::Unicode("🍌".to_owned());
+        assert!(menu.entry(&other).is_none());
+    }
+
+    #[test]
+    fn entry_without_emoji_is_fallback() {
+        let mut menu = menu(&[("🍎", 1, None)]);
+        let any = RoleMenuEntry {
+            emoji: None,
+            role: 2,
+            group: None,
+        };
+        menu.entries.insert(0, any);
+        let unicode = ReactionType::Unicode("🍎".to_owned());
+        assert_eq!(menu.entry(&unicode).map(|entry| entry.role), Some(1));
+        let other = ReactionType::Unicode("🍌".to_owned());
+        assert_eq!(menu.entry(&other).map(|entry| entry.role), Some(2));
+    }
+
+    #[test]
+    fn exclusive_groups() {
+        let menu = menu(&[
+            ("🇪", 1, Some("region")),
+            ("🇺", 2, Some("region")),
+            ("🎵", 3, None),
+        ]);
+        assert_eq!(menu.roles_to_replace(2, &[1, 3]), Ok(vec![1]));
+        assert_eq!(menu.roles_to_replace(3, &[1]), Ok(vec![]));
+    }
+
+    #[test]
+    fn requirements_and_limits() {
+        let mut menu = menu(&[
+            ("🇪", 1, Some("region")),
+            ("🇺", 2, Some("region")),
+            ("🎵", 3, None),
+        ]);
+        menu.max_roles = Some(1);
+        assert_eq!(
+            menu.roles_to_replace(3, &[1]),
+            Err(MenuDenial::TooManyRoles(1))
+        );
+        // Replacing a role of the group doesn't increase the count
+        assert_eq!(menu.roles_to_replace(2, &[1]), Ok(vec![1]));
+        menu.required_role = Some(10);
+        assert_eq!(
+            menu.roles_to_replace(1, &[]),
+            Err(MenuDenial::MissingRole(10))
+        );
+        assert_eq!(menu.roles_to_replace(1, &[10]), Ok(vec![]));
+    }
+}
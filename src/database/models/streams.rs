@@ -1,18 +1,64 @@
+use crate::streams::{render_template, truncate_message, Platform, Stream};
+
+use chrono::{DateTime, Utc};
 use sqlx::{mysql::MySqlRow, FromRow, Row};
+use std::hash::{Hash, Hasher};
 
-#[derive(PartialEq, Eq, Hash)]
+/// A stream tracked in a channel, identified by channel, user, and platform
+#[derive(Clone, Debug)]
 pub struct StreamTrack {
     pub channel_id: u64,
-    pub user_id: u64,
+    pub user_id: String,
+    pub platform: Platform,
+    /// Role that is mentioned in the notification
+    pub role_id: Option<u64>,
+    /// Message sent along the notification, see `streams::render_template`
+    pub template: Option<String>,
 }
 
 impl StreamTrack {
-    pub fn new(channel_id: u64, user_id: u64) -> Self {
+    pub fn new(channel_id: u64, user_id: String, platform: Platform) -> Self {
         Self {
             channel_id,
             user_id,
+            platform,
+            role_id: None,
+            template: None,
         }
     }
+
+    /// Content of the notification message, if any
+    pub fn notification_content(&self, stream: &Stream) -> Option<String> {
+        let template = self
+            .template
+            .as_deref()
+            .and_then(|template| render_template(template, stream).ok());
+        let mut content = match (self.role_id, template) {
+            (Some(role), Some(template)) => format!("<@&{}> {}", role, template),
+            (Some(role), None) => format!("<@&{}>", role),
+            (None, template) => template?,
+        };
+        truncate_message(&mut content);
+        Some(content)
+    }
+}
+
+impl PartialEq for StreamTrack {
+    fn eq(&self, other: &Self) -> bool {
+        self.channel_id == other.channel_id
+            && self.user_id == other.user_id
+            && self.platform == other.platform
+    }
+}
+
+impl Eq for StreamTrack {}
+
+impl Hash for StreamTrack {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.channel_id.hash(state);
+        self.user_id.hash(state);
+        self.platform.hash(state);
+    }
 }
 
 impl<'c> FromRow<'c, MySqlRow> for StreamTrack {
@@ -20,18 +66,46 @@ impl<'c> FromRow<'c, MySqlRow> for StreamTrack {
         Ok(StreamTrack {
             channel_id: row.get("channel_id"),
             user_id: row.get("user_id"),
+            platform: Platform::from_u8(row.get("platform")),
+            role_id: row.get("role_id"),
+            template: row.get("template"),
         })
     }
 }
 
-#[derive(Debug, PartialEq)]
-pub struct TwitchUser {
-    user_id: u64,
-    name: String,
+/// Notification of a stream that was live during the last check
+pub struct StreamNotification {
+    pub channel_id: u64,
+    pub message_id: u64,
+    pub stream: Stream,
+    pub image_url: String,
+    pub started_at: DateTime<Utc>,
+    pub peak_viewers: u32,
 }
 
-impl TwitchUser {
-    pub fn new(user_id: u64, name: String) -> Self {
-        Self { user_id, name }
+impl<'c> FromRow<'c, MySqlRow> for StreamNotification {
+    fn from_row(row: &MySqlRow) -> Result<StreamNotification, sqlx::Error> {
+        let started_at = row.get("started_at");
+        let peak_viewers = row.get("peak_viewers");
+        let stream = Stream {
+            platform: Platform::from_u8(row.get("platform")),
+            user_id: row.get("user_id"),
+            username: row.get("username"),
+            title: row.get("title"),
+            url: row.get("url"),
+            thumbnail_url: String::new(),
+            viewer_count: peak_viewers,
+            started_at: Some(started_at),
+            language: None,
+            game: None,
+        };
+        Ok(StreamNotification {
+            channel_id: row.get("channel_id"),
+            message_id: row.get("message_id"),
+            stream,
+            image_url: row.get("image_url"),
+            started_at,
+            peak_viewers,
+        })
     }
 }
@@ -0,0 +1,71 @@
+use crate::{commands::fun::BgConfig, embeds::EmbedData};
+
+use rosu::models::GameMode;
+
+#[derive(Clone)]
+pub struct BGConfigEmbed {
+    title: &'static str,
+    description: Option<&'static str>,
+    fields: Vec<(String, String, bool)>,
+}
+
+impl BGConfigEmbed {
+    pub fn new(config: &BgConfig, stored: bool) -> Self {
+        let mode = match config.mode {
+            GameMode::MNA => "mania",
+            _ => "std",
+        };
+        let included = if config.included.is_empty() {
+            "Any".to_owned()
+        } else {
+            config.included.join("\n")
+        };
+        let excluded = if config.excluded.is_empty() {
+            "None".to_owned()
+        } else {
+            config.excluded.join("\n")
+        };
+        let hint_cooldown = if config.hint_cooldown == 0 {
+            "None".to_owned()
+        } else {
+            format!("{}s", config.hint_cooldown)
+        };
+        let fields = vec![
+            ("Mode".to_owned(), mode.to_owned(), true),
+            ("Included".to_owned(), included, true),
+            ("Excluded".to_owned(), excluded, true),
+            ("Reveal".to_owned(), config.reveal.to_string(), true),
+            ("Hint cooldown".to_owned(), hint_cooldown, true),
+            (
+                "Auto-stop".to_owned(),
+                format!("{}min", config.auto_stop),
+                true,
+            ),
+        ];
+        let description = if stored {
+            None
+        } else {
+            Some(
+                "No settings stored for this channel, `<bg s` asks for tags.\n\
+                Change a setting with `<bg config [setting] [value]`",
+            )
+        };
+        Self {
+            title: "Background game settings",
+            description,
+            fields,
+        }
+    }
+}
+
+impl EmbedData for BGConfigEmbed {
+    fn title(&self) -> Option<&str> {
+        Some(self.title)
+    }
+    fn description(&self) -> Option<&str> {
+        self.description
+    }
+    fn fields(&self) -> Option<Vec<(String, String, bool)>> {
+        Some(self.fields.clone())
+    }
+}
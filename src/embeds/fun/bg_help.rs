@@ -13,6 +13,7 @@ impl BGHelpEmbed {
             try to guess the **title** of the map's song.\n\
             Content in parentheses `(...)` or content after `ft.` or `feat.` \
             will be removed from the title you need to guess.\n\
+            The unicode title and aliases from `<bgalias` are accepted as well.\n\
             Use these subcommands to initiate with the game:";
         let fields = vec![
             (
@@ -31,6 +32,45 @@ impl BGHelpEmbed {
                     .to_owned(),
                 false,
             ),
+            (
+                "Rounds".to_owned(),
+                "Start the game with `--rounds` or `-r` followed by a number \
+                e.g. `<bg s --rounds 10` to play a fixed amount of rounds.\n\
+                The faster you guess, the more points you get, \
+                but each `hint` or `bigger` used in a round reduces them.\n\
+                After each round the scoreboard is shown and the game ends \
+                with the final standings."
+                    .to_owned(),
+                false,
+            ),
+            (
+                "Reveal modes".to_owned(),
+                "Start the game with `--reveal` followed by a mode \
+                e.g. `<bg s --reveal blur` to change how backgrounds are shown:\n\
+                `circle` (default), `pixel`, `blur`, `tiles`, `gray`, `invert`, \
+                `mirror`, or `random` for a different one each round.\n\
+                Every `bigger` reveals more of the background."
+                    .to_owned(),
+                false,
+            ),
+            (
+                "Difficulty".to_owned(),
+                "Start the game with `--difficulty` or `-d` followed by \
+                `easy`, `medium`, or `hard` e.g. `<bg s -d hard` to prefer \
+                backgrounds that are guessed quickly or rarely.\n\
+                Backgrounds that haven't been played often enough \
+                are judged by their `Easy` / `Hard` tags instead."
+                    .to_owned(),
+                false,
+            ),
+            (
+                "Tag voting".to_owned(),
+                "After a background is resolved, react with the tag emojis \
+                to vote on which tags the mapset should have.\n\
+                Once enough players agree, the tag is added to the mapset."
+                    .to_owned(),
+                false,
+            ),
             (
                 "hint / h / tip".to_owned(),
                 "Receive a hint (can be used multiple times)".to_owned(),
@@ -52,7 +92,29 @@ impl BGHelpEmbed {
                 "ranking / leaderboard / lb".to_owned(),
                 "Check out the leaderboard of this server.\n\
                 Add the argument `global` or just `g` (e.g. `<bg lb g`) \
-                to get the leaderboard across all servers"
+                to get the leaderboard across all servers.\n\
+                Add the argument `season` or just `s` (e.g. `<bg lb g s`) \
+                to only count this month's guesses"
+                    .to_owned(),
+                true,
+            ),
+            (
+                "seasons / winners".to_owned(),
+                "Check out the winners of past seasons on this server.\n\
+                Add the argument `global` or just `g` for the global winners"
+                    .to_owned(),
+                true,
+            ),
+            (
+                "sessions / history".to_owned(),
+                "Check out your results of recent games with rounds".to_owned(),
+                true,
+            ),
+            (
+                "config / settings".to_owned(),
+                "Store the mode, tags, reveal mode, hint cooldown, and auto-stop \
+                of this channel so `start` uses them without asking for tags.\n\
+                Requires authority status"
                     .to_owned(),
                 true,
             ),
@@ -60,7 +122,8 @@ impl BGHelpEmbed {
                 "stop / end".to_owned(),
                 "Resolve the last background and stop the game in this channel.\n\
                 Not required to use since the game will end automatically \
-                if no one guessed the background after __3 minutes__."
+                if no one guessed the background after __3 minutes__ \
+                or the channel's configured auto-stop."
                     .to_owned(),
                 true,
             ),
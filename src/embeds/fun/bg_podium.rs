@@ -0,0 +1,59 @@
+use super::bg_scoreboard::standings_table;
+use crate::{
+    commands::fun::SessionScore,
+    embeds::{EmbedData, Footer},
+};
+
+#[derive(Clone)]
+pub struct BGPodiumEmbed {
+    title: &'static str,
+    description: String,
+    fields: Vec<(String, String, bool)>,
+    footer: Footer,
+}
+
+impl BGPodiumEmbed {
+    pub fn new(rounds_played: usize, rounds: usize, standings: &[SessionScore]) -> Self {
+        let medals = ["🥇", "🥈", "🥉"];
+        let fields = standings
+            .iter()
+            .zip(medals.iter())
+            .map(|(score, medal)| {
+                let value = format!("{} points\n{} guessed", score.points, score.guesses);
+                (format!("{} {}", medal, score.name), value, true)
+            })
+            .collect();
+        let footer_text = if rounds_played < rounds {
+            format!(
+                "Game ended early after {}/{} rounds ~ Check `<bg sessions` for past games",
+                rounds_played, rounds
+            )
+        } else {
+            format!(
+                "{} rounds played ~ Check `<bg sessions` for past games",
+                rounds
+            )
+        };
+        Self {
+            title: "Final standings",
+            description: standings_table(standings),
+            fields,
+            footer: Footer::new(footer_text),
+        }
+    }
+}
+
+impl EmbedData for BGPodiumEmbed {
+    fn title(&self) -> Option<&str> {
+        Some(self.title)
+    }
+    fn description(&self) -> Option<&str> {
+        Some(&self.description)
+    }
+    fn fields(&self) -> Option<Vec<(String, String, bool)>> {
+        Some(self.fields.clone())
+    }
+    fn footer(&self) -> Option<&Footer> {
+        Some(&self.footer)
+    }
+}
@@ -1,10 +1,17 @@
 use crate::{
     embeds::{Author, EmbedData, Footer},
-    util::globals::SYMBOLS,
+    util::{datetime::season_name, globals::SYMBOLS},
 };
 
 use std::fmt::Write;
 
+/// Which scores a bg game leaderboard consists of
+#[derive(Clone, Copy)]
+pub struct BGRankingView {
+    pub global: bool,
+    pub season: Option<u32>,
+}
+
 #[derive(Clone)]
 pub struct BGRankingEmbed {
     author: Author,
@@ -16,7 +23,7 @@ impl BGRankingEmbed {
     pub fn new(
         author_idx: Option<usize>,
         list: Vec<(&String, u32)>,
-        global: bool,
+        view: BGRankingView,
         idx: usize,
         pages: (usize, usize),
     ) -> Self {
@@ -46,9 +53,14 @@ impl BGRankingEmbed {
         if let Some(author_idx) = author_idx {
             let _ = write!(footer_text, " ~ Your rank: {}", author_idx + 1);
         }
+        let period = match view.season {
+            Some(season) => format!("in {}", season_name(season)),
+            None => "of all time".to_owned(),
+        };
         let author_text = format!(
-            "{} leaderboard for correct guesses:",
-            if global { "Global" } else { "Server" }
+            "{} leaderboard for correct guesses {}:",
+            if view.global { "Global" } else { "Server" },
+            period
         );
         Self {
             author: Author::new(author_text),
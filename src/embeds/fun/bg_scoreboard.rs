@@ -0,0 +1,71 @@
+use crate::{
+    commands::fun::SessionScore,
+    embeds::{EmbedData, Footer},
+    util::globals::SYMBOLS,
+};
+
+use std::fmt::Write;
+
+#[derive(Clone)]
+pub struct BGScoreboardEmbed {
+    title: String,
+    description: String,
+    footer: Footer,
+}
+
+impl BGScoreboardEmbed {
+    pub fn new(
+        round: usize,
+        rounds: usize,
+        standings: &[SessionScore],
+        winner: Option<(&str, u32)>,
+    ) -> Self {
+        let mut description = match winner {
+            Some((name, points)) => format!("**{}** earned **{}** points\n", name, points),
+            None => "No one guessed this one\n".to_owned(),
+        };
+        description.push_str(&standings_table(standings));
+        Self {
+            title: format!("Scoreboard after round {}/{}", round, rounds),
+            description,
+            footer: Footer::new(format!("{} rounds left", rounds.saturating_sub(round))),
+        }
+    }
+}
+
+impl EmbedData for BGScoreboardEmbed {
+    fn title(&self) -> Option<&str> {
+        Some(&self.title)
+    }
+    fn description(&self) -> Option<&str> {
+        Some(&self.description)
+    }
+    fn footer(&self) -> Option<&Footer> {
+        Some(&self.footer)
+    }
+}
+
+pub(super) fn standings_table(standings: &[SessionScore]) -> String {
+    if standings.is_empty() {
+        return "No points scored yet".to_owned();
+    }
+    let len = standings
+        .iter()
+        .fold(0, |max, score| max.max(score.name.chars().count()));
+    let mut table = String::with_capacity(256);
+    table.push_str("```\n");
+    for (i, score) in standings.iter().enumerate() {
+        let _ = writeln!(
+            table,
+            "{:>2} {:1} # {:<len$} => {} ({} guessed)",
+            i + 1,
+            SYMBOLS.get(i).copied().unwrap_or(""),
+            score.name,
+            score.points,
+            score.guesses,
+            len = len
+        );
+    }
+    table.push_str("```");
+    table
+}
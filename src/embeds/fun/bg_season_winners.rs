@@ -0,0 +1,44 @@
+use crate::{
+    embeds::{Author, EmbedData},
+    util::datetime::season_name,
+};
+
+use std::fmt::Write;
+
+#[derive(Clone)]
+pub struct BGSeasonWinnersEmbed {
+    author: Author,
+    description: String,
+}
+
+impl BGSeasonWinnersEmbed {
+    pub fn new(winners: &[(u32, &str, u32)], global: bool) -> Self {
+        let mut description = String::with_capacity(256);
+        for (season, name, score) in winners {
+            let _ = writeln!(
+                description,
+                "**{}**: {} with **{}** correct guesses",
+                season_name(*season),
+                name,
+                score
+            );
+        }
+        let author_text = format!(
+            "{} winners of past backgroundgame seasons:",
+            if global { "Global" } else { "Server" }
+        );
+        Self {
+            author: Author::new(author_text),
+            description,
+        }
+    }
+}
+
+impl EmbedData for BGSeasonWinnersEmbed {
+    fn author(&self) -> Option<&Author> {
+        Some(&self.author)
+    }
+    fn description(&self) -> Option<&str> {
+        Some(&self.description)
+    }
+}
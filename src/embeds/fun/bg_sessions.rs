@@ -0,0 +1,45 @@
+use crate::{
+    database::BgSessionEntry,
+    embeds::{Author, EmbedData},
+    util::datetime::how_long_ago,
+};
+
+use std::fmt::Write;
+
+#[derive(Clone)]
+pub struct BGSessionsEmbed {
+    author: Author,
+    description: String,
+}
+
+impl BGSessionsEmbed {
+    pub fn new(name: &str, sessions: &[BgSessionEntry]) -> Self {
+        let mut description = String::with_capacity(512);
+        for session in sessions {
+            let _ = writeln!(
+                description,
+                "**#{placement}**/{players} ~ **{points}** points ~ \
+                {guesses}/{rounds} guessed ~ {ago}",
+                placement = session.placement,
+                players = session.players,
+                points = session.points,
+                guesses = session.guesses,
+                rounds = session.rounds,
+                ago = how_long_ago(&session.ended_at),
+            );
+        }
+        Self {
+            author: Author::new(format!("Recent bg game sessions of {}:", name)),
+            description,
+        }
+    }
+}
+
+impl EmbedData for BGSessionsEmbed {
+    fn author(&self) -> Option<&Author> {
+        Some(&self.author)
+    }
+    fn description(&self) -> Option<&str> {
+        Some(&self.description)
+    }
+}
@@ -0,0 +1,69 @@
+use crate::{
+    commands::fun::{HlEntry, HlVersion},
+    embeds::{Author, EmbedData, Footer},
+    util::globals::MAP_THUMB_URL,
+};
+
+#[derive(Clone)]
+pub struct HLRoundEmbed {
+    author: Author,
+    description: String,
+    thumbnail: String,
+    footer: Footer,
+}
+
+impl HLRoundEmbed {
+    pub fn new(
+        known: &HlEntry,
+        next: &HlEntry,
+        version: HlVersion,
+        streak: u32,
+        revealed: bool,
+    ) -> Self {
+        let next_value = if revealed {
+            format_value(next.value, version)
+        } else {
+            "???".to_owned()
+        };
+        let description = format!(
+            "**{}**\n{}\n\n**{}**\n{}",
+            known.name,
+            format_value(known.value, version),
+            next.name,
+            next_value
+        );
+        let author_text = if revealed {
+            format!("Higher or lower ({})", version)
+        } else {
+            format!("Higher or lower ({}): Is the second one ⬆️ or ⬇️?", version)
+        };
+        Self {
+            author: Author::new(author_text),
+            description,
+            thumbnail: format!("{}{}l.jpg", MAP_THUMB_URL, next.mapset_id),
+            footer: Footer::new(format!("Streak: {}", streak)),
+        }
+    }
+}
+
+fn format_value(value: f32, version: HlVersion) -> String {
+    match version {
+        HlVersion::Pp => format!("{:.2}pp", value),
+        HlVersion::Stars => format!("{:.2}★", value),
+    }
+}
+
+impl EmbedData for HLRoundEmbed {
+    fn author(&self) -> Option<&Author> {
+        Some(&self.author)
+    }
+    fn description(&self) -> Option<&str> {
+        Some(&self.description)
+    }
+    fn thumbnail(&self) -> Option<&str> {
+        Some(&self.thumbnail)
+    }
+    fn footer(&self) -> Option<&Footer> {
+        Some(&self.footer)
+    }
+}
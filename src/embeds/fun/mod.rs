@@ -1,9 +1,25 @@
+mod bg_config;
 mod bg_help;
+mod bg_podium;
 mod bg_ranking;
+mod bg_scoreboard;
+mod bg_season_winners;
+mod bg_sessions;
 mod bg_start;
 mod bg_tags;
+mod hl_ranking;
+mod hl_round;
+mod song_game_help;
 
+pub use bg_config::BGConfigEmbed;
 pub use bg_help::BGHelpEmbed;
-pub use bg_ranking::BGRankingEmbed;
+pub use bg_podium::BGPodiumEmbed;
+pub use bg_ranking::{BGRankingEmbed, BGRankingView};
+pub use bg_scoreboard::BGScoreboardEmbed;
+pub use bg_season_winners::BGSeasonWinnersEmbed;
+pub use bg_sessions::BGSessionsEmbed;
 pub use bg_start::BGStartEmbed;
 pub use bg_tags::BGTagsEmbed;
+pub use hl_ranking::HLRankingEmbed;
+pub use hl_round::HLRoundEmbed;
+pub use song_game_help::SongGameHelpEmbed;
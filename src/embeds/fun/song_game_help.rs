@@ -0,0 +1,61 @@
+use crate::embeds::EmbedData;
+
+#[derive(Clone)]
+pub struct SongGameHelpEmbed {
+    title: &'static str,
+    description: &'static str,
+    fields: Vec<(String, String, bool)>,
+}
+
+impl SongGameHelpEmbed {
+    pub fn new() -> Self {
+        let description = "Given a short clip of a map's song, \
+            try to guess the **title** of the song.\n\
+            Content in parentheses `(...)` or content after `ft.` or `feat.` \
+            will be removed from the title you need to guess.\n\
+            Correct guesses count towards the background game leaderboards.\n\
+            Use these subcommands to initiate with the game:";
+        let fields = vec![
+            (
+                "start / s / skip / resolve / r".to_owned(),
+                "Start the game in the current channel.\n\
+                If a game was already running, it will resolve the current \
+                song and give a new one."
+                    .to_owned(),
+                false,
+            ),
+            (
+                "hint / h / tip / longer".to_owned(),
+                "Receive a longer clip of the song, \
+                the last one being the whole preview"
+                    .to_owned(),
+                true,
+            ),
+            (
+                "stop / end".to_owned(),
+                "Resolve the last song and stop the game in this channel.\n\
+                Not required to use since the game will end automatically \
+                if no one guessed the song after __3 minutes__."
+                    .to_owned(),
+                true,
+            ),
+        ];
+        Self {
+            fields,
+            description,
+            title: "Song guessing game",
+        }
+    }
+}
+
+impl EmbedData for SongGameHelpEmbed {
+    fn title(&self) -> Option<&str> {
+        Some(self.title)
+    }
+    fn description(&self) -> Option<&str> {
+        Some(self.description)
+    }
+    fn fields(&self) -> Option<Vec<(String, String, bool)>> {
+        Some(self.fields.clone())
+    }
+}
@@ -1,6 +1,7 @@
 use crate::{
     commands::osu::ProfileResult,
     embeds::{osu, Author, EmbedData, Footer},
+    osu_v2::UserExtended,
     util::{
         datetime::{date_to_string, how_long_ago, sec_to_minsec},
         globals::AVATAR_URL,
@@ -28,6 +29,7 @@ impl ProfileEmbed {
         user: User,
         profile_result: Option<ProfileResult>,
         globals_count: BTreeMap<usize, String>,
+        extended: Option<UserExtended>,
         cache: &Cache,
     ) -> Self {
         let footer_text = format!(
@@ -72,6 +74,24 @@ impl ProfileEmbed {
                 true,
             ),
         ];
+        if let Some(extended) = extended {
+            let stats = extended.statistics;
+            fields.push((
+                "Max combo:".to_owned(),
+                with_comma_u64(stats.maximum_combo as u64),
+                true,
+            ));
+            fields.push((
+                "Replays watched:".to_owned(),
+                with_comma_u64(stats.replays_watched_by_others as u64),
+                true,
+            ));
+            fields.push((
+                "Followers:".to_owned(),
+                with_comma_u64(extended.follower_count as u64),
+                true,
+            ));
+        }
         let description = if let Some(values) = profile_result {
             let mut combo = String::from(&values.avg_combo.to_string());
             match values.mode {
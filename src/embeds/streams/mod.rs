@@ -1,5 +1,9 @@
 mod allstreams;
-mod twitch_notif;
+mod osu_streams;
+mod stream_end;
+mod stream_notif;
 
 pub use allstreams::AllStreamsEmbed;
-pub use twitch_notif::TwitchNotifEmbed;
+pub use osu_streams::OsuStreamsEmbed;
+pub use stream_end::StreamEndEmbed;
+pub use stream_notif::StreamNotifEmbed;
@@ -0,0 +1,61 @@
+use crate::{
+    embeds::{Author, EmbedData, Footer},
+    streams::Stream,
+    util::{datetime::sec_to_duration, numbers::with_comma_u64},
+};
+
+use chrono::Utc;
+use std::fmt::Write;
+
+#[derive(Clone)]
+pub struct OsuStreamsEmbed {
+    author: Author,
+    description: String,
+    footer: Footer,
+}
+
+impl OsuStreamsEmbed {
+    pub fn new(streams: &[Stream], idx: usize, pages: (usize, usize), total: usize) -> Self {
+        let now = Utc::now();
+        let mut description = String::with_capacity(1024);
+        for (i, stream) in streams.iter().enumerate() {
+            let _ = write!(
+                description,
+                "**{}.** [{}]({}) • {} viewers",
+                idx + i,
+                stream.username,
+                stream.url,
+                with_comma_u64(stream.viewer_count as u64),
+            );
+            if let Some(started_at) = stream.started_at {
+                let _ = write!(
+                    description,
+                    " • {}",
+                    sec_to_duration((now - started_at).num_seconds())
+                );
+            }
+            if let Some(ref language) = stream.language {
+                let _ = write!(description, " • `{}`", language);
+            }
+            let title: String = stream.title.chars().take(80).collect();
+            let _ = writeln!(description, "\n{}", title.trim());
+        }
+        Self {
+            author: Author::new(format!("{} live osu! streams:", total)),
+            description,
+            footer: Footer::new(format!("Page {}/{}", pages.0, pages.1)),
+        }
+    }
+}
+
+impl EmbedData for OsuStreamsEmbed {
+    fn author(&self) -> Option<&Author> {
+        Some(&self.author)
+    }
+    fn description(&self) -> Option<&str> {
+        Some(&self.description)
+    }
+    fn footer(&self) -> Option<&Footer> {
+        Some(&self.footer)
+    }
+}
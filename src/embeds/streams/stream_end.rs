@@ -0,0 +1,58 @@
+use crate::{
+    embeds::{Author, EmbedData},
+    streams::LiveStream,
+    util::{datetime::sec_to_duration, numbers::with_comma_u64},
+};
+
+use chrono::Utc;
+
+#[derive(Clone)]
+pub struct StreamEndEmbed {
+    description: String,
+    thumbnail: String,
+    title: String,
+    url: String,
+    author: Author,
+    fields: Vec<(String, String, bool)>,
+}
+
+impl StreamEndEmbed {
+    pub fn new(live: &LiveStream) -> Self {
+        let stream = &live.stream;
+        let duration = sec_to_duration((Utc::now() - live.started_at).num_seconds());
+        let peak = with_comma_u64(live.peak_viewers as u64);
+        let fields = vec![
+            ("Duration".to_owned(), duration, true),
+            ("Peak viewers".to_owned(), peak, true),
+        ];
+        Self {
+            title: stream.username.clone(),
+            description: stream.title.clone(),
+            thumbnail: live.image_url.clone(),
+            url: stream.url.clone(),
+            author: Author::new(format!("Stream ended on {}:", stream.platform)),
+            fields,
+        }
+    }
+}
+
+impl EmbedData for StreamEndEmbed {
+    fn description(&self) -> Option<&str> {
+        Some(&self.description)
+    }
+    fn author(&self) -> Option<&Author> {
+        Some(&self.author)
+    }
+    fn thumbnail(&self) -> Option<&str> {
+        Some(self.thumbnail.as_str()).filter(|thumbnail| !thumbnail.is_empty())
+    }
+    fn title(&self) -> Option<&str> {
+        Some(&self.title)
+    }
+    fn url(&self) -> Option<&str> {
+        Some(&self.url)
+    }
+    fn fields(&self) -> Option<Vec<(String, String, bool)>> {
+        Some(self.fields.clone())
+    }
+}
@@ -0,0 +1,69 @@
+use crate::{
+    embeds::{Author, EmbedData, Footer},
+    streams::LiveStream,
+    util::{datetime::sec_to_duration, numbers::with_comma_u64},
+};
+
+use chrono::Utc;
+
+#[derive(Clone)]
+pub struct StreamNotifEmbed {
+    description: String,
+    thumbnail: String,
+    image: String,
+    title: String,
+    url: String,
+    author: Author,
+    footer: Footer,
+    fields: Vec<(String, String, bool)>,
+}
+
+impl StreamNotifEmbed {
+    pub fn new(live: &LiveStream) -> Self {
+        let stream = &live.stream;
+        let mut fields = Vec::with_capacity(2);
+        if let Some(ref game) = stream.game {
+            fields.push(("Game".to_owned(), game.clone(), true));
+        }
+        let viewers = with_comma_u64(stream.viewer_count as u64);
+        fields.push(("Viewers".to_owned(), viewers, true));
+        let uptime = sec_to_duration((Utc::now() - live.started_at).num_seconds());
+        Self {
+            title: stream.username.clone(),
+            description: stream.title.clone(),
+            thumbnail: live.image_url.clone(),
+            image: stream.thumbnail_url.clone(),
+            url: stream.url.clone(),
+            author: Author::new(format!("Now live on {}:", stream.platform)),
+            footer: Footer::new(format!("Live for {}", uptime)),
+            fields,
+        }
+    }
+}
+
+impl EmbedData for StreamNotifEmbed {
+    fn description(&self) -> Option<&str> {
+        Some(&self.description)
+    }
+    fn author(&self) -> Option<&Author> {
+        Some(&self.author)
+    }
+    fn thumbnail(&self) -> Option<&str> {
+        Some(self.thumbnail.as_str()).filter(|thumbnail| !thumbnail.is_empty())
+    }
+    fn image(&self) -> Option<&str> {
+        Some(self.image.as_str()).filter(|image| !image.is_empty())
+    }
+    fn title(&self) -> Option<&str> {
+        Some(&self.title)
+    }
+    fn url(&self) -> Option<&str> {
+        Some(&self.url)
+    }
+    fn footer(&self) -> Option<&Footer> {
+        Some(&self.footer)
+    }
+    fn fields(&self) -> Option<Vec<(String, String, bool)>> {
+        Some(self.fields.clone())
+    }
+}
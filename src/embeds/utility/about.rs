@@ -4,11 +4,12 @@ use crate::{
         datetime::how_long_ago,
         numbers::{round, with_comma_u64},
     },
-    BootTime,
+    BootTime, OsuCache,
 };
 
 use failure::Error;
 use serenity::client::Context;
+use std::fmt::Write;
 use sysinfo::{get_current_pid, ProcessExt, ProcessorExt, System, SystemExt};
 
 #[derive(Clone)]
@@ -52,6 +53,16 @@ impl AboutEmbed {
 
         let data = ctx.data.read().await;
         let boot_time = *data.get::<BootTime>().unwrap();
+        let mut cache_stats = String::with_capacity(128);
+        for (kind, stats) in data.get::<OsuCache>().unwrap().stats().iter() {
+            let _ = writeln!(
+                cache_stats,
+                "{}: {} entries, {}% hits",
+                kind,
+                stats.len,
+                round(stats.hit_rate())
+            );
+        }
 
         let thumbnail = cache
             .current_user_field(|user| user.avatar_url())
@@ -77,6 +88,7 @@ impl AboutEmbed {
                 format!("{}/{} MB", used_ram, total_ram),
                 true,
             ),
+            ("osu!api cache".to_owned(), cache_stats, false),
             (
                 "Github".to_owned(),
                 "https://github.com/MaxOhn/Bathbot".to_string(),
@@ -1,9 +1,15 @@
 mod about;
 mod avatar;
 mod command_counter;
+mod mod_log;
+mod mod_log_history;
 mod role_assign;
+mod role_menu;
 
 pub use about::AboutEmbed;
 pub use avatar::AvatarEmbed;
 pub use command_counter::CommandCounterEmbed;
+pub use mod_log::ModLogEmbed;
+pub use mod_log_history::ModLogHistoryEmbed;
 pub use role_assign::RoleAssignEmbed;
+pub use role_menu::RoleMenuEmbed;
@@ -0,0 +1,41 @@
+use crate::{database::ModLogEntry, embeds::EmbedData};
+
+use chrono::{DateTime, Utc};
+
+#[derive(Clone)]
+pub struct ModLogEmbed {
+    title: String,
+    description: String,
+    fields: Vec<(String, String, bool)>,
+    timestamp: DateTime<Utc>,
+}
+
+impl ModLogEmbed {
+    pub fn new(entry: &ModLogEntry) -> Self {
+        let mut fields = vec![("By".to_owned(), format!("<@{}>", entry.user_id), true)];
+        if let Some(target) = entry.target_id {
+            fields.push(("User".to_owned(), format!("<@{}>", target), true));
+        }
+        Self {
+            title: entry.action.to_string(),
+            description: entry.details.clone(),
+            fields,
+            timestamp: entry.timestamp,
+        }
+    }
+}
+
+impl EmbedData for ModLogEmbed {
+    fn title(&self) -> Option<&str> {
+        Some(&self.title)
+    }
+    fn description(&self) -> Option<&str> {
+        Some(&self.description)
+    }
+    fn fields(&self) -> Option<Vec<(String, String, bool)>> {
+        Some(self.fields.clone())
+    }
+    fn timestamp(&self) -> Option<&DateTime<Utc>> {
+        Some(&self.timestamp)
+    }
+}
@@ -0,0 +1,60 @@
+use crate::{
+    database::ModLogEntry,
+    embeds::{Author, EmbedData, Footer},
+    util::datetime::how_long_ago,
+};
+
+use std::fmt::Write;
+
+#[derive(Clone)]
+pub struct ModLogHistoryEmbed {
+    author: Author,
+    description: String,
+    footer: Footer,
+}
+
+impl ModLogHistoryEmbed {
+    pub fn new(name: &str, entries: &[ModLogEntry], idx: usize, pages: (usize, usize)) -> Self {
+        let mut description = String::with_capacity(1024);
+        for (i, entry) in entries.iter().enumerate() {
+            let _ = write!(
+                description,
+                "**{}.** {} by <@{}>",
+                idx + i,
+                entry.action,
+                entry.user_id
+            );
+            if let Some(target) = entry.target_id.filter(|&target| target != entry.user_id) {
+                let _ = write!(description, " for <@{}>", target);
+            }
+            let _ = writeln!(description, " ~ {}", how_long_ago(&entry.timestamp));
+            // Only the first line of the details to keep pages short
+            let details: String = entry
+                .details
+                .lines()
+                .next()
+                .unwrap_or_default()
+                .chars()
+                .take(100)
+                .collect();
+            let _ = writeln!(description, "{}", details);
+        }
+        Self {
+            author: Author::new(format!("Mod log of {}:", name)),
+            description,
+            footer: Footer::new(format!("Page {}/{}", pages.0, pages.1)),
+        }
+    }
+}
+
+impl EmbedData for ModLogHistoryEmbed {
+    fn author(&self) -> Option<&Author> {
+        Some(&self.author)
+    }
+    fn description(&self) -> Option<&str> {
+        Some(&self.description)
+    }
+    fn footer(&self) -> Option<&Footer> {
+        Some(&self.footer)
+    }
+}
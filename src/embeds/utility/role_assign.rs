@@ -16,12 +16,23 @@ pub struct RoleAssignEmbed {
 }
 
 impl RoleAssignEmbed {
-    pub async fn new(msg: Message, guild: GuildId, role: RoleId, cache: &Cache) -> Self {
+    pub async fn new(
+        msg: Message,
+        guild: GuildId,
+        role: RoleId,
+        emoji: Option<&str>,
+        cache: &Cache,
+    ) -> Self {
+        let reaction = match emoji {
+            Some(emoji) => format!("reacts with {} to", emoji),
+            None => "reacts to".to_owned(),
+        };
         let description = format!(
-            "Whoever reacts to {author}'s [message]\
+            "Whoever {reaction} {author}'s [message]\
             (https://discordapp.com/channels/{guild}/{channel}/{msg})\n\
             ```\n{content}\n```\n\
             in {channel_mention} will be assigned the {role_mention} role!",
+            reaction = reaction,
             author = msg.author.mention(),
             guild = guild,
             channel = msg.channel_id,
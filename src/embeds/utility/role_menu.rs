@@ -0,0 +1,46 @@
+use crate::embeds::EmbedData;
+
+use serenity::model::{id::RoleId, misc::Mentionable};
+use std::fmt::Write;
+
+#[derive(Clone)]
+pub struct RoleMenuEmbed {
+    description: String,
+    title: String,
+}
+
+impl RoleMenuEmbed {
+    pub fn new(
+        title: String,
+        roles: &[(String, RoleId)],
+        required_role: Option<RoleId>,
+        max_roles: Option<u8>,
+        exclusive: bool,
+    ) -> Self {
+        let mut description = String::with_capacity(256);
+        for (emoji, role) in roles {
+            let _ = writeln!(description, "{} {}", emoji, role.mention());
+        }
+        if exclusive || max_roles.is_some() || required_role.is_some() {
+            description.push('\n');
+        }
+        if exclusive {
+            description.push_str("Only one of these roles can be picked\n");
+        } else if let Some(max) = max_roles {
+            let _ = writeln!(description, "At most {} of these roles can be picked", max);
+        }
+        if let Some(role) = required_role {
+            let _ = writeln!(description, "Requires the {} role", role.mention());
+        }
+        Self { description, title }
+    }
+}
+
+impl EmbedData for RoleMenuEmbed {
+    fn description(&self) -> Option<&str> {
+        Some(&self.description)
+    }
+    fn title(&self) -> Option<&str> {
+        Some(&self.title)
+    }
+}
@@ -1,13 +1,18 @@
 use crate::{
-    database::MySQL,
-    embeds::{EmbedData, TwitchNotifEmbed},
-    streams::{Twitch, TwitchStream},
-    structs::{OnlineTwitch, ReactionTracker, StreamTracks},
-    util::discord::{_add_guild, get_member},
+    database::{MenuDenial, ModAction, ModLogEntry, MySQL, RoleMenu, RoleMenuEntry, StreamTrack},
+    embeds::{EmbedData, StreamEndEmbed, StreamNotifEmbed},
+    streams::{LiveStream, Platform, Stream, StreamEvent, StreamPlatform},
+    structs::{OnlineStreams, Osu, ReactionTracker, StreamEvents, StreamPlatforms, StreamTracks},
+    util::{
+        datetime,
+        discord::{_add_guild, get_member, mod_log},
+        rank_roles,
+    },
     WITH_STREAM_TRACK,
 };
 
-use rayon::prelude::*;
+use chrono::Utc;
+use rosu::backend::requests::BeatmapRequest;
 use serenity::{
     async_trait,
     http::Http,
@@ -16,18 +21,20 @@ use serenity::{
         event::ResumedEvent,
         gateway::{Activity, Ready},
         guild::Guild,
-        id::{ChannelId, MessageId, RoleId},
+        id::{ChannelId, GuildId, MessageId, RoleId},
     },
     prelude::*,
 };
 use std::{
     collections::{HashMap, HashSet},
+    fmt::Write,
     sync::{Arc, Once},
 };
-use strfmt::strfmt;
 use tokio::time;
 
 static START: Once = Once::new();
+/// Seconds to wait before each request for a stream that just went online
+const STREAM_EVENT_DELAYS: [u64; 5] = [2, 4, 8, 16, 30];
 
 pub struct Handler;
 
@@ -40,18 +47,63 @@ impl EventHandler for Handler {
                 let http = Arc::clone(&ctx.http);
                 let data = Arc::clone(&ctx.data);
                 let _ = tokio::spawn(async move {
+                    let mut events = {
+                        let mut data = data.write().await;
+                        data.get_mut::<StreamEvents>().and_then(Option::take)
+                    };
+                    if events.is_some() {
+                        _subscribe_streams(&data).await;
+                    }
                     let track_delay = 10;
                     let mut interval = time::interval(time::Duration::from_secs(track_delay * 60));
-                    interval.tick().await;
                     loop {
-                        _check_streams(&http, &data).await;
-                        interval.tick().await;
+                        match events {
+                            Some(ref mut events) => tokio::select! {
+                                _ = interval.tick() => _check_streams(&http, &data).await,
+                                Some(event) = events.recv() => {
+                                    debug!("Received stream event {:?}", event);
+                                    _handle_stream_event(&http, &data, event).await;
+                                }
+                            },
+                            None => {
+                                interval.tick().await;
+                                _check_streams(&http, &data).await;
+                            }
+                        }
                     }
                 });
                 info!("Stream tracking started");
             } else {
                 info!("Stream tracking skipped");
             }
+
+            // Refreshing outdated maps of the DB
+            let data = Arc::clone(&ctx.data);
+            let _ = tokio::spawn(async move {
+                _refresh_maps(&data).await;
+            });
+
+            // Archiving the winners of finished bg game seasons
+            let data = Arc::clone(&ctx.data);
+            let _ = tokio::spawn(async move {
+                let mut interval = time::interval(time::Duration::from_secs(3600));
+                loop {
+                    interval.tick().await;
+                    _archive_bg_season(&data).await;
+                }
+            });
+
+            // Updating the rank roles of linked members
+            let ctx = ctx.clone();
+            let _ = tokio::spawn(async move {
+                // Let the cache fill up with guild members first
+                time::delay_for(time::Duration::from_secs(600)).await;
+                let mut interval = time::interval(time::Duration::from_secs(24 * 3600));
+                loop {
+                    interval.tick().await;
+                    _update_rank_roles(&ctx).await;
+                }
+            });
         });
 
         // Tracking reactions
@@ -60,11 +112,11 @@ impl EventHandler for Handler {
             match data.get::<MySQL>() {
                 Some(mysql) => {
                     let reaction_tracker: HashMap<_, _> = mysql
-                        .get_role_assigns()
+                        .get_role_menus()
                         .await
-                        .expect("Could not get role assigns")
+                        .expect("Could not get role menus")
                         .into_iter()
-                        .map(|((c, m), r)| ((ChannelId(c), MessageId(m)), RoleId(r)))
+                        .map(|((c, m), menu)| ((ChannelId(c), MessageId(m)), menu))
                         .collect();
                     {
                         data.insert::<ReactionTracker>(reaction_tracker);
@@ -99,154 +151,473 @@ impl EventHandler for Handler {
 
     async fn reaction_add(&self, ctx: Context, reaction: Reaction) {
         // Check if the reacting user now gets a role
-        let key = (reaction.channel_id, reaction.message_id);
-        let role: Option<RoleId> = match ctx.data.read().await.get::<ReactionTracker>() {
-            Some(tracker) => {
-                if tracker.contains_key(&key) {
-                    Some(*tracker.get(&key).unwrap())
-                } else {
-                    None
+        let menu = match role_menu(&ctx, &reaction).await {
+            Some(menu) => menu,
+            None => return,
+        };
+        let role = match menu.entry(&reaction.emoji) {
+            Some(entry) => entry.role,
+            None => return,
+        };
+        let mut member = match get_member(&ctx, reaction.channel_id, reaction.user_id).await {
+            Some(member) => member,
+            None => return,
+        };
+        let member_roles: Vec<_> = member.roles.iter().map(|role| role.0).collect();
+        let replaced = match menu.roles_to_replace(role, &member_roles) {
+            Ok(replaced) => replaced,
+            Err(denial) => {
+                let content = match denial {
+                    MenuDenial::MissingRole(required) => format!(
+                        "You need the `{}` role before you can get `{}`",
+                        role_name(&ctx, required).await,
+                        role_name(&ctx, role).await
+                    ),
+                    MenuDenial::TooManyRoles(max) => format!(
+                        "You can only have {} role{} of that message, \
+                        remove a reaction before picking another one",
+                        max,
+                        if max == 1 { "" } else { "s" }
+                    ),
+                };
+                if let Err(why) = reaction.delete(&ctx).await {
+                    warn!("Could not delete denied role reaction: {}", why);
+                }
+                if let Ok(channel) = reaction.user_id.create_dm_channel(&ctx).await {
+                    let _ = channel.say(&ctx, content).await;
                 }
-            }
-            None => {
-                error!("Could not get ReactionTracker");
                 return;
             }
         };
-        if let Some(role) = role {
-            if let Some(mut member) = get_member(&ctx, reaction.channel_id, reaction.user_id).await
-            {
-                let role_name = role
-                    .to_role_cached(&ctx.cache)
-                    .await
-                    .expect("Role not found in cache")
-                    .name;
-                if let Err(why) = member.add_role(&ctx.http, role).await {
-                    error!("Could not add role to member for reaction: {}", why);
-                } else {
-                    info!(
-                        "Assigned role '{}' to member {}",
-                        role_name, member.user.name
-                    );
-                }
+        // Roles of the same exclusive group are swapped out
+        for old_role in replaced {
+            if let Err(why) = member.remove_role(&ctx.http, RoleId(old_role)).await {
+                error!("Could not remove exclusive role from member: {}", why);
+                continue;
+            }
+            let action = ModAction::RoleRemoved;
+            log_role_change(&ctx, &reaction, member.guild_id, action, old_role).await;
+            let old_emoji = menu
+                .entries
+                .iter()
+                .find(|entry| entry.role == old_role)
+                .and_then(RoleMenuEntry::reaction);
+            if let Some(emoji) = old_emoji {
+                let _ = ctx
+                    .http
+                    .delete_reaction(
+                        reaction.channel_id.0,
+                        reaction.message_id.0,
+                        Some(reaction.user_id.0),
+                        &emoji,
+                    )
+                    .await;
             }
         }
+        if let Err(why) = member.add_role(&ctx.http, RoleId(role)).await {
+            error!("Could not add role to member for reaction: {}", why);
+        } else {
+            info!(
+                "Assigned role '{}' to member {}",
+                role_name(&ctx, role).await,
+                member.user.name
+            );
+            log_role_change(&ctx, &reaction, member.guild_id, ModAction::RoleAdded, role).await;
+        }
     }
 
     async fn reaction_remove(&self, ctx: Context, reaction: Reaction) {
         // Check if the reacting user now loses a role
-        let key = (reaction.channel_id, reaction.message_id);
-        let role = match ctx.data.read().await.get::<ReactionTracker>() {
-            Some(tracker) => {
-                if tracker.contains_key(&key) {
-                    Some(*tracker.get(&key).unwrap())
-                } else {
-                    None
+        let menu = match role_menu(&ctx, &reaction).await {
+            Some(menu) => menu,
+            None => return,
+        };
+        let role = match menu.entry(&reaction.emoji) {
+            Some(entry) => RoleId(entry.role),
+            None => return,
+        };
+        if let Some(mut member) = get_member(&ctx, reaction.channel_id, reaction.user_id).await {
+            // The role might have been swapped out already
+            if !member.roles.contains(&role) {
+                return;
+            }
+            if let Err(why) = member.remove_role(&ctx.http, role).await {
+                error!("Could not remove role from member for reaction: {}", why);
+            } else {
+                info!(
+                    "Removed role '{}' from member {}",
+                    role_name(&ctx, role.0).await,
+                    member.user.name
+                );
+                let action = ModAction::RoleRemoved;
+                log_role_change(&ctx, &reaction, member.guild_id, action, role.0).await;
+            }
+        }
+    }
+}
+
+async fn role_menu(ctx: &Context, reaction: &Reaction) -> Option<RoleMenu> {
+    let key = (reaction.channel_id, reaction.message_id);
+    match ctx.data.read().await.get::<ReactionTracker>() {
+        Some(tracker) => tracker.get(&key).cloned(),
+        None => {
+            error!("Could not get ReactionTracker");
+            None
+        }
+    }
+}
+
+async fn log_role_change(
+    ctx: &Context,
+    reaction: &Reaction,
+    guild_id: GuildId,
+    action: ModAction,
+    role: u64,
+) {
+    let details = format!(
+        "<@&{}> through a reaction on [this message]\
+        (https://discordapp.com/channels/{}/{}/{})",
+        role, guild_id, reaction.channel_id, reaction.message_id
+    );
+    let user = reaction.user_id.0;
+    let entry = ModLogEntry::new(guild_id.0, action, user, details).target(user);
+    mod_log(ctx, entry).await;
+}
+
+async fn role_name(ctx: &Context, role: u64) -> String {
+    match RoleId(role).to_role_cached(&ctx.cache).await {
+        Some(role) => role.name,
+        None => role.to_string(),
+    }
+}
+
+async fn _refresh_maps(data: &RwLock<TypeMap>) {
+    let receiver = {
+        let data = data.read().await;
+        data.get::<MySQL>().and_then(MySQL::take_refresh_receiver)
+    };
+    let mut receiver = match receiver {
+        Some(receiver) => receiver,
+        None => {
+            warn!("Could not start refreshing maps");
+            return;
+        }
+    };
+    while let Some(map_id) = receiver.recv().await {
+        let data = data.read().await;
+        let osu = data.get::<Osu>().unwrap();
+        let mysql = data.get::<MySQL>().unwrap();
+        let map_req = BeatmapRequest::new().map_id(map_id);
+        match map_req.queue_single(osu).await {
+            Ok(Some(map)) => {
+                if let Err(why) = mysql.insert_beatmap(&map).await {
+                    warn!("Could not update refreshed map {} in DB: {}", map_id, why);
                 }
             }
-            None => {
-                error!("Could not get ReactionTracker");
+            Ok(None) => warn!(
+                "Map {} could not be refreshed, API returned nothing",
+                map_id
+            ),
+            Err(why) => warn!("Error while refreshing map {}: {}", map_id, why),
+        }
+        mysql.refresh_done(map_id);
+    }
+}
+
+async fn _archive_bg_season(data: &RwLock<TypeMap>) {
+    let season = datetime::previous_season(datetime::season(&Utc::now()));
+    let data = data.read().await;
+    let mysql = data.get::<MySQL>().unwrap();
+    if let Err(why) = mysql.archive_bg_season(season).await {
+        warn!("Error while archiving bg game season {}: {}", season, why);
+    }
+}
+
+async fn _update_rank_roles(ctx: &Context) {
+    let rank_roles = {
+        let data = ctx.data.read().await;
+        match data.get::<MySQL>().unwrap().get_rank_roles().await {
+            Ok(rank_roles) => rank_roles,
+            Err(why) => {
+                warn!("Error while getting rank roles from DB: {}", why);
                 return;
             }
-        };
-        if let Some(role) = role {
-            if let Some(mut member) = get_member(&ctx, reaction.channel_id, reaction.user_id).await
-            {
-                let role_name = role
-                    .to_role_cached(&ctx.cache)
-                    .await
-                    .expect("Role not found in cache")
-                    .name;
-                if let Err(why) = member.remove_role(&ctx.http, role).await {
-                    error!("Could not remove role from member for reaction: {}", why);
-                } else {
-                    info!(
-                        "Removed role '{}' from member {}",
-                        role_name, member.user.name
-                    );
-                }
+        }
+    };
+    for (guild_id, rules) in rank_roles {
+        let guild_id = GuildId(guild_id);
+        let changes = rank_roles::guild_changes(&ctx.cache, &ctx.data, guild_id, &rules).await;
+        let failed = rank_roles::apply_changes(&ctx.http, guild_id, &changes).await;
+        debug!(
+            "Updated rank roles of {} members in guild {} ({} failed)",
+            changes.len(),
+            guild_id,
+            failed
+        );
+        if changes.is_empty() {
+            continue;
+        }
+        let mut details = format!(
+            "Daily update of the rank roles of {} members",
+            changes.len()
+        );
+        if failed > 0 {
+            let _ = write!(details, ", {} roles could not be updated", failed);
+        }
+        let user_id = ctx.cache.current_user_id().await;
+        let entry = ModLogEntry::new(guild_id.0, ModAction::RankRoles, user_id.0, details);
+        mod_log(ctx, entry).await;
+    }
+}
+
+// Register all tracked streams for platforms that support events
+async fn _subscribe_streams(data: &RwLock<TypeMap>) {
+    let reading = data.read().await;
+    let platforms = reading.get::<StreamPlatforms>().unwrap();
+    let tracked: HashSet<_> = reading
+        .get::<StreamTracks>()
+        .unwrap()
+        .iter()
+        .map(|track| (track.platform, track.user_id.as_str()))
+        .collect();
+    for (platform, user_id) in tracked {
+        if let Some(client) = platforms.get(&platform) {
+            if let Err(why) = client.subscribe(user_id).await {
+                warn!(
+                    "Error while subscribing to {} stream {}: {}",
+                    platform, user_id, why
+                );
             }
         }
     }
 }
 
+/// Check the streams of all platforms, updating the notifications of live ones
 async fn _check_streams(http: &Http, data: &RwLock<TypeMap>) {
-    let now_online = {
-        let reading = data.read().await;
+    let reading = data.read().await;
 
-        // Get data about what needs to be tracked for which channel
-        let stream_tracks = reading.get::<StreamTracks>().unwrap();
-        let user_ids: Vec<_> = stream_tracks.iter().map(|track| track.user_id).collect();
-        // Twitch provides up to 100 streams per request, otherwise its trimmed
-        if user_ids.len() > 100 {
-            warn!("Reached 100 twitch trackings, improve handling!");
+    // Get data about what needs to be tracked for which channel
+    let stream_tracks = reading.get::<StreamTracks>().unwrap();
+    let platforms = reading.get::<StreamPlatforms>().unwrap();
+    let mysql = reading.get::<MySQL>().unwrap();
+    let mut online_streams = reading.get::<OnlineStreams>().unwrap().clone();
+    for (&platform, client) in platforms.iter() {
+        // Streams that are still marked as live need to be checked even if untracked by now
+        let mut user_ids: Vec<_> = stream_tracks
+            .iter()
+            .filter(|track| track.platform == platform)
+            .map(|track| track.user_id.clone())
+            .chain(
+                online_streams
+                    .keys()
+                    .filter(|(online_platform, _)| *online_platform == platform)
+                    .map(|(_, user_id)| user_id.clone()),
+            )
+            .collect();
+        if user_ids.is_empty() {
+            continue;
         }
+        user_ids.sort_unstable();
+        user_ids.dedup();
 
-        // Get stream data about all streams that need to be tracked
-        let twitch = reading.get::<Twitch>().unwrap();
-        let mut streams = match twitch.get_streams(&user_ids).await {
+        // Get stream data about all streams of the platform that are live
+        let streams = match client.get_live_streams(&user_ids).await {
             Ok(streams) => streams,
             Err(why) => {
-                warn!("Error while retrieving streams: {}", why);
-                return;
+                warn!("Error while retrieving {} streams: {}", platform, why);
+                continue;
             }
         };
 
-        // Filter streams whether they're live
-        streams.retain(TwitchStream::is_live);
-        let online_streams = reading.get::<OnlineTwitch>().unwrap();
-        let now_online: HashSet<_> = streams.iter().map(|stream| stream.user_id).collect();
+        // Update the notifications of streams that are known to be live
+        let mut live_ids = HashSet::with_capacity(streams.len());
+        let mut new_streams = Vec::new();
+        for stream in streams {
+            live_ids.insert(stream.user_id.clone());
+            let key = (platform, stream.user_id.clone());
+            let live = match online_streams.get_mut(&key) {
+                Some(live) => live,
+                None => {
+                    new_streams.push(stream);
+                    continue;
+                }
+            };
+            live.update(stream);
+            let data = StreamNotifEmbed::new(live);
+            for &(channel, message) in live.notifications.iter() {
+                let _ = ChannelId(channel)
+                    .edit_message(http, message, |m| m.embed(|e| data.build(e)))
+                    .await;
+            }
+            if let Err(why) = mysql.update_stream_notifications(live).await {
+                warn!("Error while updating stream notifications: {}", why);
+            }
+        }
 
-        // If there was no activity change since last time, don't do anything
-        if &now_online == online_streams {
-            None
-        } else {
-            // Filter streams whether its already known they're live
-            streams.retain(|stream| !online_streams.contains(&stream.user_id));
+        // Mark the notifications of streams that went offline as ended
+        let ended: Vec<_> = online_streams
+            .keys()
+            .filter(|(online_platform, user_id)| {
+                *online_platform == platform && !live_ids.contains(user_id)
+            })
+            .cloned()
+            .collect();
+        for key in ended {
+            let live = online_streams.remove(&key).unwrap();
+            _end_stream(http, mysql, &live).await;
+        }
+        if new_streams.is_empty() {
+            continue;
+        }
+        let notified = _notify_streams(http, stream_tracks, mysql, &**client, new_streams);
+        for live in notified.await {
+            online_streams.insert((platform, live.stream.user_id.clone()), live);
+        }
+    }
+    std::mem::drop(reading);
+    let mut writing = data.write().await;
+    *writing.get_mut::<OnlineStreams>().unwrap() = online_streams;
+}
 
-            let ids: Vec<_> = streams.iter().map(|s| s.user_id).collect();
-            let users: HashMap<_, _> = match twitch.get_users(&ids).await {
-                Ok(users) => users.into_iter().map(|u| (u.user_id, u)).collect(),
-                Err(why) => {
-                    warn!("Error while retrieving twitch users: {}", why);
-                    return;
-                }
+/// Handle an event right away instead of waiting for the next check
+async fn _handle_stream_event(http: &Http, data: &RwLock<TypeMap>, event: StreamEvent) {
+    match event {
+        StreamEvent::Online(user_id) => _stream_online(http, data, user_id).await,
+        StreamEvent::Offline(user_id) => {
+            let key = (Platform::Twitch, user_id);
+            let live = {
+                let mut writing = data.write().await;
+                writing.get_mut::<OnlineStreams>().unwrap().remove(&key)
             };
+            if let Some(live) = live {
+                let reading = data.read().await;
+                _end_stream(http, reading.get::<MySQL>().unwrap(), &live).await;
+            }
+        }
+    }
+}
 
-            let mut fmt_data = HashMap::new();
-            fmt_data.insert(String::from("width"), String::from("360"));
-            fmt_data.insert(String::from("height"), String::from("180"));
+// Twitch lists streams a while after their online event so retry until it shows up
+async fn _stream_online(http: &Http, data: &RwLock<TypeMap>, user_id: String) {
+    let key = (Platform::Twitch, user_id);
+    for &delay in STREAM_EVENT_DELAYS.iter() {
+        time::delay_for(time::Duration::from_secs(delay)).await;
+        let reading = data.read().await;
+        let stream_tracks = reading.get::<StreamTracks>().unwrap();
+        let tracked = stream_tracks
+            .iter()
+            .any(|track| track.platform == key.0 && track.user_id == key.1);
+        // Untracked or already found by the regular check
+        if !tracked || reading.get::<OnlineStreams>().unwrap().contains_key(&key) {
+            return;
+        }
+        let client = match reading.get::<StreamPlatforms>().unwrap().get(&key.0) {
+            Some(client) => client,
+            None => return,
+        };
+        let streams = match client.get_live_streams(&[key.1.clone()]).await {
+            Ok(streams) if streams.is_empty() => continue,
+            Ok(streams) => streams,
+            Err(why) => {
+                warn!("Error while retrieving {} stream {}: {}", key.0, key.1, why);
+                continue;
+            }
+        };
+        let mysql = reading.get::<MySQL>().unwrap();
+        let notified = _notify_streams(http, stream_tracks, mysql, &**client, streams).await;
+        std::mem::drop(reading);
+        let mut writing = data.write().await;
+        let online_streams = writing.get_mut::<OnlineStreams>().unwrap();
+        for live in notified {
+            online_streams.insert((key.0, live.stream.user_id.clone()), live);
+        }
+        return;
+    }
+    debug!(
+        "{} stream {} did not show up after its online event",
+        key.0, key.1
+    );
+}
 
-            // Put streams into a more suitable data type and process the thumbnail url
-            let streams: HashMap<u64, TwitchStream> = streams
-                .into_par_iter()
-                .map(|mut stream| {
-                    if let Ok(thumbnail) = strfmt(&stream.thumbnail_url, &fmt_data) {
-                        stream.thumbnail_url = thumbnail;
+// Mark the notifications of a stream that went offline as ended
+async fn _end_stream(http: &Http, mysql: &MySQL, live: &LiveStream) {
+    let data = StreamEndEmbed::new(live);
+    for &(channel, message) in live.notifications.iter() {
+        let _ = ChannelId(channel)
+            .edit_message(http, message, |m| m.embed(|e| data.build(e)))
+            .await;
+    }
+    let stream = &live.stream;
+    if let Err(why) = mysql
+        .remove_stream_notifications(stream.platform, &stream.user_id)
+        .await
+    {
+        warn!("Error while removing stream notifications: {}", why);
+    }
+}
+
+// Notify the tracking channels of streams that just came online
+async fn _notify_streams(
+    http: &Http,
+    stream_tracks: &HashSet<StreamTrack>,
+    mysql: &MySQL,
+    client: &dyn StreamPlatform,
+    new_streams: Vec<Stream>,
+) -> Vec<LiveStream> {
+    let platform = client.platform();
+    let mut notified = Vec::with_capacity(new_streams.len());
+    let ids: Vec<_> = new_streams.iter().map(|s| s.user_id.clone()).collect();
+    let mut users: HashMap<_, _> = match client.get_users(&ids).await {
+        Ok(users) => users.into_iter().map(|u| (u.user_id.clone(), u)).collect(),
+        Err(why) => {
+            warn!("Error while retrieving {} users: {}", platform, why);
+            HashMap::new()
+        }
+    };
+    for stream in new_streams {
+        let image_url = users
+            .remove(&stream.user_id)
+            .map(|user| user.image_url)
+            .unwrap_or_default();
+        let user_id = stream.user_id.clone();
+        let mut live = LiveStream::new(stream, image_url);
+        let data = StreamNotifEmbed::new(&live);
+        let tracks = stream_tracks
+            .iter()
+            .filter(|track| track.platform == platform && track.user_id == user_id);
+        for track in tracks {
+            let channel = ChannelId(track.channel_id);
+            let content = track.notification_content(&live.stream);
+            let message = channel.send_message(http, |m| {
+                if let Some(ref content) = content {
+                    m.content(content);
+                }
+                // Titles and names are chosen by the streamer, only the role may ping
+                m.allowed_mentions(|am| {
+                    am.empty_parse();
+                    if let Some(role) = track.role_id {
+                        am.roles(vec![role]);
                     }
-                    (stream.user_id, stream)
-                })
-                .collect();
-
-            // Process each tracking by notifying corresponding channels
-            for track in stream_tracks {
-                if streams.contains_key(&track.user_id) {
-                    let stream = streams.get(&track.user_id).unwrap();
-                    let data = TwitchNotifEmbed::new(stream, users.get(&stream.user_id).unwrap());
-                    let _ = ChannelId(track.channel_id)
-                        .send_message(http, |m| m.embed(|e| data.build(e)))
-                        .await;
+                    am
+                });
+                m.embed(|e| data.build(e))
+            });
+            let message = match message.await {
+                Ok(message) => message,
+                Err(why) => {
+                    warn!("Error while sending stream notification: {}", why);
+                    continue;
                 }
+            };
+            live.notifications.push((channel.0, message.id.0));
+            if let Err(why) = mysql
+                .add_stream_notification(channel.0, message.id.0, &live)
+                .await
+            {
+                warn!("Error while adding stream notification: {}", why);
             }
-            Some(now_online)
-        }
-    };
-    if let Some(now_online) = now_online {
-        let mut writing = data.write().await;
-        let online_twitch = writing.get_mut::<OnlineTwitch>().unwrap();
-        online_twitch.clear();
-        for id in now_online {
-            online_twitch.insert(id);
         }
+        notified.push(live);
     }
+    notified
 }
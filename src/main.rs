@@ -3,6 +3,8 @@ mod commands;
 pub mod database;
 mod embeds;
 mod events;
+mod osu_cache;
+mod osu_v2;
 pub mod pagination;
 pub mod roppai;
 mod scraper;
@@ -10,14 +12,20 @@ mod streams;
 pub mod structs;
 pub mod util;
 
+#[cfg(test)]
+mod tests;
+
 use crate::scraper::Scraper;
 use commands::{fun::*, help::*, osu::*, owner::*, streams::*, utility::*};
 use database::MySQL;
 use events::Handler;
-use streams::Twitch;
+use osu_cache::OsuCache;
+use osu_v2::OsuV2;
+use streams::{serve_eventsub, EventSubConfig, Platform, StreamPlatform, Twitch, YouTube};
 use structs::Osu;
 use structs::*;
 pub use util::{discord::get_member, MessageExt};
+use util::{bg_import, discord::command_permission};
 
 #[macro_use]
 extern crate bitflags;
@@ -29,23 +37,28 @@ extern crate log;
 use chrono::{Local, Utc};
 use fern::colors::{Color, ColoredLevelConfig};
 use log::LevelFilter;
-use rosu::backend::Osu as OsuClient;
+use rosu::{backend::Osu as OsuClient, models::GameMode};
 use serenity::{
     framework::{
         standard::{macros::hook, CommandResult, DispatchError},
         StandardFramework,
     },
     http::Http,
-    model::channel::{Channel, Message},
+    model::{
+        channel::{Channel, Message},
+        id::UserId,
+    },
     prelude::*,
 };
 use std::{
     collections::{HashMap, HashSet},
     env,
+    path::Path,
     sync::Arc,
 };
+use tokio::sync::mpsc;
 
-// Will create an async worker to regularly check for online twitch streams
+// Will create an async worker to regularly check for online streams
 pub const WITH_STREAM_TRACK: bool = false;
 // Will make the scraper use the osu_session cookie of an osu! account
 pub const WITH_SCRAPER: bool = false;
@@ -97,42 +110,96 @@ async fn main() {
     // Osu
     let osu_token = env::var("OSU_TOKEN").expect("Could not load OSU_TOKEN");
     let osu = OsuClient::new(osu_token);
+
+    // Background import from the command line, without starting the bot
+    let cli_args: Vec<_> = env::args().skip(1).collect();
+    if cli_args.first().map(String::as_str) == Some("import-bgs") {
+        import_bgs(&cli_args[1..], &mysql, &osu).await;
+        return;
+    }
+
     let discord_links = mysql
         .get_discord_links()
         .await
         .unwrap_or_else(|why| panic!("Could not get discord_links: {}", why));
 
+    // Osu api v2, optional since the scraper covers most of it
+    let osu_v2 = match (env::var("OSU_CLIENT_ID"), env::var("OSU_CLIENT_SECRET")) {
+        (Ok(client_id), Ok(client_secret)) => match OsuV2::new(client_id, client_secret).await {
+            Ok(osu_v2) => Some(osu_v2),
+            Err(why) => {
+                warn!("Could not create osu!api v2 client: {}", why);
+                None
+            }
+        },
+        _ => {
+            info!("No osu!api v2 credentials, skipping its client");
+            None
+        }
+    };
+
     // Scraper
-    let scraper = Scraper::new()
+    let scraper = Scraper::new(osu_v2)
         .await
         .unwrap_or_else(|why| panic!("Could not create Scraper: {}", why));
 
     // Stream tracking
-    let twitch_users = mysql
-        .get_twitch_users()
+    let stream_users = mysql
+        .get_stream_users()
         .await
-        .unwrap_or_else(|why| panic!("Could not get twitch_users: {}", why));
+        .unwrap_or_else(|why| panic!("Could not get stream_users: {}", why));
     let stream_tracks = mysql
         .get_stream_tracks()
         .await
         .unwrap_or_else(|why| panic!("Could not get stream_tracks: {}", why));
-    let twitch_client_id = env::var("TWITCH_CLIENT_ID").expect("Could not load TWITCH_CLIENT_ID");
-    let twitch_token = env::var("TWITCH_TOKEN").expect("Could not load TWITCH_TOKEN");
-    let twitch = if WITH_STREAM_TRACK {
-        Some(
-            Twitch::new(&twitch_client_id, &twitch_token)
+    let online_streams = mysql
+        .get_stream_notifications()
+        .await
+        .unwrap_or_else(|why| panic!("Could not get stream_notifications: {}", why));
+    let mut stream_platforms: HashMap<Platform, Box<dyn StreamPlatform>> = HashMap::new();
+    let mut stream_events = None;
+    if WITH_STREAM_TRACK {
+        let twitch_client_id =
+            env::var("TWITCH_CLIENT_ID").expect("Could not load TWITCH_CLIENT_ID");
+        let twitch_token = env::var("TWITCH_TOKEN").expect("Could not load TWITCH_TOKEN");
+        let mut twitch = Twitch::new(&twitch_client_id, &twitch_token)
+            .await
+            .unwrap_or_else(|why| panic!("Could not create Twitch: {}", why));
+        // EventSub notifies about twitch streams right away instead of waiting for the next poll
+        if let Ok(callback) = env::var("TWITCH_EVENTSUB_CALLBACK") {
+            let secret =
+                env::var("TWITCH_EVENTSUB_SECRET").expect("Could not load TWITCH_EVENTSUB_SECRET");
+            let addr = env::var("TWITCH_EVENTSUB_ADDR")
+                .expect("Could not load TWITCH_EVENTSUB_ADDR")
+                .parse()
+                .unwrap_or_else(|why| panic!("Could not parse TWITCH_EVENTSUB_ADDR: {}", why));
+            let (sender, receiver) = mpsc::unbounded_channel();
+            let addr = serve_eventsub(addr, secret.clone(), sender)
                 .await
-                .unwrap_or_else(|why| panic!("Could not create Twitch: {}", why)),
-        )
-    } else {
-        None
-    };
+                .unwrap_or_else(|why| panic!("Could not start EventSub server: {}", why));
+            info!("Listening for twitch EventSub callbacks on {}", addr);
+            twitch = twitch.with_eventsub(EventSubConfig { callback, secret });
+            stream_events = Some(receiver);
+        }
+        stream_platforms.insert(Platform::Twitch, Box::new(twitch));
+        // YouTube is optional since its quota is rather small
+        match env::var("YOUTUBE_API_KEY") {
+            Ok(api_key) => {
+                stream_platforms.insert(Platform::YouTube, Box::new(YouTube::new(api_key)));
+            }
+            Err(_) => info!("No YouTube api key, skipping youtube stream tracking"),
+        }
+    }
 
     // Individual guild settings
     let guilds = mysql
         .get_guilds()
         .await
         .unwrap_or_else(|why| panic!("Could not get Guilds: {}", why));
+    let command_permissions = mysql
+        .get_command_permissions()
+        .await
+        .unwrap_or_else(|why| panic!("Could not get command permissions: {}", why));
 
     // General
     let owners = match http.get_current_application_info().await {
@@ -155,9 +222,67 @@ async fn main() {
     // Framework setup
     // ---------------
 
-    let framework = StandardFramework::new()
+    let framework = create_framework(owners).await;
+
+    let mut discord = Client::new(&discord_token)
+        .event_handler(Handler)
+        .framework(framework)
+        .await
+        .expect("Could not create discord client");
+
+    // Insert everything
+    {
+        let mut data = discord.data.write().await;
+        data.insert::<CommandCounter>(HashMap::default());
+        data.insert::<Osu>(Arc::new(osu));
+        data.insert::<OsuCache>(Arc::new(OsuCache::default()));
+        data.insert::<Scraper>(scraper);
+        data.insert::<MySQL>(Arc::new(mysql));
+        data.insert::<DiscordLinks>(discord_links);
+        data.insert::<BootTime>(now);
+        data.insert::<PerformanceCalculatorLock>(Arc::new(Mutex::new(())));
+        data.insert::<StreamUsers>(stream_users);
+        data.insert::<StreamTracks>(stream_tracks);
+        data.insert::<OnlineStreams>(online_streams);
+        data.insert::<StreamPlatforms>(stream_platforms);
+        data.insert::<StreamEvents>(stream_events);
+        data.insert::<Guilds>(guilds);
+        data.insert::<CommandPermissions>(command_permissions);
+        data.insert::<BgGames>(HashMap::new());
+        data.insert::<SongGames>(HashMap::new());
+        data.insert::<BgVerified>(verified_users);
+    }
+
+    // Boot it all up
+    if let Err(why) = discord.start().await {
+        panic!("Client error: {}", why)
+    }
+}
+
+// `bathbot import-bgs [directory or zip] [std / mania]`
+async fn import_bgs(args: &[String], mysql: &MySQL, osu: &OsuClient) {
+    let path = match args.first() {
+        Some(path) => Path::new(path),
+        None => {
+            error!("Usage: bathbot import-bgs [directory or zip] [std / mania]");
+            return;
+        }
+    };
+    let mode = match args.get(1).map(|mode| mode.to_lowercase()).as_deref() {
+        Some("mna") | Some("mania") | Some("m") => GameMode::MNA,
+        _ => GameMode::STD,
+    };
+    match bg_import::import_backgrounds(path, mode, mysql, osu).await {
+        Ok(report) => info!("Background import finished:\n{}", report),
+        Err(why) => error!("Background import failed: {}", why),
+    }
+}
+
+// Shared between the actual client and the command tests
+async fn create_framework(owners: HashSet<UserId>) -> StandardFramework {
+    StandardFramework::new()
         .configure(|c| {
-            c.prefixes(vec!["<", "!!"])
+            c.prefixes(commands::PREFIXES.to_vec())
                 .delimiter(' ')
                 .case_insensitivity(true)
                 .ignore_bots(true)
@@ -184,39 +309,7 @@ async fn main() {
         .group(&FUN_GROUP)
         .group(&UTILITY_GROUP)
         .group(&STREAMTRACKING_GROUP)
-        .group(&OWNER_GROUP);
-
-    let mut discord = Client::new(&discord_token)
-        .event_handler(Handler)
-        .framework(framework)
-        .await
-        .expect("Could not create discord client");
-
-    // Insert everything
-    {
-        let mut data = discord.data.write().await;
-        data.insert::<CommandCounter>(HashMap::default());
-        data.insert::<Osu>(osu);
-        data.insert::<Scraper>(scraper);
-        data.insert::<MySQL>(mysql);
-        data.insert::<DiscordLinks>(discord_links);
-        data.insert::<BootTime>(now);
-        data.insert::<PerformanceCalculatorLock>(Arc::new(Mutex::new(())));
-        data.insert::<TwitchUsers>(twitch_users);
-        data.insert::<StreamTracks>(stream_tracks);
-        data.insert::<OnlineTwitch>(HashSet::new());
-        if let Some(twitch) = twitch {
-            data.insert::<Twitch>(twitch);
-        }
-        data.insert::<Guilds>(guilds);
-        data.insert::<BgGames>(HashMap::new());
-        data.insert::<BgVerified>(verified_users);
-    }
-
-    // Boot it all up
-    if let Err(why) = discord.start().await {
-        panic!("Client error: {}", why)
-    }
+        .group(&OWNER_GROUP)
 }
 
 #[hook]
@@ -234,6 +327,12 @@ async fn before(ctx: &Context, msg: &Message, cmd_name: &str) -> bool {
         None => "Private".to_owned(),
     };
     info!("[{}] {}: {}", location, msg.author.name, msg.content);
+    if let Err(content) = command_permission(ctx, msg).await {
+        if let Ok(response) = msg.channel_id.say(ctx, content).await {
+            response.reaction_delete(ctx, msg.author.id).await;
+        }
+        return false;
+    }
     match ctx.data.write().await.get_mut::<CommandCounter>() {
         Some(counter) => *counter.entry(cmd_name.to_owned()).or_insert(0) += 1,
         None => warn!("Could not get CommandCounter"),
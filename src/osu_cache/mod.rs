@@ -0,0 +1,191 @@
+mod ttl_map;
+
+pub use ttl_map::CacheStats;
+use ttl_map::TtlMap;
+
+use failure::Error;
+use rosu::{
+    backend::{
+        requests::{BeatmapRequest, RecentRequest, UserRequest},
+        Osu as OsuClient,
+    },
+    models::{Beatmap, GameMode, Score, User},
+};
+use std::{sync::Mutex, time::Duration};
+
+type Result<T> = std::result::Result<T, Error>;
+
+const USER_TTL: Duration = Duration::from_secs(300);
+const BEST_TTL: Duration = Duration::from_secs(300);
+const RECENT_TTL: Duration = Duration::from_secs(30);
+const MAP_TTL: Duration = Duration::from_secs(3600);
+
+const USER_CAPACITY: usize = 2000;
+const BEST_CAPACITY: usize = 500;
+const RECENT_CAPACITY: usize = 500;
+const MAP_CAPACITY: usize = 5000;
+
+#[derive(Clone, Hash, PartialEq, Eq)]
+enum UserKey {
+    Id(u32, u8),
+    Name(String, u8),
+}
+
+/// Short-lived in-memory cache in front of the osu!api so that
+/// consecutive commands on the same user don't repeat the same requests
+pub struct OsuCache {
+    users: Mutex<TtlMap<UserKey, User>>,
+    best: Mutex<TtlMap<(u32, u8), Vec<Score>>>,
+    /// User ids of the cached top scores so they can be invalidated by name
+    best_ids: Mutex<TtlMap<(String, u8), u32>>,
+    recent: Mutex<TtlMap<(String, u8), Vec<Score>>>,
+    maps: Mutex<TtlMap<u32, Beatmap>>,
+}
+
+impl Default for OsuCache {
+    fn default() -> Self {
+        Self {
+            users: Mutex::new(TtlMap::new(USER_TTL, USER_CAPACITY)),
+            best: Mutex::new(TtlMap::new(BEST_TTL, BEST_CAPACITY)),
+            best_ids: Mutex::new(TtlMap::new(BEST_TTL, BEST_CAPACITY)),
+            recent: Mutex::new(TtlMap::new(RECENT_TTL, RECENT_CAPACITY)),
+            maps: Mutex::new(TtlMap::new(MAP_TTL, MAP_CAPACITY)),
+        }
+    }
+}
+
+impl OsuCache {
+    pub async fn user(&self, osu: &OsuClient, name: &str, mode: GameMode) -> Result<Option<User>> {
+        let key = UserKey::Name(name.to_lowercase(), mode as u8);
+        let cached = self.users.lock().unwrap().get(&key);
+        if let Some(user) = cached {
+            return Ok(Some(user));
+        }
+        let user = UserRequest::with_username(name)
+            .mode(mode)
+            .queue_single(osu)
+            .await
+            .map_err(|why| format_err!("{}", why))?;
+        if let Some(ref user) = user {
+            self.insert_user(user, mode);
+            // Also cache it under the requested name in case that's not the
+            // actual one, e.g. a previous name of the user
+            self.users.lock().unwrap().insert(key, user.clone());
+        }
+        Ok(user)
+    }
+
+    pub async fn user_by_id(
+        &self,
+        osu: &OsuClient,
+        user_id: u32,
+        mode: GameMode,
+    ) -> Result<Option<User>> {
+        let key = UserKey::Id(user_id, mode as u8);
+        let cached = self.users.lock().unwrap().get(&key);
+        if let Some(user) = cached {
+            return Ok(Some(user));
+        }
+        let user = UserRequest::with_user_id(user_id)
+            .mode(mode)
+            .queue_single(osu)
+            .await
+            .map_err(|why| format_err!("{}", why))?;
+        if let Some(ref user) = user {
+            self.insert_user(user, mode);
+        }
+        Ok(user)
+    }
+
+    /// Top 100 scores of the user
+    pub async fn best(&self, osu: &OsuClient, user: &User, mode: GameMode) -> Result<Vec<Score>> {
+        let key = (user.user_id, mode as u8);
+        let cached = self.best.lock().unwrap().get(&key);
+        if let Some(scores) = cached {
+            return Ok(scores);
+        }
+        let scores = user
+            .get_top_scores(osu, 100, mode)
+            .await
+            .map_err(|why| format_err!("{}", why))?;
+        self.best.lock().unwrap().insert(key, scores.clone());
+        let name_key = (user.username.to_lowercase(), mode as u8);
+        self.best_ids.lock().unwrap().insert(name_key, user.user_id);
+        Ok(scores)
+    }
+
+    /// Up to 50 most recent scores of the user
+    pub async fn recent(&self, osu: &OsuClient, name: &str, mode: GameMode) -> Result<Vec<Score>> {
+        let key = (name.to_lowercase(), mode as u8);
+        let cached = self.recent.lock().unwrap().get(&key);
+        if let Some(scores) = cached {
+            return Ok(scores);
+        }
+        let scores = RecentRequest::with_username(name)
+            .mode(mode)
+            .limit(50)
+            .queue(osu)
+            .await
+            .map_err(|why| format_err!("{}", why))?;
+        self.recent.lock().unwrap().insert(key, scores.clone());
+        Ok(scores)
+    }
+
+    pub async fn beatmap(&self, osu: &OsuClient, map_id: u32) -> Result<Option<Beatmap>> {
+        let cached = self.maps.lock().unwrap().get(&map_id);
+        if let Some(map) = cached {
+            return Ok(Some(map));
+        }
+        let map = BeatmapRequest::new()
+            .map_id(map_id)
+            .queue_single(osu)
+            .await
+            .map_err(|why| format_err!("{}", why))?;
+        if let Some(ref map) = map {
+            self.maps.lock().unwrap().insert(map_id, map.clone());
+        }
+        Ok(map)
+    }
+
+    /// Drop everything cached for the user in the given mode,
+    /// e.g. because they just set a new score
+    pub fn invalidate_user(&self, name: &str, mode: GameMode) {
+        let name = name.to_lowercase();
+        let mode = mode as u8;
+        let user_id = {
+            let mut users = self.users.lock().unwrap();
+            let user = users.remove(&UserKey::Name(name.clone(), mode));
+            // The requested name might be a previous one so also remove the actual name
+            if let Some(ref user) = user {
+                users.remove(&UserKey::Name(user.username.to_lowercase(), mode));
+                users.remove(&UserKey::Id(user.user_id, mode));
+            }
+            user.map(|user| user.user_id)
+        };
+        // The user entry might be gone already while the top scores are still cached
+        let best_id = self.best_ids.lock().unwrap().remove(&(name.clone(), mode));
+        {
+            let mut best = self.best.lock().unwrap();
+            for user_id in user_id.into_iter().chain(best_id) {
+                best.remove(&(user_id, mode));
+            }
+        }
+        self.recent.lock().unwrap().remove(&(name, mode));
+    }
+
+    pub fn stats(&self) -> [(&'static str, CacheStats); 4] {
+        [
+            ("Users", self.users.lock().unwrap().stats()),
+            ("Top scores", self.best.lock().unwrap().stats()),
+            ("Recent scores", self.recent.lock().unwrap().stats()),
+            ("Beatmaps", self.maps.lock().unwrap().stats()),
+        ]
+    }
+
+    fn insert_user(&self, user: &User, mode: GameMode) {
+        let mut users = self.users.lock().unwrap();
+        let name_key = UserKey::Name(user.username.to_lowercase(), mode as u8);
+        users.insert(name_key, user.clone());
+        users.insert(UserKey::Id(user.user_id, mode as u8), user.clone());
+    }
+}
@@ -0,0 +1,125 @@
+use std::{
+    collections::HashMap,
+    hash::Hash,
+    time::{Duration, Instant},
+};
+
+#[derive(Copy, Clone, Debug, Default)]
+pub struct CacheStats {
+    pub hits: u64,
+    pub misses: u64,
+    pub len: usize,
+}
+
+impl CacheStats {
+    pub fn hit_rate(&self) -> f32 {
+        let total = self.hits + self.misses;
+        if total == 0 {
+            0.0
+        } else {
+            100.0 * self.hits as f32 / total as f32
+        }
+    }
+}
+
+/// Map whose entries expire after `ttl` and which holds at most `capacity` entries
+pub struct TtlMap<K, V> {
+    entries: HashMap<K, (Instant, V)>,
+    ttl: Duration,
+    capacity: usize,
+    hits: u64,
+    misses: u64,
+}
+
+impl<K: Hash + Eq + Clone, V: Clone> TtlMap<K, V> {
+    pub fn new(ttl: Duration, capacity: usize) -> Self {
+        Self {
+            entries: HashMap::with_capacity(capacity.min(256)),
+            ttl,
+            capacity,
+            hits: 0,
+            misses: 0,
+        }
+    }
+
+    pub fn get(&mut self, key: &K) -> Option<V> {
+        let ttl = self.ttl;
+        let fresh = self
+            .entries
+            .get(key)
+            .filter(|(inserted, _)| inserted.elapsed() < ttl)
+            .map(|(_, value)| value.clone());
+        if fresh.is_some() {
+            self.hits += 1;
+        } else {
+            self.misses += 1;
+            self.entries.remove(key);
+        }
+        fresh
+    }
+
+    pub fn insert(&mut self, key: K, value: V) {
+        if self.entries.len() >= self.capacity && !self.entries.contains_key(&key) {
+            self.evict();
+        }
+        self.entries.insert(key, (Instant::now(), value));
+    }
+
+    pub fn remove(&mut self, key: &K) -> Option<V> {
+        self.entries.remove(key).map(|(_, value)| value)
+    }
+
+    pub fn stats(&self) -> CacheStats {
+        CacheStats {
+            hits: self.hits,
+            misses: self.misses,
+            len: self.entries.len(),
+        }
+    }
+
+    // Drop expired entries or, if there are none, the oldest one
+    fn evict(&mut self) {
+        let ttl = self.ttl;
+        let len = self.entries.len();
+        self.entries
+            .retain(|_, (inserted, _)| inserted.elapsed() < ttl);
+        if self.entries.len() < len {
+            return;
+        }
+        let oldest = self
+            .entries
+            .iter()
+            .min_by_key(|(_, (inserted, _))| *inserted)
+            .map(|(key, _)| key.clone());
+        if let Some(key) = oldest {
+            self.entries.remove(&key);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn expires_and_counts() {
+        let mut map = TtlMap::new(Duration::from_millis(20), 10);
+        map.insert(1, "a");
+        assert_eq!(map.get(&1), Some("a"));
+        std::thread::sleep(Duration::from_millis(30));
+        assert_eq!(map.get(&1), None);
+        let stats = map.stats();
+        assert_eq!((stats.hits, stats.misses, stats.len), (1, 1, 0));
+    }
+
+    #[test]
+    fn evicts_oldest_when_full() {
+        let mut map = TtlMap::new(Duration::from_secs(60), 2);
+        map.insert(1, 1);
+        map.insert(2, 2);
+        map.insert(3, 3);
+        assert_eq!(map.get(&1), None);
+        assert_eq!(map.get(&2), Some(2));
+        assert_eq!(map.get(&3), Some(3));
+    }
+}
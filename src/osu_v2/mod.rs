@@ -0,0 +1,132 @@
+mod models;
+
+pub use models::{Beatmapset, Rankings, UserExtended};
+
+use crate::{
+    scraper::{MostPlayedMap, ScraperScore, ScraperScores},
+    util::{
+        globals::{OSU_API_V2, OSU_OAUTH_TOKEN},
+        oauth::ClientToken,
+    },
+};
+
+use failure::Error;
+use governor::{
+    clock::DefaultClock,
+    state::{direct::NotKeyed, InMemoryState},
+    Quota, RateLimiter,
+};
+use reqwest::{Client, StatusCode};
+use rosu::models::GameMode;
+use serde::de::DeserializeOwned;
+use std::num::NonZeroU32;
+
+type Result<T> = std::result::Result<T, Error>;
+
+/// Client for the parts of the osu!api v2 that are usable with client credentials
+pub struct OsuV2 {
+    client: Client,
+    token: ClientToken,
+    ratelimiter: RateLimiter<NotKeyed, InMemoryState, DefaultClock>,
+}
+
+impl OsuV2 {
+    pub async fn new(client_id: String, client_secret: String) -> Result<Self> {
+        let client = Client::new();
+        let token = ClientToken::new(
+            &client,
+            "osu!api v2",
+            OSU_OAUTH_TOKEN,
+            client_id,
+            client_secret,
+            Some("public"),
+        )
+        .await?;
+        let quota = Quota::per_second(NonZeroU32::new(10).unwrap());
+        let ratelimiter = RateLimiter::direct(quota);
+        Ok(Self {
+            client,
+            token,
+            ratelimiter,
+        })
+    }
+
+    async fn send_request<T: DeserializeOwned>(
+        &self,
+        route: &str,
+        query: &[(&str, String)],
+    ) -> Result<T> {
+        let url = format!("{}{}", OSU_API_V2, route);
+        let mut retried = false;
+        loop {
+            let token = self.token.get(&self.client).await?;
+            self.ratelimiter.until_ready().await;
+            debug!("Requesting osu!api v2 route {}", route);
+            let response = self
+                .client
+                .get(&url)
+                .bearer_auth(&token)
+                .query(query)
+                .send()
+                .await?;
+            // The token might have been revoked early
+            if response.status() == StatusCode::UNAUTHORIZED && !retried {
+                retried = true;
+                self.token.renew(&self.client, &token).await?;
+                continue;
+            }
+            let response = match response.error_for_status() {
+                Ok(response) => response,
+                Err(why) => bail!("osu!api v2 got bad response: {}", why),
+            };
+            return Ok(serde_json::from_slice(&response.bytes().await?)?);
+        }
+    }
+
+    /// Performance rankings, pages contain 50 users each and start at 1
+    pub async fn get_rankings(
+        &self,
+        mode: GameMode,
+        country: Option<&str>,
+        page: usize,
+    ) -> Result<Rankings> {
+        let route = format!("rankings/{}/performance", mode_str(mode));
+        let mut query = vec![("cursor[page]", page.to_string())];
+        if let Some(country) = country {
+            query.push(("country", country.to_owned()));
+        }
+        self.send_request(&route, &query).await
+    }
+
+    /// Global top 50 of a map, country and mod leaderboards require a user token
+    pub async fn get_beatmap_scores(&self, map_id: u32) -> Result<Vec<ScraperScore>> {
+        let route = format!("beatmaps/{}/scores", map_id);
+        let scores: ScraperScores = self.send_request(&route, &[]).await?;
+        Ok(scores.get())
+    }
+
+    pub async fn get_beatmapset(&self, mapset_id: u32) -> Result<Beatmapset> {
+        let route = format!("beatmapsets/{}", mapset_id);
+        self.send_request(&route, &[]).await
+    }
+
+    pub async fn get_most_played(&self, user_id: u32, amount: u32) -> Result<Vec<MostPlayedMap>> {
+        let route = format!("users/{}/beatmapsets/most_played", user_id);
+        let query = [("limit", amount.to_string())];
+        self.send_request(&route, &query).await
+    }
+
+    pub async fn get_user(&self, user_id: u32, mode: GameMode) -> Result<UserExtended> {
+        let route = format!("users/{}/{}", user_id, mode_str(mode));
+        self.send_request(&route, &[]).await
+    }
+}
+
+fn mode_str(mode: GameMode) -> &'static str {
+    match mode {
+        GameMode::STD => "osu",
+        GameMode::MNA => "mania",
+        GameMode::TKO => "taiko",
+        GameMode::CTB => "fruits",
+    }
+}
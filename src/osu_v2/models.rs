@@ -0,0 +1,42 @@
+use serde_derive::Deserialize;
+
+#[derive(Deserialize)]
+pub struct Rankings {
+    pub ranking: Vec<UserStatistics>,
+    pub total: u32,
+}
+
+#[derive(Deserialize)]
+pub struct UserStatistics {
+    pub pp: f32,
+    pub global_rank: Option<u32>,
+    pub hit_accuracy: f32,
+    pub play_count: u32,
+    #[serde(default)]
+    pub maximum_combo: u32,
+    #[serde(default)]
+    pub replays_watched_by_others: u32,
+    pub user: Option<UserCompact>,
+}
+
+#[derive(Deserialize)]
+pub struct UserCompact {
+    pub id: u32,
+    pub username: String,
+    pub country_code: String,
+}
+
+#[derive(Deserialize)]
+pub struct UserExtended {
+    #[serde(default)]
+    pub follower_count: u32,
+    pub statistics: UserStatistics,
+}
+
+#[derive(Deserialize)]
+pub struct Beatmapset {
+    pub title: String,
+    pub title_unicode: String,
+    pub artist: String,
+    pub artist_unicode: String,
+}
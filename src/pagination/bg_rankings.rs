@@ -1,5 +1,5 @@
 use super::{create_collector, Pages, Pagination};
-use crate::embeds::BGRankingEmbed;
+use crate::embeds::{BGRankingEmbed, BGRankingView};
 
 use failure::Error;
 use serenity::{
@@ -17,7 +17,7 @@ pub struct BGRankingPagination {
     collector: ReactionCollector,
     pages: Pages,
     author_idx: Option<usize>,
-    global: bool,
+    view: BGRankingView,
     scores: Vec<(u64, u32)>,
     usernames: HashMap<u64, String>,
     http: Arc<Http>,
@@ -31,7 +31,7 @@ impl BGRankingPagination {
         author: UserId,
         author_idx: Option<usize>,
         scores: Vec<(u64, u32)>,
-        global: bool,
+        view: BGRankingView,
     ) -> Self {
         let collector = create_collector(ctx, &msg, author, 60).await;
         let cache = Arc::clone(&ctx.cache);
@@ -44,7 +44,7 @@ impl BGRankingPagination {
             author_idx,
             scores,
             usernames: HashMap::with_capacity(per_page),
-            global,
+            view,
             http,
             cache,
         }
@@ -99,7 +99,7 @@ impl Pagination for BGRankingPagination {
         Ok(BGRankingEmbed::new(
             self.author_idx,
             scores,
-            self.global,
+            self.view,
             self.pages.index + 1,
             (self.page(), self.pages.total_pages),
         ))
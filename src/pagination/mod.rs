@@ -1,11 +1,14 @@
 mod bg_rankings;
 mod command_count;
 mod common;
+mod hl_rankings;
 mod leaderboard;
 mod map;
+mod mod_log;
 mod most_played;
 mod most_played_common;
 mod nochoke;
+mod osu_streams;
 mod osustats_globals;
 mod recent;
 mod top;
@@ -13,11 +16,14 @@ mod top;
 pub use bg_rankings::BGRankingPagination;
 pub use command_count::CommandCountPagination;
 pub use common::CommonPagination;
+pub use hl_rankings::HLRankingPagination;
 pub use leaderboard::LeaderboardPagination;
 pub use map::MapPagination;
+pub use mod_log::ModLogPagination;
 pub use most_played::MostPlayedPagination;
 pub use most_played_common::MostPlayedCommonPagination;
 pub use nochoke::NoChokePagination;
+pub use osu_streams::OsuStreamsPagination;
 pub use osustats_globals::OsuStatsGlobalsPagination;
 pub use recent::RecentPagination;
 pub use top::TopPagination;
@@ -0,0 +1,67 @@
+use super::{create_collector, Pages, Pagination};
+
+use crate::{database::ModLogEntry, embeds::ModLogHistoryEmbed};
+
+use failure::Error;
+use serenity::{
+    async_trait,
+    client::Context,
+    collector::ReactionCollector,
+    model::{channel::Message, id::UserId},
+};
+
+pub struct ModLogPagination {
+    msg: Message,
+    collector: ReactionCollector,
+    pages: Pages,
+    name: String,
+    entries: Vec<ModLogEntry>,
+}
+
+impl ModLogPagination {
+    pub async fn new(
+        ctx: &Context,
+        msg: Message,
+        author: UserId,
+        name: String,
+        entries: Vec<ModLogEntry>,
+    ) -> Self {
+        let collector = create_collector(ctx, &msg, author, 60).await;
+        Self {
+            msg,
+            collector,
+            pages: Pages::new(10, entries.len()),
+            name,
+            entries,
+        }
+    }
+}
+
+#[async_trait]
+impl Pagination for ModLogPagination {
+    type PageData = ModLogHistoryEmbed;
+    fn msg(&mut self) -> &mut Message {
+        &mut self.msg
+    }
+    fn collector(&mut self) -> &mut ReactionCollector {
+        &mut self.collector
+    }
+    fn pages(&self) -> Pages {
+        self.pages
+    }
+    fn pages_mut(&mut self) -> &mut Pages {
+        &mut self.pages
+    }
+    async fn build_page(&mut self) -> Result<Self::PageData, Error> {
+        let end = self
+            .entries
+            .len()
+            .min(self.pages.index + self.pages.per_page);
+        Ok(ModLogHistoryEmbed::new(
+            &self.name,
+            &self.entries[self.pages.index..end],
+            self.pages.index + 1,
+            (self.page(), self.pages.total_pages),
+        ))
+    }
+}
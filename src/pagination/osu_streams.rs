@@ -0,0 +1,59 @@
+use super::{create_collector, Pages, Pagination};
+
+use crate::{embeds::OsuStreamsEmbed, streams::Stream};
+
+use failure::Error;
+use serenity::{
+    async_trait,
+    client::Context,
+    collector::ReactionCollector,
+    model::{channel::Message, id::UserId},
+};
+
+pub struct OsuStreamsPagination {
+    msg: Message,
+    collector: ReactionCollector,
+    pages: Pages,
+    streams: Vec<Stream>,
+}
+
+impl OsuStreamsPagination {
+    pub async fn new(ctx: &Context, msg: Message, author: UserId, streams: Vec<Stream>) -> Self {
+        let collector = create_collector(ctx, &msg, author, 60).await;
+        Self {
+            msg,
+            collector,
+            pages: Pages::new(10, streams.len()),
+            streams,
+        }
+    }
+}
+
+#[async_trait]
+impl Pagination for OsuStreamsPagination {
+    type PageData = OsuStreamsEmbed;
+    fn msg(&mut self) -> &mut Message {
+        &mut self.msg
+    }
+    fn collector(&mut self) -> &mut ReactionCollector {
+        &mut self.collector
+    }
+    fn pages(&self) -> Pages {
+        self.pages
+    }
+    fn pages_mut(&mut self) -> &mut Pages {
+        &mut self.pages
+    }
+    async fn build_page(&mut self) -> Result<Self::PageData, Error> {
+        let end = self
+            .streams
+            .len()
+            .min(self.pages.index + self.pages.per_page);
+        Ok(OsuStreamsEmbed::new(
+            &self.streams[self.pages.index..end],
+            self.pages.index + 1,
+            (self.page(), self.pages.total_pages),
+            self.streams.len(),
+        ))
+    }
+}
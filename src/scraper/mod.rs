@@ -1,14 +1,15 @@
 mod deserialize;
 mod most_played;
 mod osu_stats;
+mod rankings;
 mod score;
 
 pub use most_played::MostPlayedMap;
 pub use osu_stats::*;
-use score::ScraperScores;
-pub use score::{ScraperBeatmap, ScraperScore};
+pub use rankings::{parse_rankings, RankingEntry};
+pub use score::{ScraperBeatmap, ScraperScore, ScraperScores};
 
-use crate::{arguments::ModSelection, util::globals::HOMEPAGE, WITH_SCRAPER};
+use crate::{arguments::ModSelection, osu_v2::OsuV2, util::globals::HOMEPAGE, WITH_SCRAPER};
 
 use failure::Error;
 use governor::{
@@ -22,7 +23,6 @@ use reqwest::{
     Client, Response,
 };
 use rosu::models::{GameMode, GameMods};
-use scraper::{Html, Node, Selector};
 use serde_json::Value;
 use std::{collections::HashSet, convert::TryFrom, env, fmt::Write, num::NonZeroU32};
 
@@ -31,10 +31,12 @@ type Result<T> = std::result::Result<T, Error>;
 pub struct Scraper {
     client: Client,
     ratelimiter: RateLimiter<NotKeyed, InMemoryState, DefaultClock>,
+    // Preferred over scraping whenever it provides the data
+    api_v2: Option<OsuV2>,
 }
 
 impl Scraper {
-    pub async fn new() -> Result<Self> {
+    pub async fn new(api_v2: Option<OsuV2>) -> Result<Self> {
         // Initialize client
         let mut builder = Client::builder();
         if WITH_SCRAPER {
@@ -54,9 +56,14 @@ impl Scraper {
         Ok(Self {
             client,
             ratelimiter,
+            api_v2,
         })
     }
 
+    pub fn api_v2(&self) -> Option<&OsuV2> {
+        self.api_v2.as_ref()
+    }
+
     async fn send_request(&self, url: String) -> Result<Response> {
         debug!("Scraping url {}", url);
         self.ratelimiter.until_ready().await;
@@ -111,6 +118,12 @@ impl Scraper {
 
     // Retrieve the most played maps of a user
     pub async fn get_most_played(&self, user_id: u32, amount: u32) -> Result<Vec<MostPlayedMap>> {
+        if let Some(api) = self.api_v2.as_ref() {
+            match api.get_most_played(user_id, amount).await {
+                Ok(maps) => return Ok(maps),
+                Err(why) => warn!("Falling back to scraper for most played: {}", why),
+            }
+        }
         let url = format!(
             "{base}users/{id}/beatmapsets/most_played?limit={limit}",
             base = HOMEPAGE,
@@ -163,6 +176,13 @@ impl Scraper {
         national: bool,
         mods: Option<&GameMods>,
     ) -> Result<Vec<ScraperScore>> {
+        // Client credentials only grant access to the unfiltered global leaderboard
+        if let (Some(api), false, None) = (self.api_v2.as_ref(), national, mods) {
+            match api.get_beatmap_scores(map_id).await {
+                Ok(scores) => return Ok(scores),
+                Err(why) => warn!("Falling back to scraper for leaderboard: {}", why),
+            }
+        }
         let mut url = format!("{base}beatmaps/{id}/scores?", base = HOMEPAGE, id = map_id);
         if national {
             url.push_str("type=country");
@@ -190,6 +210,56 @@ impl Scraper {
         if rank < 1 || 10_000 < rank {
             bail!("Rank must be between 1 and 10_000, got {}", rank);
         }
+        let mut page_idx = rank / 50;
+        if rank % 50 != 0 {
+            page_idx += 1;
+        }
+        let entries = self
+            .get_rankings_page(mode, country_acronym, page_idx)
+            .await?;
+        // Entries without user are skipped so the index can't be relied on
+        match entries.iter().find(|entry| entry.rank == rank) {
+            Some(entry) => Ok(entry.user_id),
+            None => bail!("No user with rank {} in the rankings", rank),
+        }
+    }
+
+    // Retrieve a page of the performance rankings, each containing 50 users, starting at page 1
+    pub async fn get_rankings_page(
+        &self,
+        mode: GameMode,
+        country_acronym: Option<&str>,
+        page: usize,
+    ) -> Result<Vec<RankingEntry>> {
+        if page < 1 || 200 < page {
+            bail!("Page must be between 1 and 200, got {}", page);
+        }
+        if let Some(api) = self.api_v2.as_ref() {
+            match api.get_rankings(mode, country_acronym, page).await {
+                Ok(rankings) => {
+                    let offset = (page - 1) * 50;
+                    let entries = rankings
+                        .ranking
+                        .into_iter()
+                        .enumerate()
+                        .filter_map(|(i, stats)| {
+                            let user = stats.user?;
+                            Some(RankingEntry {
+                                rank: offset + i + 1,
+                                user_id: user.id,
+                                username: user.username,
+                                country_code: user.country_code,
+                                pp: stats.pp,
+                                accuracy: stats.hit_accuracy,
+                                playcount: stats.play_count,
+                            })
+                        })
+                        .collect();
+                    return Ok(entries);
+                }
+                Err(why) => warn!("Falling back to scraper for rankings: {}", why),
+            }
+        }
         let mode = get_mode_str(mode);
         let mut url = format!(
             "{base}rankings/{mode}/performance?",
@@ -199,54 +269,13 @@ impl Scraper {
         if let Some(country) = country_acronym {
             let _ = write!(url, "country={}&", country);
         }
-        let mut page_idx = rank / 50;
-        if rank % 50 != 0 {
-            page_idx += 1;
-        }
-        let _ = write!(url, "page={}", page_idx);
+        let _ = write!(url, "page={}", page);
         let response = self.send_request(url).await?;
         let body = match response.error_for_status() {
             Ok(res) => res.text().await?,
             Err(why) => bail!("Scraper got bad response: {}", why),
         };
-        let html = Html::parse_document(&body);
-        let ranking_page_table = Selector::parse(".ranking-page-table").unwrap();
-        let ranking_page_table = html
-            .select(&ranking_page_table)
-            .next()
-            .ok_or_else(|| format_err!("No class 'ranking-page-table' found in response"))?;
-        let tbody = Selector::parse("tbody").unwrap();
-        let tbody = ranking_page_table
-            .select(&tbody)
-            .next()
-            .ok_or_else(|| format_err!("No 'tbody' element found in response"))?;
-        let child = tbody
-            .children()
-            .enumerate()
-            .filter(|(i, _)| i % 2 == 1) // Filter the empty lines
-            .map(|(_, child)| child)
-            .nth(if rank % 50 == 0 { 49 } else { (rank % 50) - 1 })
-            .unwrap();
-        let node = child
-            .children()
-            .nth(3)
-            .ok_or_else(|| format_err!("Unwraping 1: Could not find fourth child"))?
-            .children()
-            .nth(1)
-            .ok_or_else(|| format_err!("Unwraping 2: Could not find second child"))?
-            .children()
-            .nth(3)
-            .ok_or_else(|| format_err!("Unwraping 3: Could not find fourth child"))?;
-        match node.value() {
-            Node::Element(e) => {
-                if let Some(id) = e.attr("data-user-id") {
-                    Ok(id.parse::<u32>().unwrap())
-                } else {
-                    bail!("Could not find attribute 'data-user-id'")
-                }
-            }
-            _ => bail!("Did not reach Element node"),
-        }
+        parse_rankings(&body)
     }
 }
 
@@ -0,0 +1,124 @@
+use failure::Error;
+use scraper::{ElementRef, Html, Selector};
+
+/// Single row of a performance ranking page
+#[derive(Clone, Debug, PartialEq)]
+pub struct RankingEntry {
+    pub rank: usize,
+    pub user_id: u32,
+    pub username: String,
+    pub country_code: String,
+    pub pp: f32,
+    pub accuracy: f32,
+    pub playcount: u32,
+}
+
+/// Parse all rows of an osu-web performance ranking page.
+///
+/// Columns are looked up through their heading instead of their position
+/// so that added or reordered columns don't break the parsing.
+pub fn parse_rankings(body: &str) -> Result<Vec<RankingEntry>, Error> {
+    let html = Html::parse_document(body);
+    let table_selector = Selector::parse(".ranking-page-table").unwrap();
+    let table = html
+        .select(&table_selector)
+        .next()
+        .ok_or_else(|| format_err!("No class 'ranking-page-table' found in response"))?;
+    let heading_selector = Selector::parse("thead th").unwrap();
+    let headings: Vec<_> = table
+        .select(&heading_selector)
+        .map(|heading| text(heading).to_lowercase())
+        .collect();
+    let column = |name: &str| {
+        headings
+            .iter()
+            .position(|heading| heading == name)
+            .ok_or_else(|| format_err!("No column '{}' in rankings table", name))
+    };
+    let columns = Columns {
+        accuracy: column("accuracy")?,
+        playcount: column("play count")?,
+        pp: column("performance")?,
+    };
+    let row_selector = Selector::parse("tbody tr").unwrap();
+    table
+        .select(&row_selector)
+        .enumerate()
+        .map(|(i, row)| parse_row(row, &columns).map_err(|why| format_err!("Row {}: {}", i, why)))
+        .collect()
+}
+
+struct Columns {
+    accuracy: usize,
+    playcount: usize,
+    pp: usize,
+}
+
+fn parse_row(row: ElementRef, columns: &Columns) -> Result<RankingEntry, Error> {
+    let cell_selector = Selector::parse("td").unwrap();
+    let cells: Vec<_> = row.select(&cell_selector).collect();
+    let cell = |idx: usize| {
+        cells
+            .get(idx)
+            .map(|cell| text(*cell))
+            .ok_or_else(|| format_err!("Missing cell {}", idx))
+    };
+    let rank_selector = Selector::parse(".ranking-page-table__column--rank").unwrap();
+    let rank = row
+        .select(&rank_selector)
+        .next()
+        .map(text)
+        .ok_or_else(|| format_err!("No rank column"))?;
+    let user_selector = Selector::parse("[data-user-id]").unwrap();
+    let user = row
+        .select(&user_selector)
+        .next()
+        .ok_or_else(|| format_err!("No element with 'data-user-id'"))?;
+    let user_id = user.value().attr("data-user-id").unwrap().parse()?;
+    let country_code = country_code(row).ok_or_else(|| format_err!("No country found"))?;
+    Ok(RankingEntry {
+        rank: number(&rank)?,
+        user_id,
+        username: text(user),
+        country_code,
+        pp: number(&cell(columns.pp)?)?,
+        accuracy: number(&cell(columns.accuracy)?)?,
+        playcount: number(&cell(columns.playcount)?)?,
+    })
+}
+
+// Either from the link to the country's rankings or from the flag image
+fn country_code(row: ElementRef) -> Option<String> {
+    let link_selector = Selector::parse("a[href*=\"country=\"]").unwrap();
+    let from_link = row.select(&link_selector).next().and_then(|link| {
+        let href = link.value().attr("href")?;
+        let start = href.find("country=")? + 8;
+        Some(
+            href[start..]
+                .chars()
+                .take_while(char::is_ascii_alphabetic)
+                .collect::<String>(),
+        )
+    });
+    from_link.or_else(|| {
+        let flag_selector = Selector::parse(".flag-country").unwrap();
+        let style = row.select(&flag_selector).next()?.value().attr("style")?;
+        let end = style.find(".png")?;
+        let start = style[..end].rfind('/')? + 1;
+        Some(style[start..end].to_owned())
+    })
+}
+
+fn text(element: ElementRef) -> String {
+    element.text().collect::<String>().trim().to_owned()
+}
+
+fn number<T: std::str::FromStr>(s: &str) -> Result<T, Error> {
+    let cleaned: String = s
+        .chars()
+        .filter(|&c| c.is_ascii_digit() || c == '.')
+        .collect();
+    cleaned
+        .parse()
+        .map_err(|_| format_err!("Could not parse number from '{}'", s))
+}
@@ -0,0 +1,357 @@
+use super::models::EventSubMessage;
+
+use chrono::{DateTime, Duration, Utc};
+use failure::Error;
+use hmac::{Hmac, Mac, NewMac};
+use hyper::{
+    body::HttpBody,
+    header::CONTENT_LENGTH,
+    service::{make_service_fn, service_fn},
+    Body, HeaderMap, Method, Request, Response, Server, StatusCode,
+};
+use sha2::Sha256;
+use std::{
+    collections::{HashSet, VecDeque},
+    net::SocketAddr,
+    sync::{Arc, Mutex},
+};
+use tokio::sync::mpsc::UnboundedSender;
+
+const MESSAGE_ID: &str = "Twitch-Eventsub-Message-Id";
+const MESSAGE_TIMESTAMP: &str = "Twitch-Eventsub-Message-Timestamp";
+const MESSAGE_SIGNATURE: &str = "Twitch-Eventsub-Message-Signature";
+const MESSAGE_TYPE: &str = "Twitch-Eventsub-Message-Type";
+
+/// Largest body that is accepted, actual messages are much smaller
+const MAX_BODY_SIZE: usize = 64 * 1024;
+/// How many message ids are remembered to ignore retries
+const RECENT_IDS: usize = 1000;
+
+/// Where twitch should send the events to and the secret it signs them with
+pub struct EventSubConfig {
+    /// Public https url that is forwarded to the address of `serve_eventsub`
+    pub callback: String,
+    pub secret: String,
+}
+
+/// Twitch user id whose stream changed
+#[derive(Debug, PartialEq)]
+pub enum StreamEvent {
+    Online(String),
+    Offline(String),
+}
+
+/// Ids of the latest messages, twitch resends messages it got no response for
+#[derive(Default)]
+struct RecentIds {
+    ids: HashSet<String>,
+    order: VecDeque<String>,
+}
+
+impl RecentIds {
+    /// Remember the id, returns `false` if it was already known
+    fn insert(&mut self, id: &str) -> bool {
+        if self.ids.contains(id) {
+            return false;
+        }
+        if self.order.len() == RECENT_IDS {
+            if let Some(oldest) = self.order.pop_front() {
+                self.ids.remove(&oldest);
+            }
+        }
+        self.ids.insert(id.to_owned());
+        self.order.push_back(id.to_owned());
+        true
+    }
+}
+
+/// Start the webhook server that receives EventSub callbacks,
+/// returns the address it is bound to
+pub async fn serve_eventsub(
+    addr: SocketAddr,
+    secret: String,
+    sender: UnboundedSender<StreamEvent>,
+) -> Result<SocketAddr, Error> {
+    let secret = Arc::new(secret);
+    let recent_ids = Arc::new(Mutex::new(RecentIds::default()));
+    let make_service = make_service_fn(move |_| {
+        let secret = Arc::clone(&secret);
+        let recent_ids = Arc::clone(&recent_ids);
+        let sender = sender.clone();
+        async move {
+            Ok::<_, hyper::Error>(service_fn(move |req| {
+                handle(
+                    req,
+                    Arc::clone(&secret),
+                    Arc::clone(&recent_ids),
+                    sender.clone(),
+                )
+            }))
+        }
+    });
+    let server = Server::try_bind(&addr)?.serve(make_service);
+    let addr = server.local_addr();
+    tokio::spawn(async move {
+        if let Err(why) = server.await {
+            error!("EventSub server stopped: {}", why);
+        }
+    });
+    Ok(addr)
+}
+
+async fn handle(
+    req: Request<Body>,
+    secret: Arc<String>,
+    recent_ids: Arc<Mutex<RecentIds>>,
+    sender: UnboundedSender<StreamEvent>,
+) -> Result<Response<Body>, hyper::Error> {
+    if req.method() != Method::POST {
+        return Ok(respond(StatusCode::METHOD_NOT_ALLOWED, Body::empty()));
+    }
+    let headers = req.headers().clone();
+    let too_large = header_str(&headers, CONTENT_LENGTH.as_str())
+        .and_then(|len| len.parse::<usize>().ok())
+        .map_or(false, |len| len > MAX_BODY_SIZE);
+    if too_large {
+        return Ok(respond(StatusCode::PAYLOAD_TOO_LARGE, Body::empty()));
+    }
+    // The length header is optional so the size is checked while reading too
+    let mut req_body = req.into_body();
+    let mut body = Vec::new();
+    while let Some(chunk) = req_body.data().await {
+        let chunk = chunk?;
+        if body.len() + chunk.len() > MAX_BODY_SIZE {
+            return Ok(respond(StatusCode::PAYLOAD_TOO_LARGE, Body::empty()));
+        }
+        body.extend_from_slice(&chunk);
+    }
+    let header = |name| header_str(&headers, name);
+    let (id, timestamp, signature) = match (
+        header(MESSAGE_ID),
+        header(MESSAGE_TIMESTAMP),
+        header(MESSAGE_SIGNATURE),
+    ) {
+        (Some(id), Some(timestamp), Some(signature)) => (id, timestamp, signature),
+        _ => return Ok(respond(StatusCode::BAD_REQUEST, Body::empty())),
+    };
+    if !verify_signature(&secret, id, timestamp, &body, signature) {
+        warn!("Received EventSub message with invalid signature");
+        return Ok(respond(StatusCode::FORBIDDEN, Body::empty()));
+    }
+    // Prevent replays of old messages
+    if !is_recent(timestamp, Utc::now()) {
+        return Ok(respond(StatusCode::FORBIDDEN, Body::empty()));
+    }
+    let message: EventSubMessage = match serde_json::from_slice(&body) {
+        Ok(message) => message,
+        Err(why) => {
+            warn!("Could not deserialize EventSub message: {}", why);
+            return Ok(respond(StatusCode::BAD_REQUEST, Body::empty()));
+        }
+    };
+    match header(MESSAGE_TYPE) {
+        Some("webhook_callback_verification") => match message.challenge {
+            Some(challenge) => {
+                debug!(
+                    "Verified EventSub subscription {}",
+                    message.subscription.kind
+                );
+                Ok(respond(StatusCode::OK, Body::from(challenge)))
+            }
+            None => Ok(respond(StatusCode::BAD_REQUEST, Body::empty())),
+        },
+        Some("notification") => {
+            let user_id = match message.event {
+                Some(event) => event.broadcaster_user_id,
+                None => return Ok(respond(StatusCode::BAD_REQUEST, Body::empty())),
+            };
+            let event = match message.subscription.kind.as_str() {
+                "stream.online" => StreamEvent::Online(user_id),
+                "stream.offline" => StreamEvent::Offline(user_id),
+                other => {
+                    debug!("Ignoring EventSub notification of type {}", other);
+                    return Ok(respond(StatusCode::NO_CONTENT, Body::empty()));
+                }
+            };
+            // Retries of messages that were already handled only need a response
+            if !recent_ids.lock().unwrap().insert(id) {
+                debug!("Ignoring retried EventSub message {}", id);
+                return Ok(respond(StatusCode::NO_CONTENT, Body::empty()));
+            }
+            if sender.send(event).is_err() {
+                warn!("EventSub notification receiver is gone");
+            }
+            Ok(respond(StatusCode::NO_CONTENT, Body::empty()))
+        }
+        Some("revocation") => {
+            warn!(
+                "Twitch revoked EventSub subscription {}: {}",
+                message.subscription.kind, message.subscription.status
+            );
+            Ok(respond(StatusCode::NO_CONTENT, Body::empty()))
+        }
+        _ => Ok(respond(StatusCode::BAD_REQUEST, Body::empty())),
+    }
+}
+
+/// Whether the timestamp is within ten minutes of now, in either direction
+fn is_recent(timestamp: &str, now: DateTime<Utc>) -> bool {
+    DateTime::parse_from_rfc3339(timestamp).map_or(false, |date| {
+        let diff = now - date.with_timezone(&Utc);
+        diff < Duration::minutes(10) && diff > Duration::minutes(-10)
+    })
+}
+
+fn header_str<'h>(headers: &'h HeaderMap, name: &str) -> Option<&'h str> {
+    headers.get(name).and_then(|value| value.to_str().ok())
+}
+
+fn respond(status: StatusCode, body: Body) -> Response<Body> {
+    let mut response = Response::new(body);
+    *response.status_mut() = status;
+    response
+}
+
+fn mac(secret: &str, id: &str, timestamp: &str, body: &[u8]) -> Hmac<Sha256> {
+    let mut mac = Hmac::<Sha256>::new_varkey(secret.as_bytes()).unwrap();
+    mac.update(id.as_bytes());
+    mac.update(timestamp.as_bytes());
+    mac.update(body);
+    mac
+}
+
+/// Signature header twitch sends along a message
+#[cfg(test)]
+fn sign(secret: &str, id: &str, timestamp: &str, body: &[u8]) -> String {
+    let code = mac(secret, id, timestamp, body).finalize().into_bytes();
+    format!("sha256={}", hex::encode(code))
+}
+
+fn verify_signature(secret: &str, id: &str, timestamp: &str, body: &[u8], signature: &str) -> bool {
+    signature
+        .strip_prefix("sha256=")
+        .and_then(|code| hex::decode(code).ok())
+        .map_or(false, |code| {
+            mac(secret, id, timestamp, body).verify(&code).is_ok()
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use reqwest::Client;
+    use serde_json::json;
+    use tokio::sync::mpsc;
+
+    const SECRET: &str = "s3cr3t";
+
+    #[test]
+    fn signatures() {
+        let ts = "2020-08-20T12:00:00Z";
+        let signature = sign(SECRET, "id", ts, b"body");
+        assert!(verify_signature(SECRET, "id", ts, b"body", &signature));
+        assert!(!verify_signature(SECRET, "id", ts, b"bodY", &signature));
+        assert!(!verify_signature("other", "id", ts, b"body", &signature));
+        assert!(!verify_signature(SECRET, "id", ts, b"body", "sha256=zz"));
+    }
+
+    #[test]
+    fn timestamps() {
+        let now = Utc::now();
+        let at = |diff| (now + Duration::minutes(diff)).to_rfc3339();
+        assert!(is_recent(&at(-9), now));
+        assert!(is_recent(&at(9), now));
+        assert!(!is_recent(&at(-11), now));
+        assert!(!is_recent(&at(11), now));
+        assert!(!is_recent("yesterday", now));
+    }
+
+    #[test]
+    fn bounded_recent_ids() {
+        let mut recent = RecentIds::default();
+        assert!(recent.insert("0"));
+        assert!(!recent.insert("0"));
+        for i in 1..=RECENT_IDS {
+            assert!(recent.insert(&i.to_string()));
+        }
+        // The oldest id was dropped
+        assert!(recent.insert("0"));
+        assert_eq!(recent.order.len(), RECENT_IDS);
+    }
+
+    async fn post(
+        addr: SocketAddr,
+        id: &str,
+        kind: &str,
+        body: &serde_json::Value,
+        secret: &str,
+    ) -> reqwest::Response {
+        let body = body.to_string();
+        let timestamp = Utc::now().to_rfc3339();
+        Client::new()
+            .post(&format!("http://{}/", addr))
+            .header(MESSAGE_ID, id)
+            .header(MESSAGE_TIMESTAMP, timestamp.as_str())
+            .header(
+                MESSAGE_SIGNATURE,
+                sign(secret, id, &timestamp, body.as_bytes()),
+            )
+            .header(MESSAGE_TYPE, kind)
+            .body(body)
+            .send()
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn webhook_round_trip() {
+        let (sender, mut receiver) = mpsc::unbounded_channel();
+        let addr = serve_eventsub(([127, 0, 0, 1], 0).into(), SECRET.to_owned(), sender)
+            .await
+            .unwrap();
+        let subscription = json!({ "type": "stream.online", "status": "enabled" });
+
+        let challenge = json!({ "subscription": subscription, "challenge": "pogchamp" });
+        let response = post(
+            addr,
+            "0",
+            "webhook_callback_verification",
+            &challenge,
+            SECRET,
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.text().await.unwrap(), "pogchamp");
+
+        let notification = json!({
+            "subscription": subscription,
+            "event": { "broadcaster_user_id": "1337" },
+        });
+        let response = post(addr, "1", "notification", &notification, "wrong").await;
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+        let response = post(addr, "1", "notification", &notification, SECRET).await;
+        assert_eq!(response.status(), StatusCode::NO_CONTENT);
+        assert_eq!(
+            receiver.recv().await,
+            Some(StreamEvent::Online("1337".to_owned()))
+        );
+
+        // Retries are acknowledged but not forwarded
+        let response = post(addr, "1", "notification", &notification, SECRET).await;
+        assert_eq!(response.status(), StatusCode::NO_CONTENT);
+        let offline = json!({
+            "subscription": { "type": "stream.offline", "status": "enabled" },
+            "event": { "broadcaster_user_id": "1337" },
+        });
+        post(addr, "2", "notification", &offline, SECRET).await;
+        assert_eq!(
+            receiver.recv().await,
+            Some(StreamEvent::Offline("1337".to_owned()))
+        );
+
+        let huge = json!({ "subscription": subscription, "challenge": "a".repeat(MAX_BODY_SIZE) });
+        let response = post(addr, "3", "webhook_callback_verification", &huge, SECRET).await;
+        assert_eq!(response.status(), StatusCode::PAYLOAD_TOO_LARGE);
+    }
+}
@@ -1,5 +1,134 @@
+mod eventsub;
 mod models;
+mod template;
 mod twitch;
+mod youtube;
 
+pub use eventsub::{serve_eventsub, EventSubConfig, StreamEvent};
 pub use models::{TwitchStream, TwitchUser};
+pub use template::{render_template, truncate_message, validate_template};
 pub use twitch::*;
+pub use youtube::YouTube;
+
+use chrono::{DateTime, Utc};
+use failure::Error;
+use serenity::async_trait;
+use std::{fmt, str::FromStr};
+
+/// Platforms whose streams can be tracked, the value is stored in the `platform` columns
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Platform {
+    Twitch = 0,
+    YouTube = 1,
+}
+
+impl Platform {
+    pub fn from_u8(platform: u8) -> Self {
+        match platform {
+            1 => Platform::YouTube,
+            _ => Platform::Twitch,
+        }
+    }
+}
+
+impl FromStr for Platform {
+    type Err = ();
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "twitch" | "ttv" | "t" => Ok(Platform::Twitch),
+            "youtube" | "yt" | "y" => Ok(Platform::YouTube),
+            _ => Err(()),
+        }
+    }
+}
+
+impl fmt::Display for Platform {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Platform::Twitch => f.write_str("twitch"),
+            Platform::YouTube => f.write_str("youtube"),
+        }
+    }
+}
+
+/// A channel on some platform, `user_id` is whatever the platform identifies it by
+#[derive(Clone, Debug)]
+pub struct StreamUser {
+    pub user_id: String,
+    pub name: String,
+    pub image_url: String,
+}
+
+/// Metadata of a stream that is currently live
+#[derive(Clone, Debug)]
+pub struct Stream {
+    pub platform: Platform,
+    pub user_id: String,
+    pub username: String,
+    pub title: String,
+    pub url: String,
+    pub thumbnail_url: String,
+    pub viewer_count: u32,
+    pub started_at: Option<DateTime<Utc>>,
+    pub language: Option<String>,
+    pub game: Option<String>,
+}
+
+/// A stream that was live during the last check and the notifications posted for it
+#[derive(Clone, Debug)]
+pub struct LiveStream {
+    pub stream: Stream,
+    pub image_url: String,
+    pub started_at: DateTime<Utc>,
+    pub peak_viewers: u32,
+    /// Channel and message id of each notification
+    pub notifications: Vec<(u64, u64)>,
+}
+
+impl LiveStream {
+    pub fn new(stream: Stream, image_url: String) -> Self {
+        Self {
+            started_at: stream.started_at.unwrap_or_else(Utc::now),
+            peak_viewers: stream.viewer_count,
+            stream,
+            image_url,
+            notifications: Vec::new(),
+        }
+    }
+
+    /// Replace the metadata with that of a more recent check
+    pub fn update(&mut self, stream: Stream) {
+        self.peak_viewers = self.peak_viewers.max(stream.viewer_count);
+        self.stream = stream;
+    }
+}
+
+/// Everything the stream tracking needs from a platform
+#[async_trait]
+pub trait StreamPlatform: Send + Sync {
+    fn platform(&self) -> Platform;
+
+    /// Resolve a channel name to its user
+    async fn get_user(&self, name: &str) -> Result<StreamUser, Error>;
+
+    /// Users of the given ids, used for their profile images
+    async fn get_users(&self, user_ids: &[String]) -> Result<Vec<StreamUser>, Error>;
+
+    /// All streams of the given users that are currently live
+    async fn get_live_streams(&self, user_ids: &[String]) -> Result<Vec<Stream>, Error>;
+
+    /// Live streams of the osu! category, optionally only of the given language
+    async fn get_osu_streams(&self, _language: Option<&str>) -> Result<Vec<Stream>, Error> {
+        Ok(Vec::new())
+    }
+
+    /// Register for events of the user, platforms without events are only polled
+    async fn subscribe(&self, _user_id: &str) -> Result<(), Error> {
+        Ok(())
+    }
+
+    /// Stop the events of the user once nothing tracks it anymore
+    async fn unsubscribe(&self, _user_id: &str) -> Result<(), Error> {
+        Ok(())
+    }
+}
@@ -1,6 +1,8 @@
 mod twitch;
+mod youtube;
 
 pub use twitch::*;
+pub use youtube::*;
 
 use serde::{de, Deserialize, Deserializer};
 use std::str::FromStr;
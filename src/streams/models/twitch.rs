@@ -31,6 +31,9 @@ pub struct TwitchStream {
     pub username: String,
     #[serde(rename = "type", deserialize_with = "get_live")]
     pub live: bool,
+    pub viewer_count: u32,
+    pub started_at: String,
+    pub language: String,
 }
 
 impl TwitchStream {
@@ -50,4 +53,62 @@ where
 #[derive(DeserializeDerive)]
 pub struct TwitchStreams {
     pub data: Vec<TwitchStream>,
+    #[serde(default)]
+    pub pagination: TwitchPagination,
+}
+
+#[derive(DeserializeDerive, Default)]
+pub struct TwitchPagination {
+    pub cursor: Option<String>,
+}
+
+#[derive(DeserializeDerive)]
+pub struct TwitchGame {
+    #[serde(rename = "id", deserialize_with = "str_to_u64")]
+    pub game_id: u64,
+    pub name: String,
+}
+
+#[derive(DeserializeDerive)]
+pub struct TwitchGames {
+    pub data: Vec<TwitchGame>,
+}
+
+/// Existing EventSub subscriptions of the client
+#[derive(DeserializeDerive)]
+pub struct TwitchSubscriptions {
+    pub data: Vec<TwitchSubscription>,
+    #[serde(default)]
+    pub pagination: TwitchPagination,
+}
+
+#[derive(DeserializeDerive)]
+pub struct TwitchSubscription {
+    pub id: String,
+    pub condition: TwitchSubscriptionCondition,
+}
+
+#[derive(DeserializeDerive)]
+pub struct TwitchSubscriptionCondition {
+    pub broadcaster_user_id: Option<String>,
+}
+
+/// Body of any request twitch sends to the EventSub webhook
+#[derive(DeserializeDerive)]
+pub struct EventSubMessage {
+    pub subscription: EventSubSubscription,
+    pub challenge: Option<String>,
+    pub event: Option<EventSubEvent>,
+}
+
+#[derive(DeserializeDerive)]
+pub struct EventSubSubscription {
+    #[serde(rename = "type")]
+    pub kind: String,
+    pub status: String,
+}
+
+#[derive(DeserializeDerive)]
+pub struct EventSubEvent {
+    pub broadcaster_user_id: String,
 }
@@ -0,0 +1,82 @@
+use super::str_to_u64;
+use serde_derive::Deserialize;
+
+#[derive(Deserialize)]
+pub struct YouTubeList<T> {
+    #[serde(default = "Vec::new")]
+    pub items: Vec<T>,
+}
+
+#[derive(Deserialize)]
+pub struct YouTubeThumbnail {
+    pub url: String,
+}
+
+#[derive(Deserialize)]
+pub struct YouTubeThumbnails {
+    pub default: Option<YouTubeThumbnail>,
+    pub high: Option<YouTubeThumbnail>,
+}
+
+impl YouTubeThumbnails {
+    pub fn best(self) -> String {
+        self.high
+            .or(self.default)
+            .map(|thumbnail| thumbnail.url)
+            .unwrap_or_default()
+    }
+}
+
+#[derive(Deserialize)]
+pub struct YouTubeChannelSnippet {
+    pub title: String,
+    pub thumbnails: YouTubeThumbnails,
+}
+
+#[derive(Deserialize)]
+pub struct YouTubeChannel {
+    pub id: String,
+    pub snippet: YouTubeChannelSnippet,
+}
+
+#[derive(Deserialize)]
+pub struct YouTubePlaylistItemDetails {
+    #[serde(rename = "videoId")]
+    pub video_id: String,
+}
+
+#[derive(Deserialize)]
+pub struct YouTubePlaylistItem {
+    #[serde(rename = "contentDetails")]
+    pub content_details: YouTubePlaylistItemDetails,
+}
+
+#[derive(Deserialize)]
+pub struct YouTubeVideoSnippet {
+    #[serde(rename = "channelId")]
+    pub channel_id: String,
+    #[serde(rename = "channelTitle")]
+    pub channel_title: String,
+    pub title: String,
+    pub thumbnails: YouTubeThumbnails,
+    #[serde(rename = "defaultAudioLanguage")]
+    pub language: Option<String>,
+}
+
+#[derive(Deserialize)]
+pub struct YouTubeLiveDetails {
+    #[serde(rename = "actualStartTime")]
+    pub start_time: Option<String>,
+    #[serde(rename = "actualEndTime")]
+    pub end_time: Option<String>,
+    #[serde(rename = "concurrentViewers", default, deserialize_with = "str_to_u64")]
+    pub viewers: u64,
+}
+
+#[derive(Deserialize)]
+pub struct YouTubeVideo {
+    pub id: String,
+    pub snippet: YouTubeVideoSnippet,
+    #[serde(rename = "liveStreamingDetails")]
+    pub live_details: Option<YouTubeLiveDetails>,
+}
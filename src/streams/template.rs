@@ -0,0 +1,133 @@
+use super::{Platform, Stream};
+
+use std::collections::HashMap;
+use strfmt::strfmt;
+
+pub const TEMPLATE_MAX_LEN: usize = 1000;
+/// Discord's limit for the content of a message
+pub const MESSAGE_MAX_LEN: usize = 2000;
+/// Role mention in front of the rendered template, e.g. `<@&123> `
+const ROLE_MENTION_MAX_LEN: usize = 24;
+
+/// Fill in the placeholders `{name}`, `{title}`, `{game}`, `{url}`, and `{viewers}`
+pub fn render_template(template: &str, stream: &Stream) -> Result<String, strfmt::FmtError> {
+    let mut vars = HashMap::with_capacity(5);
+    vars.insert(String::from("name"), stream.username.clone());
+    vars.insert(String::from("title"), stream.title.clone());
+    let game = stream.game.clone().unwrap_or_else(|| String::from("-"));
+    vars.insert(String::from("game"), game);
+    vars.insert(String::from("url"), stream.url.clone());
+    vars.insert(String::from("viewers"), stream.viewer_count.to_string());
+    strfmt(template, &vars)
+}
+
+/// Cut the content to the message limit, templates stored earlier might render longer
+pub fn truncate_message(content: &mut String) {
+    if let Some((idx, _)) = content.char_indices().nth(MESSAGE_MAX_LEN) {
+        let end = content[..idx].char_indices().last().map_or(0, |(i, _)| i);
+        content.truncate(end);
+        content.push('…');
+    }
+}
+
+/// Check a template before storing it by rendering it for the longest possible stream
+pub fn validate_template(template: &str) -> Result<(), String> {
+    if template.chars().count() > TEMPLATE_MAX_LEN {
+        return Err(format!(
+            "The template must not be longer than {} characters",
+            TEMPLATE_MAX_LEN
+        ));
+    }
+    // Format specs like `{name:>999999}` would blow up every notification
+    if let Some(spec) = format_spec(template) {
+        return Err(format!(
+            "Invalid placeholder `{}`, placeholders can't have a format",
+            spec
+        ));
+    }
+    // Longest values the platforms allow
+    let example = Stream {
+        platform: Platform::Twitch,
+        user_id: String::from("0"),
+        username: "n".repeat(100),
+        title: "t".repeat(140),
+        url: "u".repeat(100),
+        thumbnail_url: String::new(),
+        viewer_count: u32::MAX,
+        started_at: None,
+        language: None,
+        game: Some("g".repeat(100)),
+    };
+    match render_template(template, &example) {
+        Ok(content) if content.chars().count() + ROLE_MENTION_MAX_LEN > MESSAGE_MAX_LEN => {
+            Err(format!(
+                "The template could render to more than {} characters for long \
+                stream titles or names, use fewer placeholders",
+                MESSAGE_MAX_LEN
+            ))
+        }
+        Ok(_) => Ok(()),
+        Err(why) => Err(format!(
+            "Invalid template: {}\nAvailable placeholders are \
+            `{{name}}`, `{{title}}`, `{{game}}`, `{{url}}`, and `{{viewers}}`",
+            why
+        )),
+    }
+}
+
+/// First placeholder that contains more than just the key
+fn format_spec(template: &str) -> Option<&str> {
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+        rest = &rest[start..];
+        // Escaped brace
+        if rest.starts_with("{{") {
+            rest = &rest[2..];
+            continue;
+        }
+        let end = rest.find('}')?;
+        let placeholder = &rest[..=end];
+        if placeholder[1..end].contains(|c: char| !c.is_ascii_alphanumeric() && c != '_') {
+            return Some(placeholder);
+        }
+        rest = &rest[end + 1..];
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn placeholders() {
+        assert!(validate_template("{name} is live playing {game}: {url}").is_ok());
+        assert!(validate_template("{viewers} watching {title}").is_ok());
+        assert!(validate_template("{streamer} is live").is_err());
+        assert!(validate_template("{name is live").is_err());
+    }
+
+    #[test]
+    fn format_specs() {
+        assert!(validate_template("{name:>999999999} is live").is_err());
+        assert!(validate_template("{viewers:.5} watching").is_err());
+        assert!(validate_template("{{name}} is {name}").is_ok());
+    }
+
+    #[test]
+    fn rendered_length() {
+        assert!(validate_template(&"{title}".repeat(13)).is_ok());
+        assert!(validate_template(&"{title}".repeat(140)).is_err());
+    }
+
+    #[test]
+    fn truncated_to_message_limit() {
+        let mut content = "ä".repeat(MESSAGE_MAX_LEN + 10);
+        truncate_message(&mut content);
+        assert_eq!(content.chars().count(), MESSAGE_MAX_LEN);
+        assert!(content.ends_with('…'));
+        let mut content = "a".repeat(MESSAGE_MAX_LEN);
+        truncate_message(&mut content);
+        assert_eq!(content.len(), MESSAGE_MAX_LEN);
+    }
+}
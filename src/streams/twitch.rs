@@ -1,6 +1,18 @@
-use super::models::{TwitchStream, TwitchStreams, TwitchUser, TwitchUsers};
-use crate::util::globals::{TWITCH_STREAM_ENDPOINT, TWITCH_USERS_ENDPOINT};
+use super::{
+    models::{
+        TwitchGames, TwitchStream, TwitchStreams, TwitchSubscriptions, TwitchUser, TwitchUsers,
+    },
+    EventSubConfig, Platform, Stream, StreamPlatform, StreamUser,
+};
+use crate::util::{
+    globals::{
+        TWITCH_BASE, TWITCH_EVENTSUB_ENDPOINT, TWITCH_GAMES_ENDPOINT, TWITCH_OAUTH_TOKEN,
+        TWITCH_STREAM_ENDPOINT, TWITCH_USERS_ENDPOINT,
+    },
+    oauth::ClientToken,
+};
 
+use chrono::{DateTime, Utc};
 use failure::Error;
 use governor::{
     clock::DefaultClock,
@@ -9,56 +21,85 @@ use governor::{
 };
 use rayon::prelude::*;
 use reqwest::{
-    header::{HeaderMap, HeaderName, HeaderValue},
-    Client, Response,
+    header::{HeaderMap, HeaderName, HeaderValue, CONTENT_TYPE},
+    Client, RequestBuilder, Response, StatusCode,
 };
 use serde::Serialize;
-use serde_derive::Deserialize;
-use std::{convert::TryFrom, fmt, num::NonZeroU32};
+use serde_json::json;
+use serenity::async_trait;
+use std::{collections::HashMap, convert::TryFrom, num::NonZeroU32};
+use strfmt::strfmt;
+
+const TWITCH_OSU_GAME_ID: u64 = 21465;
+const OSU_STREAM_PAGES: usize = 5;
 
 pub struct Twitch {
     client: Client,
-    auth_token: OAuthToken,
+    token: ClientToken,
+    eventsub: Option<EventSubConfig>,
     ratelimiter: RateLimiter<NotKeyed, InMemoryState, DefaultClock>,
 }
 
 impl Twitch {
-    pub async fn new(client_id: &str, token: &str) -> Result<Self, Error> {
+    pub async fn new(client_id: &str, client_secret: &str) -> Result<Self, Error> {
         let mut headers = HeaderMap::new();
         let client_id_header = HeaderName::try_from("Client-ID").unwrap();
         headers.insert(client_id_header, HeaderValue::from_str(client_id)?);
         let client = Client::builder().default_headers(headers).build()?;
-        let auth_response = client
-            .post("https://id.twitch.tv/oauth2/token")
-            .form(&[
-                ("grant_type", "client_credentials"),
-                ("client_id", client_id),
-                ("client_secret", token),
-            ])
-            .send()
-            .await?;
-        let auth_token = serde_json::from_slice(&auth_response.bytes().await?)?;
+        let token = ClientToken::new(
+            &client,
+            "twitch",
+            TWITCH_OAUTH_TOKEN,
+            client_id.to_owned(),
+            client_secret.to_owned(),
+            None,
+        )
+        .await?;
         let quota = Quota::per_second(NonZeroU32::new(5).unwrap());
         let ratelimiter = RateLimiter::direct(quota);
         Ok(Self {
             client,
-            auth_token,
+            token,
+            eventsub: None,
             ratelimiter,
         })
     }
 
+    /// Receive stream events through the given webhook instead of only polling
+    pub fn with_eventsub(mut self, config: EventSubConfig) -> Self {
+        self.eventsub = Some(config);
+        self
+    }
+
+    async fn send<F>(&self, request: F) -> Result<Response, Error>
+    where
+        F: Fn() -> RequestBuilder,
+    {
+        let mut retried = false;
+        loop {
+            let token = self.token.get(&self.client).await?;
+            self.ratelimiter.until_ready().await;
+            let response = request().bearer_auth(&token).send().await?;
+            // The token might have been revoked early
+            if response.status() == StatusCode::UNAUTHORIZED && !retried {
+                retried = true;
+                self.token.renew(&self.client, &token).await?;
+                continue;
+            }
+            return Ok(response);
+        }
+    }
+
     async fn send_request<T: Serialize + ?Sized>(
         &self,
         endpoint: &str,
         data: &T,
-    ) -> Result<Response, reqwest::Error> {
-        self.ratelimiter.until_ready().await;
-        self.client
-            .get(endpoint)
-            .bearer_auth(&self.auth_token)
-            .query(data)
-            .send()
-            .await
+    ) -> Result<Response, Error> {
+        let response = self.send(|| self.client.get(endpoint).query(data)).await?;
+        match response.error_for_status() {
+            Ok(response) => Ok(response),
+            Err(why) => bail!("Twitch API got bad response: {}", why),
+        }
     }
 
     pub async fn get_user(&self, name: &str) -> Result<TwitchUser, Error> {
@@ -92,22 +133,214 @@ impl Twitch {
         let mut streams = Vec::with_capacity(user_ids.len());
         for chunk in user_ids.chunks(100) {
             let mut data: Vec<_> = chunk.par_iter().map(|&id| ("user_id", id)).collect();
-            data.push(("first", user_ids.len() as u64));
+            data.push(("first", chunk.len() as u64));
             let response = self.send_request(TWITCH_STREAM_ENDPOINT, &data).await?;
             let parsed_response: TwitchStreams = serde_json::from_slice(&response.bytes().await?)?;
             streams.extend(parsed_response.data);
         }
         Ok(streams)
     }
+
+    /// Maps game ids to their name
+    pub async fn get_games(&self, game_ids: &[u64]) -> Result<HashMap<u64, String>, Error> {
+        let mut games = HashMap::with_capacity(game_ids.len());
+        for chunk in game_ids.chunks(100) {
+            let data: Vec<_> = chunk.iter().map(|&id| ("id", id)).collect();
+            let response = self.send_request(TWITCH_GAMES_ENDPOINT, &data).await?;
+            let parsed_response: TwitchGames = serde_json::from_slice(&response.bytes().await?)?;
+            games.extend(
+                parsed_response
+                    .data
+                    .into_iter()
+                    .map(|g| (g.game_id, g.name)),
+            );
+        }
+        Ok(games)
+    }
+
+    // Only keeps the streams that are live
+    async fn convert_streams(&self, mut streams: Vec<TwitchStream>) -> Vec<Stream> {
+        let mut fmt_data = HashMap::new();
+        fmt_data.insert(String::from("width"), String::from("360"));
+        fmt_data.insert(String::from("height"), String::from("180"));
+        streams.retain(TwitchStream::is_live);
+        let mut game_ids: Vec<_> = streams.iter().filter_map(|s| s.game_id).collect();
+        game_ids.sort_unstable();
+        game_ids.dedup();
+        let games = match self.get_games(&game_ids).await {
+            Ok(games) => games,
+            Err(why) => {
+                warn!("Error while retrieving twitch games: {}", why);
+                HashMap::new()
+            }
+        };
+        streams
+            .into_iter()
+            .map(|stream| {
+                let thumbnail_url =
+                    strfmt(&stream.thumbnail_url, &fmt_data).unwrap_or(stream.thumbnail_url);
+                Stream {
+                    platform: Platform::Twitch,
+                    user_id: stream.user_id.to_string(),
+                    url: format!("{}{}", TWITCH_BASE, stream.username),
+                    username: stream.username,
+                    title: stream.title,
+                    thumbnail_url,
+                    viewer_count: stream.viewer_count,
+                    started_at: DateTime::parse_from_rfc3339(&stream.started_at)
+                        .ok()
+                        .map(|date| date.with_timezone(&Utc)),
+                    language: Some(stream.language).filter(|language| !language.is_empty()),
+                    game: stream.game_id.and_then(|id| games.get(&id).cloned()),
+                }
+            })
+            .collect()
+    }
 }
 
-#[derive(Deserialize)]
-struct OAuthToken {
-    access_token: String,
+#[async_trait]
+impl StreamPlatform for Twitch {
+    fn platform(&self) -> Platform {
+        Platform::Twitch
+    }
+
+    async fn get_user(&self, name: &str) -> Result<StreamUser, Error> {
+        Twitch::get_user(self, name).await.map(StreamUser::from)
+    }
+
+    async fn get_users(&self, user_ids: &[String]) -> Result<Vec<StreamUser>, Error> {
+        let user_ids = parse_ids(user_ids);
+        let users = Twitch::get_users(self, &user_ids).await?;
+        Ok(users.into_iter().map(StreamUser::from).collect())
+    }
+
+    async fn get_live_streams(&self, user_ids: &[String]) -> Result<Vec<Stream>, Error> {
+        let user_ids = parse_ids(user_ids);
+        let streams = self.get_streams(&user_ids).await?;
+        Ok(self.convert_streams(streams).await)
+    }
+
+    async fn get_osu_streams(&self, language: Option<&str>) -> Result<Vec<Stream>, Error> {
+        let mut streams = Vec::new();
+        let mut cursor = None;
+        // Streams are sorted by viewers so the last pages are rarely of interest
+        for _ in 0..OSU_STREAM_PAGES {
+            let mut data = vec![
+                ("game_id", TWITCH_OSU_GAME_ID.to_string()),
+                ("first", "100".to_owned()),
+            ];
+            if let Some(language) = language {
+                data.push(("language", language.to_owned()));
+            }
+            if let Some(cursor) = cursor.take() {
+                data.push(("after", cursor));
+            }
+            let response = self.send_request(TWITCH_STREAM_ENDPOINT, &data).await?;
+            let parsed_response: TwitchStreams = serde_json::from_slice(&response.bytes().await?)?;
+            let last_page = parsed_response.data.len() < 100;
+            streams.extend(parsed_response.data);
+            match parsed_response.pagination.cursor {
+                Some(next) if !last_page => cursor = Some(next),
+                _ => break,
+            }
+        }
+        Ok(self.convert_streams(streams).await)
+    }
+
+    async fn subscribe(&self, user_id: &str) -> Result<(), Error> {
+        let eventsub = match self.eventsub {
+            Some(ref eventsub) => eventsub,
+            None => return Ok(()),
+        };
+        for kind in &["stream.online", "stream.offline"] {
+            let body = json!({
+                "type": kind,
+                "version": "1",
+                "condition": { "broadcaster_user_id": user_id },
+                "transport": {
+                    "method": "webhook",
+                    "callback": eventsub.callback,
+                    "secret": eventsub.secret,
+                },
+            })
+            .to_string();
+            let response = self
+                .send(|| {
+                    self.client
+                        .post(TWITCH_EVENTSUB_ENDPOINT)
+                        .header(CONTENT_TYPE, "application/json")
+                        .body(body.clone())
+                })
+                .await?;
+            // Twitch responds with a conflict if the subscription already exists
+            if response.status() == StatusCode::CONFLICT {
+                continue;
+            }
+            if let Err(why) = response.error_for_status() {
+                bail!("Could not subscribe to {} of {}: {}", kind, user_id, why);
+            }
+        }
+        Ok(())
+    }
+
+    async fn unsubscribe(&self, user_id: &str) -> Result<(), Error> {
+        if self.eventsub.is_none() {
+            return Ok(());
+        }
+        let mut subscription_ids = Vec::new();
+        let mut cursor: Option<String> = None;
+        loop {
+            let mut data = vec![("user_id", user_id.to_owned())];
+            if let Some(ref cursor) = cursor {
+                data.push(("after", cursor.to_owned()));
+            }
+            let response = self.send_request(TWITCH_EVENTSUB_ENDPOINT, &data).await?;
+            let parsed_response: TwitchSubscriptions =
+                serde_json::from_slice(&response.bytes().await?)?;
+            let last_page = parsed_response.data.is_empty();
+            subscription_ids.extend(
+                parsed_response
+                    .data
+                    .into_iter()
+                    .filter(|sub| sub.condition.broadcaster_user_id.as_deref() == Some(user_id))
+                    .map(|sub| sub.id),
+            );
+            match parsed_response.pagination.cursor {
+                Some(next) if !last_page => cursor = Some(next),
+                _ => break,
+            }
+        }
+        for id in subscription_ids {
+            let response = self
+                .send(|| {
+                    self.client
+                        .delete(TWITCH_EVENTSUB_ENDPOINT)
+                        .query(&[("id", &id)])
+                })
+                .await?;
+            // Already gone, e.g. revoked by twitch
+            if response.status() == StatusCode::NOT_FOUND {
+                continue;
+            }
+            if let Err(why) = response.error_for_status() {
+                bail!("Could not unsubscribe from {} of {}: {}", id, user_id, why);
+            }
+        }
+        Ok(())
+    }
 }
 
-impl fmt::Display for OAuthToken {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "{}", self.access_token)
+impl From<TwitchUser> for StreamUser {
+    fn from(user: TwitchUser) -> Self {
+        Self {
+            user_id: user.user_id.to_string(),
+            name: user.display_name,
+            image_url: user.image_url,
+        }
     }
 }
+
+// Twitch ids are numeric, anything else can't be a twitch user
+fn parse_ids(user_ids: &[String]) -> Vec<u64> {
+    user_ids.iter().filter_map(|id| id.parse().ok()).collect()
+}
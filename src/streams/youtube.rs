@@ -0,0 +1,174 @@
+use super::{
+    models::{YouTubeChannel, YouTubeList, YouTubePlaylistItem, YouTubeVideo},
+    Platform, Stream, StreamPlatform, StreamUser,
+};
+use crate::util::globals::{YOUTUBE_API, YOUTUBE_VIDEO_BASE};
+
+use chrono::{DateTime, Utc};
+use failure::Error;
+use governor::{
+    clock::DefaultClock,
+    state::{direct::NotKeyed, InMemoryState},
+    Quota, RateLimiter,
+};
+use reqwest::Client;
+use serde::de::DeserializeOwned;
+use serenity::async_trait;
+use std::num::NonZeroU32;
+
+/// Client for the YouTube Data API, only live streams are considered.
+/// Live streams are found through the channels' latest uploads since
+/// that costs 1 quota unit per channel whereas a search costs 100.
+pub struct YouTube {
+    client: Client,
+    api_key: String,
+    ratelimiter: RateLimiter<NotKeyed, InMemoryState, DefaultClock>,
+}
+
+impl YouTube {
+    pub fn new(api_key: String) -> Self {
+        let quota = Quota::per_second(NonZeroU32::new(5).unwrap());
+        Self {
+            client: Client::new(),
+            api_key,
+            ratelimiter: RateLimiter::direct(quota),
+        }
+    }
+
+    async fn send_request<T: DeserializeOwned>(
+        &self,
+        route: &str,
+        query: &[(&str, &str)],
+    ) -> Result<T, Error> {
+        self.ratelimiter.until_ready().await;
+        let response = self
+            .client
+            .get(&format!("{}{}", YOUTUBE_API, route))
+            .query(query)
+            .query(&[("key", &self.api_key)])
+            .send()
+            .await?;
+        let response = match response.error_for_status() {
+            Ok(response) => response,
+            Err(why) => bail!("YouTube API got bad response: {}", why),
+        };
+        Ok(serde_json::from_slice(&response.bytes().await?)?)
+    }
+
+    async fn get_channels(&self, query: &[(&str, &str)]) -> Result<Vec<YouTubeChannel>, Error> {
+        let mut query = query.to_vec();
+        query.push(("part", "snippet"));
+        let channels: YouTubeList<YouTubeChannel> = self.send_request("channels", &query).await?;
+        Ok(channels.items)
+    }
+
+    // Ids of the channel's latest videos, live streams are among them once they started
+    async fn get_latest_videos(&self, channel_id: &str) -> Result<Vec<String>, Error> {
+        // The uploads playlist id is the channel id with `UU` instead of `UC`
+        let playlist_id = format!("UU{}", channel_id.get(2..).unwrap_or_default());
+        let query = [
+            ("part", "contentDetails"),
+            ("playlistId", playlist_id.as_str()),
+            ("maxResults", "5"),
+        ];
+        let items: YouTubeList<YouTubePlaylistItem> =
+            self.send_request("playlistItems", &query).await?;
+        Ok(items
+            .items
+            .into_iter()
+            .map(|item| item.content_details.video_id)
+            .collect())
+    }
+}
+
+#[async_trait]
+impl StreamPlatform for YouTube {
+    fn platform(&self) -> Platform {
+        Platform::YouTube
+    }
+
+    /// Either a channel id or the legacy username of a channel
+    async fn get_user(&self, name: &str) -> Result<StreamUser, Error> {
+        let key = if is_channel_id(name) {
+            "id"
+        } else {
+            "forUsername"
+        };
+        match self.get_channels(&[(key, name)]).await?.pop() {
+            Some(channel) => Ok(channel.into()),
+            None => bail!("YouTube API gave no results for channel {}", name),
+        }
+    }
+
+    async fn get_users(&self, user_ids: &[String]) -> Result<Vec<StreamUser>, Error> {
+        let mut users = Vec::with_capacity(user_ids.len());
+        for chunk in user_ids.chunks(50) {
+            let ids = chunk.join(",");
+            let channels = self.get_channels(&[("id", ids.as_str())]).await?;
+            users.extend(channels.into_iter().map(StreamUser::from));
+        }
+        Ok(users)
+    }
+
+    async fn get_live_streams(&self, user_ids: &[String]) -> Result<Vec<Stream>, Error> {
+        let mut video_ids = Vec::new();
+        for channel_id in user_ids {
+            // E.g. channels without any uploads have no uploads playlist
+            match self.get_latest_videos(channel_id).await {
+                Ok(ids) => video_ids.extend(ids),
+                Err(why) => warn!(
+                    "Error while getting videos of channel {}: {}",
+                    channel_id, why
+                ),
+            }
+        }
+        let mut streams = Vec::with_capacity(video_ids.len());
+        for chunk in video_ids.chunks(50) {
+            let ids = chunk.join(",");
+            let query = [
+                ("part", "snippet,liveStreamingDetails"),
+                ("id", ids.as_str()),
+            ];
+            let videos: YouTubeList<YouTubeVideo> = self.send_request("videos", &query).await?;
+            streams.extend(videos.items.into_iter().filter_map(|video| {
+                let details = video.live_details?;
+                // Upcoming streams didn't start yet and finished ones have an end time
+                if details.start_time.is_none() || details.end_time.is_some() {
+                    return None;
+                }
+                let started_at = details
+                    .start_time
+                    .and_then(|date| DateTime::parse_from_rfc3339(&date).ok())
+                    .map(|date| date.with_timezone(&Utc));
+                Some(Stream {
+                    platform: Platform::YouTube,
+                    user_id: video.snippet.channel_id,
+                    username: video.snippet.channel_title,
+                    title: video.snippet.title,
+                    url: format!("{}{}", YOUTUBE_VIDEO_BASE, video.id),
+                    thumbnail_url: video.snippet.thumbnails.best(),
+                    viewer_count: details.viewers as u32,
+                    started_at,
+                    language: video.snippet.language,
+                    game: None,
+                })
+            }));
+        }
+        Ok(streams)
+    }
+}
+
+impl From<YouTubeChannel> for StreamUser {
+    fn from(channel: YouTubeChannel) -> Self {
+        Self {
+            user_id: channel.id,
+            name: channel.snippet.title,
+            image_url: channel.snippet.thumbnails.best(),
+        }
+    }
+}
+
+// Channel ids look like `UC` followed by 22 characters
+fn is_channel_id(name: &str) -> bool {
+    name.len() == 24 && name.starts_with("UC")
+}
@@ -1,8 +1,9 @@
 use crate::{
-    commands::fun::BackGroundGame,
-    database::{MySQL, StreamTrack},
+    commands::fun::{BackGroundGame, SongGame},
+    database::{CommandPermission, MySQL, RoleMenu, StreamTrack},
+    osu_cache::OsuCache,
     scraper::Scraper,
-    streams::Twitch,
+    streams::{LiveStream, Platform, StreamEvent, StreamPlatform},
     util::globals::AUTHORITY_ROLES,
 };
 
@@ -10,7 +11,7 @@ use chrono::{DateTime, Utc};
 use rosu::backend::Osu as OsuClient;
 use serenity::{
     framework::standard::{Args, Delimiter},
-    model::id::{ChannelId, GuildId, MessageId, RoleId, UserId},
+    model::id::{ChannelId, GuildId, MessageId, UserId},
     prelude::*,
 };
 use sqlx::{mysql::MySqlRow, FromRow, Row};
@@ -18,6 +19,7 @@ use std::{
     collections::{HashMap, HashSet},
     sync::Arc,
 };
+use tokio::sync::mpsc::UnboundedReceiver;
 
 pub struct CommandCounter;
 impl TypeMapKey for CommandCounter {
@@ -26,7 +28,11 @@ impl TypeMapKey for CommandCounter {
 
 pub struct Osu;
 impl TypeMapKey for Osu {
-    type Value = OsuClient;
+    type Value = Arc<OsuClient>;
+}
+
+impl TypeMapKey for OsuCache {
+    type Value = Arc<OsuCache>;
 }
 
 impl TypeMapKey for Scraper {
@@ -34,7 +40,7 @@ impl TypeMapKey for Scraper {
 }
 
 impl TypeMapKey for MySQL {
-    type Value = MySQL;
+    type Value = Arc<MySQL>;
 }
 
 pub struct DiscordLinks;
@@ -54,12 +60,12 @@ impl TypeMapKey for PerformanceCalculatorLock {
 
 pub struct ReactionTracker;
 impl TypeMapKey for ReactionTracker {
-    type Value = HashMap<(ChannelId, MessageId), RoleId>;
+    type Value = HashMap<(ChannelId, MessageId), RoleMenu>;
 }
 
-pub struct TwitchUsers;
-impl TypeMapKey for TwitchUsers {
-    type Value = HashMap<String, u64>;
+pub struct StreamUsers;
+impl TypeMapKey for StreamUsers {
+    type Value = HashMap<(Platform, String), String>;
 }
 
 pub struct StreamTracks;
@@ -67,19 +73,28 @@ impl TypeMapKey for StreamTracks {
     type Value = HashSet<StreamTrack>;
 }
 
-pub struct OnlineTwitch;
-impl TypeMapKey for OnlineTwitch {
-    type Value = HashSet<u64>;
+pub struct OnlineStreams;
+impl TypeMapKey for OnlineStreams {
+    type Value = HashMap<(Platform, String), LiveStream>;
+}
+
+pub struct StreamPlatforms;
+impl TypeMapKey for StreamPlatforms {
+    type Value = HashMap<Platform, Box<dyn StreamPlatform>>;
 }
 
-impl TypeMapKey for Twitch {
-    type Value = Twitch;
+/// Taken by the stream tracking worker if twitch EventSub is enabled
+pub struct StreamEvents;
+impl TypeMapKey for StreamEvents {
+    type Value = Option<UnboundedReceiver<StreamEvent>>;
 }
 
 pub struct Guild {
     pub guild_id: GuildId,
     pub with_lyrics: bool,
     pub authorities: Vec<String>,
+    /// Channel where administrative actions are logged
+    pub mod_log: Option<ChannelId>,
 }
 
 impl Guild {
@@ -93,6 +108,7 @@ impl Guild {
             guild_id: GuildId(guild_id),
             with_lyrics: true,
             authorities,
+            mod_log: None,
         }
     }
 }
@@ -109,6 +125,7 @@ impl<'c> FromRow<'c, MySqlRow> for Guild {
             guild_id: GuildId(row.get("guild_id")),
             with_lyrics: row.get("with_lyrics"),
             authorities,
+            mod_log: row.get::<Option<u64>, _>("mod_log").map(ChannelId),
         })
     }
 }
@@ -118,11 +135,22 @@ impl TypeMapKey for Guilds {
     type Value = HashMap<GuildId, Guild>;
 }
 
+/// Enabled and disabled commands of each guild
+pub struct CommandPermissions;
+impl TypeMapKey for CommandPermissions {
+    type Value = HashMap<GuildId, Vec<CommandPermission>>;
+}
+
 pub struct BgGames;
 impl TypeMapKey for BgGames {
     type Value = HashMap<ChannelId, BackGroundGame>;
 }
 
+pub struct SongGames;
+impl TypeMapKey for SongGames {
+    type Value = HashMap<ChannelId, SongGame>;
+}
+
 pub struct BgVerified;
 impl TypeMapKey for BgVerified {
     type Value = HashSet<UserId>;
@@ -0,0 +1,139 @@
+use super::harness::{embed_json, Harness};
+use crate::embeds::{RankEmbed, WhatIfEmbed};
+
+use rosu::models::{GameMode, Score, User};
+
+fn user(name: &str, pp: f32) -> User {
+    let mut user = User::default();
+    user.user_id = 2;
+    user.username = name.to_owned();
+    user.pp_raw = pp;
+    user.country = "BE".to_owned();
+    user
+}
+
+fn top_scores(pp_values: &[f32]) -> Vec<Score> {
+    pp_values
+        .iter()
+        .map(|&pp| {
+            let mut score = Score::default();
+            score.pp = Some(pp);
+            score
+        })
+        .collect()
+}
+
+#[tokio::test]
+async fn minesweeper_medium() {
+    let mut harness = Harness::new().await;
+    let outcome = harness.run("<minesweeper medium").await;
+    let msg = outcome.single_message();
+    assert!(msg.content().starts_with("Here's a 8x8 game with 12 mines:"));
+    assert_eq!(msg.content().matches("||:bomb:||").count(), 12);
+    assert_eq!(msg.content().lines().count(), 9);
+}
+
+#[tokio::test]
+async fn minesweeper_invalid_difficulty() {
+    let mut harness = Harness::new().await;
+    let outcome = harness.run("<ms impossible").await;
+    assert_eq!(
+        outcome.single_message().content(),
+        "The argument must be either `Easy`, `Medium`, `Hard`, or `Extreme`"
+    );
+}
+
+#[tokio::test]
+async fn rank_requires_rank_argument() {
+    let mut harness = Harness::new().await;
+    let outcome = harness.run("<rank").await;
+    assert!(outcome
+        .single_message()
+        .content()
+        .starts_with("No rank argument found"));
+}
+
+#[tokio::test]
+async fn rank_rejects_zero() {
+    let mut harness = Harness::new().await;
+    let outcome = harness.run("<rank badewanne3 be0").await;
+    assert_eq!(
+        outcome.single_message().content(),
+        "Rank must be greater than 0 you clown :^)"
+    );
+}
+
+#[test]
+fn rank_embed_already_above() {
+    let data = RankEmbed::new(
+        user("badewanne3", 8000.0),
+        Vec::new(),
+        50,
+        Some("BE".to_owned()),
+        user("holder", 7000.0),
+    );
+    let embed = embed_json(&data);
+    assert_eq!(
+        embed["title"],
+        "How many pp is badewanne3 missing to reach rank BE50?"
+    );
+    assert!(embed["description"]
+        .as_str()
+        .unwrap()
+        .ends_with("so badewanne3 is with **8,000pp** already above that."));
+}
+
+#[tokio::test]
+async fn whatif_requires_link_or_name() {
+    let mut harness = Harness::new().await;
+    let outcome = harness.run("<whatif 500").await;
+    assert!(outcome
+        .single_message()
+        .content()
+        .starts_with("Either specify an osu name or link your discord"));
+}
+
+#[tokio::test]
+async fn whatif_rejects_negative_pp() {
+    let mut harness = Harness::new().await;
+    harness.link("badewanne3").await;
+    let outcome = harness.run("<whatif -5").await;
+    assert_eq!(
+        outcome.single_message().content(),
+        "The pp number must be non-negative"
+    );
+}
+
+#[test]
+fn whatif_embed_new_best() {
+    let scores = top_scores(&[300.0, 200.0, 100.0]);
+    let data = WhatIfEmbed::new(user("badewanne3", 600.0), scores, GameMode::STD, 400.0);
+    let embed = embed_json(&data);
+    assert_eq!(
+        embed["title"],
+        "What if badewanne3 got a new 400pp score?"
+    );
+    assert!(embed["description"]
+        .as_str()
+        .unwrap()
+        .starts_with("A 400pp play would be badewanne3's #1 best play."));
+}
+
+#[tokio::test]
+async fn matchcosts_invalid_match() {
+    let mut harness = Harness::new().await;
+    let outcome = harness.run("<mc not_a_match").await;
+    assert_eq!(
+        outcome.single_message().content(),
+        "The first argument must be either a match id or the multiplayer link to a match"
+    );
+}
+
+#[tokio::test]
+async fn bg_help_embed() {
+    let mut harness = Harness::new().await;
+    let outcome = harness.run("<bg").await;
+    let msg = outcome.single_message();
+    assert_eq!(msg.embed_field("title"), Some("Background guessing game"));
+    assert!(msg.attachments.is_empty());
+}
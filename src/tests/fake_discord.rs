@@ -0,0 +1,315 @@
+use failure::Error;
+use hyper::{
+    body, header::CONTENT_TYPE, server::conn::Http as HyperHttp, service::service_fn, Body, Method,
+    Request, Response, StatusCode,
+};
+use serde_json::{json, Map, Value};
+use std::{
+    collections::HashMap,
+    net::SocketAddr,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+};
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::{TcpListener, TcpStream},
+    sync::Mutex,
+};
+use tokio_rustls::{
+    rustls::{Certificate, NoClientAuth, PrivateKey, ServerConfig},
+    TlsAcceptor,
+};
+
+pub const BOT_ID: u64 = 1;
+
+/// Request that reached the stand-in server, with multipart bodies flattened
+/// into `body` and the file names collected in `attachments`
+#[derive(Clone, Debug)]
+pub struct RecordedRequest {
+    pub method: Method,
+    pub path: String,
+    pub body: Value,
+    pub attachments: Vec<String>,
+}
+
+#[derive(Default)]
+struct State {
+    requests: Mutex<Vec<RecordedRequest>>,
+    routes: Mutex<HashMap<(Method, String), Value>>,
+    next_id: AtomicU64,
+}
+
+/// Local stand-in for discord's HTTP API.
+///
+/// Serenity has no configurable API base, so the server acts as an HTTPS proxy:
+/// it accepts the `CONNECT` of the client, terminates TLS with a self-signed
+/// certificate and then answers the tunneled requests itself.
+#[derive(Clone)]
+pub struct FakeDiscord {
+    addr: SocketAddr,
+    state: Arc<State>,
+}
+
+impl FakeDiscord {
+    pub async fn start() -> Result<Self, Error> {
+        let cert = rcgen::generate_simple_self_signed(vec!["discord.com".to_owned()])?;
+        let mut config = ServerConfig::new(NoClientAuth::new());
+        config.set_single_cert(
+            vec![Certificate(cert.serialize_der()?)],
+            PrivateKey(cert.serialize_private_key_der()),
+        )?;
+        let acceptor = TlsAcceptor::from(Arc::new(config));
+        let mut listener = TcpListener::bind("127.0.0.1:0").await?;
+        let addr = listener.local_addr()?;
+        let state = Arc::new(State {
+            next_id: AtomicU64::new(1000),
+            ..Default::default()
+        });
+        let server_state = Arc::clone(&state);
+        tokio::spawn(async move {
+            loop {
+                let stream = match listener.accept().await {
+                    Ok((stream, _)) => stream,
+                    Err(why) => {
+                        warn!("Fake discord could not accept connection: {}", why);
+                        continue;
+                    }
+                };
+                let acceptor = acceptor.clone();
+                let state = Arc::clone(&server_state);
+                tokio::spawn(async move {
+                    if let Err(why) = serve_tunnel(stream, acceptor, state).await {
+                        warn!("Fake discord connection error: {}", why);
+                    }
+                });
+            }
+        });
+        Ok(Self { addr, state })
+    }
+
+    pub fn proxy_url(&self) -> String {
+        format!("http://{}", self.addr)
+    }
+
+    /// Answer requests to the given route with a fixed JSON body
+    pub async fn respond(&self, method: Method, path: impl Into<String>, response: Value) {
+        let mut routes = self.state.routes.lock().await;
+        routes.insert((method, path.into()), response);
+    }
+
+    pub async fn requests(&self) -> Vec<RecordedRequest> {
+        self.state.requests.lock().await.clone()
+    }
+
+    pub async fn clear(&self) {
+        self.state.requests.lock().await.clear();
+    }
+}
+
+async fn serve_tunnel(
+    mut stream: TcpStream,
+    acceptor: TlsAcceptor,
+    state: Arc<State>,
+) -> Result<(), Error> {
+    // Read the CONNECT preamble, the client waits for our answer before the TLS handshake
+    let mut preamble = Vec::with_capacity(256);
+    let mut buf = [0; 256];
+    while !preamble.ends_with(b"\r\n\r\n") {
+        let n = stream.read(&mut buf).await?;
+        if n == 0 {
+            bail!("Connection closed before CONNECT was complete");
+        }
+        preamble.extend_from_slice(&buf[..n]);
+    }
+    if !preamble.starts_with(b"CONNECT") {
+        bail!("Expected CONNECT, got {}", String::from_utf8_lossy(&preamble));
+    }
+    stream
+        .write_all(b"HTTP/1.1 200 Connection established\r\n\r\n")
+        .await?;
+    let tls = acceptor.accept(stream).await?;
+    let service = service_fn(move |req| handle(req, Arc::clone(&state)));
+    HyperHttp::new()
+        .http1_only(true)
+        .serve_connection(tls, service)
+        .await?;
+    Ok(())
+}
+
+async fn handle(req: Request<Body>, state: Arc<State>) -> Result<Response<Body>, hyper::Error> {
+    let method = req.method().clone();
+    // Strip the api version so tests don't need to care about it
+    let path = req
+        .uri()
+        .path()
+        .trim_start_matches("/api")
+        .trim_start_matches(|c: char| c == '/' || c == 'v' || c.is_ascii_digit())
+        .to_owned();
+    let path = format!("/{}", path);
+    let content_type = req
+        .headers()
+        .get(CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .map(str::to_owned)
+        .unwrap_or_default();
+    let bytes = body::to_bytes(req.into_body()).await?;
+    let (body, attachments) = if let Some(boundary) = content_type
+        .strip_prefix("multipart/form-data; boundary=")
+        .map(str::to_owned)
+    {
+        parse_multipart(&bytes, &boundary)
+    } else {
+        let body = serde_json::from_slice(&bytes).unwrap_or(Value::Null);
+        (body, Vec::new())
+    };
+    let request = RecordedRequest {
+        method: method.clone(),
+        path: path.clone(),
+        body,
+        attachments,
+    };
+    state.requests.lock().await.push(request.clone());
+
+    // Canned responses take priority
+    if let Some(value) = state.routes.lock().await.get(&(method.clone(), path.clone())) {
+        return Ok(json_response(StatusCode::OK, value));
+    }
+    let segments: Vec<_> = path.split('/').filter(|s| !s.is_empty()).collect();
+    let response = match (&method, segments.as_slice()) {
+        (&Method::POST, ["channels", channel, "messages"]) => {
+            let id = state.next_id.fetch_add(1, Ordering::Relaxed);
+            let msg = message_json(id, channel.parse().unwrap_or(0), &request);
+            json_response(StatusCode::OK, &msg)
+        }
+        (&Method::PATCH, ["channels", channel, "messages", msg_id]) => {
+            let msg = message_json(
+                msg_id.parse().unwrap_or(0),
+                channel.parse().unwrap_or(0),
+                &request,
+            );
+            json_response(StatusCode::OK, &msg)
+        }
+        (&Method::GET, _) => json_response(
+            StatusCode::NOT_FOUND,
+            &json!({ "message": "Unknown route", "code": 0 }),
+        ),
+        _ => Response::builder()
+            .status(StatusCode::NO_CONTENT)
+            .body(Body::empty())
+            .unwrap(),
+    };
+    Ok(response)
+}
+
+fn json_response(status: StatusCode, value: &Value) -> Response<Body> {
+    Response::builder()
+        .status(status)
+        .header(CONTENT_TYPE, "application/json")
+        .body(Body::from(value.to_string()))
+        .unwrap()
+}
+
+/// Build the message discord would return after creating / editing it
+fn message_json(id: u64, channel_id: u64, request: &RecordedRequest) -> Value {
+    let content = request
+        .body
+        .get("content")
+        .and_then(Value::as_str)
+        .unwrap_or_default();
+    let embeds: Vec<_> = request
+        .body
+        .get("embed")
+        .filter(|embed| embed.is_object())
+        .map(|embed| normalize_embed(embed.clone()))
+        .into_iter()
+        .collect();
+    let attachments: Vec<_> = request
+        .attachments
+        .iter()
+        .enumerate()
+        .map(|(i, filename)| {
+            json!({
+                "id": (id + i as u64).to_string(),
+                "filename": filename,
+                "size": 0,
+                "url": format!("https://cdn.discordapp.com/{}", filename),
+                "proxy_url": format!("https://media.discordapp.net/{}", filename),
+                "height": null,
+                "width": null,
+            })
+        })
+        .collect();
+    json!({
+        "id": id.to_string(),
+        "channel_id": channel_id.to_string(),
+        "author": {
+            "id": BOT_ID.to_string(),
+            "username": "Bathbot",
+            "discriminator": "0000",
+            "avatar": null,
+            "bot": true,
+        },
+        "content": content,
+        "timestamp": "2020-07-01T00:00:00+00:00",
+        "edited_timestamp": null,
+        "tts": false,
+        "mention_everyone": false,
+        "mentions": [],
+        "mention_roles": [],
+        "attachments": attachments,
+        "embeds": embeds,
+        "reactions": [],
+        "pinned": false,
+        "type": 0,
+    })
+}
+
+// Discord fills in image sizes which serenity expects to be present
+fn normalize_embed(mut embed: Value) -> Value {
+    for key in &["thumbnail", "image"] {
+        if let Some(Value::Object(image)) = embed.get_mut(*key) {
+            let url = image.get("url").cloned().unwrap_or(Value::Null);
+            image.entry("proxy_url").or_insert(url);
+            image.entry("height").or_insert_with(|| json!(0));
+            image.entry("width").or_insert_with(|| json!(0));
+        }
+    }
+    embed
+}
+
+fn parse_multipart(bytes: &[u8], boundary: &str) -> (Value, Vec<String>) {
+    let text = String::from_utf8_lossy(bytes);
+    let delimiter = format!("--{}", boundary);
+    let mut fields = Map::new();
+    let mut attachments = Vec::new();
+    for part in text.split(&delimiter) {
+        let part = part.trim_start_matches("\r\n");
+        let (headers, content) = match part.find("\r\n\r\n") {
+            Some(idx) => (&part[..idx], part[idx + 4..].trim_end_matches("\r\n")),
+            None => continue,
+        };
+        let name = match header_param(headers, "name") {
+            Some(name) => name,
+            None => continue,
+        };
+        if let Some(filename) = header_param(headers, "filename") {
+            attachments.push(filename);
+        } else if name == "payload_json" {
+            if let Ok(Value::Object(payload)) = serde_json::from_str(content) {
+                fields.extend(payload);
+            }
+        } else {
+            fields.insert(name, Value::String(content.to_owned()));
+        }
+    }
+    (Value::Object(fields), attachments)
+}
+
+fn header_param(headers: &str, param: &str) -> Option<String> {
+    let needle = format!(" {}=\"", param);
+    let start = headers.find(&needle)? + needle.len();
+    let end = headers[start..].find('"')?;
+    Some(headers[start..start + end].to_owned())
+}
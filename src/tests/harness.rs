@@ -0,0 +1,200 @@
+use super::fake_discord::{FakeDiscord, RecordedRequest};
+use crate::{create_framework, embeds::EmbedData, osu_cache::OsuCache, structs::*};
+
+use futures::channel::mpsc::{unbounded, UnboundedReceiver};
+use hyper::Method;
+use reqwest::{Client, Proxy};
+use serde_json::{json, Value};
+use serenity::{
+    builder::CreateEmbed,
+    cache::Cache,
+    client::bridge::gateway::ShardMessenger,
+    framework::{Framework, StandardFramework},
+    gateway::InterMessage,
+    http::Http,
+    model::channel::Message,
+    prelude::{Context, RwLock, TypeMap},
+    utils::hashmap_to_json_map,
+};
+use std::{
+    collections::{HashMap, HashSet},
+    sync::Arc,
+};
+
+pub const CHANNEL_ID: u64 = 2;
+pub const AUTHOR_ID: u64 = 3;
+
+/// Message the bot sent or edited during a command
+#[derive(Debug)]
+pub struct SentMessage {
+    pub content: Option<String>,
+    pub embed: Option<Value>,
+    pub attachments: Vec<String>,
+}
+
+impl SentMessage {
+    pub fn content(&self) -> &str {
+        self.content.as_deref().unwrap_or_default()
+    }
+
+    pub fn embed_field(&self, key: &str) -> Option<&str> {
+        self.embed.as_ref()?.get(key)?.as_str()
+    }
+}
+
+/// Everything the bot did in response to a single command
+pub struct Outcome {
+    pub requests: Vec<RecordedRequest>,
+}
+
+impl Outcome {
+    pub fn messages(&self) -> Vec<SentMessage> {
+        self.requests
+            .iter()
+            .filter(|req| {
+                req.path.ends_with("/messages")
+                    && req.method == Method::POST
+                    && req.path.starts_with("/channels/")
+            })
+            .map(|req| SentMessage {
+                content: req
+                    .body
+                    .get("content")
+                    .and_then(Value::as_str)
+                    .map(str::to_owned),
+                embed: req.body.get("embed").cloned(),
+                attachments: req.attachments.clone(),
+            })
+            .collect()
+    }
+
+    pub fn single_message(&self) -> SentMessage {
+        let mut messages = self.messages();
+        assert_eq!(messages.len(), 1, "expected exactly one message");
+        messages.pop().unwrap()
+    }
+
+    /// Emojis the bot reacted with
+    pub fn reactions(&self) -> Vec<String> {
+        self.requests
+            .iter()
+            .filter(|req| req.method == Method::PUT && req.path.ends_with("/@me"))
+            .filter_map(|req| req.path.split('/').nth(6).map(percent_decode))
+            .collect()
+    }
+}
+
+/// Feeds synthetic messages through the same framework the bot uses
+/// and records what the bot sends back to discord
+pub struct Harness {
+    pub ctx: Context,
+    pub discord: FakeDiscord,
+    framework: StandardFramework,
+    next_msg_id: u64,
+    // Collectors register themselves through the shard, keep it alive
+    _shard_rx: UnboundedReceiver<InterMessage>,
+}
+
+impl Harness {
+    pub async fn new() -> Self {
+        let discord = FakeDiscord::start()
+            .await
+            .expect("Could not start fake discord");
+        let client = Client::builder()
+            .proxy(Proxy::https(&discord.proxy_url()).unwrap())
+            .danger_accept_invalid_certs(true)
+            .build()
+            .unwrap();
+        let http = Http::new(Arc::new(client), "Bot test-token");
+        let (tx, rx) = unbounded();
+        let mut data = TypeMap::new();
+        data.insert::<CommandCounter>(HashMap::default());
+        data.insert::<OsuCache>(Arc::new(OsuCache::default()));
+        data.insert::<DiscordLinks>(HashMap::new());
+        data.insert::<Guilds>(HashMap::new());
+        data.insert::<CommandPermissions>(HashMap::new());
+        data.insert::<BgGames>(HashMap::new());
+        data.insert::<SongGames>(HashMap::new());
+        data.insert::<BgVerified>(HashSet::new());
+        let ctx = Context {
+            data: Arc::new(RwLock::new(data)),
+            shard: ShardMessenger::new(tx),
+            shard_id: 0,
+            http: Arc::new(http),
+            cache: Arc::new(Cache::default()),
+        };
+        Self {
+            ctx,
+            discord,
+            framework: create_framework(HashSet::new()).await,
+            next_msg_id: 100,
+            _shard_rx: rx,
+        }
+    }
+
+    /// Link the test author to an osu account as `<link` would
+    pub async fn link(&self, osu_name: &str) {
+        let mut data = self.ctx.data.write().await;
+        let links = data.get_mut::<DiscordLinks>().unwrap();
+        links.insert(AUTHOR_ID, osu_name.to_owned());
+    }
+
+    /// Send a DM with the given content and wait for the command to finish
+    pub async fn run(&mut self, content: &str) -> Outcome {
+        self.discord.clear().await;
+        self.next_msg_id += 1;
+        let msg: Message = serde_json::from_value(json!({
+            "id": self.next_msg_id.to_string(),
+            "channel_id": CHANNEL_ID.to_string(),
+            "author": {
+                "id": AUTHOR_ID.to_string(),
+                "username": "tester",
+                "discriminator": "0001",
+                "avatar": null,
+                "bot": false,
+            },
+            "content": content,
+            "timestamp": "2020-07-01T00:00:00+00:00",
+            "edited_timestamp": null,
+            "tts": false,
+            "mention_everyone": false,
+            "mentions": [],
+            "mention_roles": [],
+            "attachments": [],
+            "embeds": [],
+            "reactions": [],
+            "pinned": false,
+            "type": 0,
+        }))
+        .expect("Invalid synthetic message");
+        self.framework.dispatch(self.ctx.clone(), msg).await;
+        Outcome {
+            requests: self.discord.requests().await,
+        }
+    }
+}
+
+/// JSON of an embed as it would be sent to discord
+pub fn embed_json(data: &impl EmbedData) -> Value {
+    let mut embed = CreateEmbed::default();
+    data.build(&mut embed);
+    Value::Object(hashmap_to_json_map(embed.0))
+}
+
+fn percent_decode(s: &str) -> String {
+    let bytes = s.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%' && i + 2 < bytes.len() {
+            if let Ok(byte) = u8::from_str_radix(&s[i + 1..i + 3], 16) {
+                decoded.push(byte);
+                i += 3;
+                continue;
+            }
+        }
+        decoded.push(bytes[i]);
+        i += 1;
+    }
+    String::from_utf8_lossy(&decoded).into_owned()
+}
@@ -0,0 +1,4 @@
+mod commands;
+mod fake_discord;
+mod harness;
+mod rankings;
@@ -0,0 +1,63 @@
+use crate::scraper::{parse_rankings, RankingEntry};
+
+const GLOBAL: &str = include_str!("fixtures/rankings_osu_global.html");
+const COUNTRY: &str = include_str!("fixtures/rankings_osu_country.html");
+
+#[test]
+fn global_page_full_rows() {
+    let entries = parse_rankings(GLOBAL).unwrap();
+    assert_eq!(entries.len(), 3);
+    assert_eq!(
+        entries[0],
+        RankingEntry {
+            rank: 1,
+            user_id: 4504101,
+            username: "WhiteCat".to_owned(),
+            country_code: "DE".to_owned(),
+            pp: 16536.0,
+            accuracy: 98.95,
+            playcount: 108372,
+        }
+    );
+    let ranks: Vec<_> = entries.iter().map(|entry| entry.rank).collect();
+    assert_eq!(ranks, vec![1, 2, 3]);
+    assert_eq!(entries[2].username, "idke");
+    assert_eq!(entries[2].country_code, "US");
+}
+
+#[test]
+fn country_page() {
+    let entries = parse_rankings(COUNTRY).unwrap();
+    assert_eq!(entries.len(), 2);
+    assert!(entries.iter().all(|entry| entry.country_code == "DE"));
+    assert_eq!(entries[1].user_id, 2558286);
+    assert_eq!(entries[1].playcount, 88104);
+}
+
+#[test]
+fn country_from_flag_without_link() {
+    let body = GLOBAL.replace("?country=", "?region=");
+    let entries = parse_rankings(&body).unwrap();
+    assert_eq!(entries[1].country_code, "AU");
+}
+
+#[test]
+fn additional_column_is_ignored() {
+    let body = GLOBAL
+        .replacen("<tr>", "<tr><th>Ranked Score</th>", 1)
+        .replace(
+            "<tr class=\"ranking-page-table__row\">",
+            "<tr class=\"ranking-page-table__row\"><td>123</td>",
+        );
+    let entries = parse_rankings(&body).unwrap();
+    assert_eq!(entries, parse_rankings(GLOBAL).unwrap());
+}
+
+#[test]
+fn changed_markup_errors_instead_of_panicking() {
+    assert!(parse_rankings("<html><body>Maintenance</body></html>").is_err());
+    let body = GLOBAL.replace("Performance", "Score");
+    assert!(parse_rankings(&body).is_err());
+    let body = GLOBAL.replace("data-user-id", "data-id");
+    assert!(parse_rankings(&body).is_err());
+}
@@ -0,0 +1,330 @@
+use crate::database::MySQL;
+
+use failure::Error;
+use image::{imageops::FilterType, DynamicImage};
+use rosu::{
+    backend::{BeatmapRequest, Osu},
+    models::GameMode,
+};
+use std::{
+    collections::HashMap,
+    env, fmt,
+    fs::File,
+    io::{Cursor, Read, Seek},
+    path::{Path, PathBuf},
+};
+use tokio::fs;
+use zip::ZipArchive;
+
+/// Hashes that differ in at most this many bits are considered the same image
+const DUPLICATE_DISTANCE: u32 = 6;
+
+/// Outcome of importing backgrounds, each file with the reason for skipping or failing
+#[derive(Default)]
+pub struct ImportReport {
+    pub imported: Vec<u32>,
+    pub skipped: Vec<(String, String)>,
+    pub failed: Vec<(String, String)>,
+}
+
+impl ImportReport {
+    pub fn summary(&self) -> String {
+        format!(
+            "Imported: {} | Skipped: {} | Failed: {}",
+            self.imported.len(),
+            self.skipped.len(),
+            self.failed.len()
+        )
+    }
+}
+
+impl fmt::Display for ImportReport {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "{}", self.summary())?;
+        if !self.imported.is_empty() {
+            writeln!(f, "\nImported:")?;
+            for mapset_id in self.imported.iter() {
+                writeln!(f, "  {}", mapset_id)?;
+            }
+        }
+        if !self.skipped.is_empty() {
+            writeln!(f, "\nSkipped:")?;
+            for (file, reason) in self.skipped.iter() {
+                writeln!(f, "  {}: {}", file, reason)?;
+            }
+        }
+        if !self.failed.is_empty() {
+            writeln!(f, "\nFailed:")?;
+            for (file, reason) in self.failed.iter() {
+                writeln!(f, "  {}: {}", file, reason)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Import all images of a directory or zip archive as backgrounds of the given mode.
+/// Files must be named after their mapset id, e.g. `948199.png`.
+pub async fn import_backgrounds(
+    source: &Path,
+    mode: GameMode,
+    mysql: &MySQL,
+    osu: &Osu,
+) -> Result<ImportReport, Error> {
+    let files = if fs::metadata(source).await?.is_dir() {
+        ImportFiles::Dir(fs::read_dir(source).await?)
+    } else {
+        ImportFiles::Zip(ZipArchive::new(File::open(source)?)?, 0)
+    };
+    import_files(files, mode, mysql, osu).await
+}
+
+/// Import the images of a zip archive given as bytes
+pub async fn import_zip(
+    bytes: Vec<u8>,
+    mode: GameMode,
+    mysql: &MySQL,
+    osu: &Osu,
+) -> Result<ImportReport, Error> {
+    let files = ImportFiles::Zip(ZipArchive::new(Cursor::new(bytes))?, 0);
+    import_files(files, mode, mysql, osu).await
+}
+
+async fn import_files<R: Read + Seek>(
+    mut files: ImportFiles<R>,
+    mode: GameMode,
+    mysql: &MySQL,
+    osu: &Osu,
+) -> Result<ImportReport, Error> {
+    let mut dir = PathBuf::from(env::var("BG_PATH")?);
+    match mode {
+        GameMode::STD => dir.push("osu"),
+        GameMode::MNA => dir.push("mania"),
+        GameMode::TKO | GameMode::CTB => bail!("Only std and mania backgrounds are supported"),
+    }
+    let mut hashes = stored_hashes(&dir, mode, mysql).await?;
+    let mut report = ImportReport::default();
+    while let Some((name, bytes)) = files.next().await? {
+        let (mapset_id, filetype) = match parse_filename(&name) {
+            Ok(parsed) => parsed,
+            Err(reason) => {
+                report.skipped.push((name, reason.to_owned()));
+                continue;
+            }
+        };
+        if hashes.contains_key(&mapset_id) {
+            report.skipped.push((name, "Already stored".to_owned()));
+            continue;
+        }
+        let hash = match image::load_from_memory(&bytes) {
+            Ok(img) => perceptual_hash(&img),
+            Err(why) => {
+                report
+                    .failed
+                    .push((name, format!("Invalid image: {}", why)));
+                continue;
+            }
+        };
+        let duplicate = hashes
+            .iter()
+            .find(|(_, &other)| hash_distance(hash, other) <= DUPLICATE_DISTANCE);
+        if let Some((other_id, _)) = duplicate {
+            let reason = format!("Near-duplicate of mapset {}", other_id);
+            report.skipped.push((name, reason));
+            continue;
+        }
+        if let Err(reason) = resolve_mapset(mapset_id, mysql, osu).await {
+            report.failed.push((name, reason));
+            continue;
+        }
+        let path = dir.join(format!("{}.{}", mapset_id, filetype));
+        if let Err(why) = fs::write(&path, &bytes).await {
+            report
+                .failed
+                .push((name, format!("Could not write file: {}", why)));
+            continue;
+        }
+        if let Err(why) = mysql.add_tag_mapset(mapset_id, &filetype, mode).await {
+            let _ = fs::remove_file(&path).await;
+            report
+                .failed
+                .push((name, format!("Could not add to map_tags: {}", why)));
+            continue;
+        }
+        if let Err(why) = mysql.set_bg_hash(mapset_id, hash).await {
+            warn!("Error while storing bg hash of {}: {}", mapset_id, why);
+        }
+        hashes.insert(mapset_id, hash);
+        report.imported.push(mapset_id);
+    }
+    Ok(report)
+}
+
+// Hashes of all backgrounds of the mode, computing the missing ones from their files
+async fn stored_hashes(
+    dir: &Path,
+    mode: GameMode,
+    mysql: &MySQL,
+) -> Result<HashMap<u32, u64>, Error> {
+    let mut stored = mysql.get_bg_hashes().await?;
+    let mut hashes = HashMap::new();
+    for mapset in mysql.get_all_tags_mapset(mode).await? {
+        let mapset_id = mapset.mapset_id;
+        let hash = match stored.remove(&mapset_id) {
+            Some(hash) => hash,
+            None => {
+                let path = dir.join(format!("{}.{}", mapset_id, mapset.filetype));
+                let img = match fs::read(&path).await.map(|b| image::load_from_memory(&b)) {
+                    Ok(Ok(img)) => img,
+                    _ => {
+                        warn!("Could not hash background {}", path.display());
+                        continue;
+                    }
+                };
+                let hash = perceptual_hash(&img);
+                if let Err(why) = mysql.set_bg_hash(mapset_id, hash).await {
+                    warn!("Error while storing bg hash of {}: {}", mapset_id, why);
+                }
+                hash
+            }
+        };
+        hashes.insert(mapset_id, hash);
+    }
+    Ok(hashes)
+}
+
+async fn resolve_mapset(mapset_id: u32, mysql: &MySQL, osu: &Osu) -> Result<(), String> {
+    if mysql.get_beatmapset(mapset_id).await.is_ok() {
+        return Ok(());
+    }
+    let request = BeatmapRequest::new().mapset_id(mapset_id);
+    match request.queue(osu).await {
+        Ok(maps) if maps.is_empty() => Err("No mapset found with this id".to_owned()),
+        Ok(maps) => {
+            // The next import and the bg game won't need to request them again
+            if let Err(why) = mysql.insert_beatmaps(&maps).await {
+                warn!(
+                    "Error while adding maps of mapset {} to DB: {}",
+                    mapset_id, why
+                );
+            }
+            Ok(())
+        }
+        Err(why) => Err(format!("osu!api issue: {}", why)),
+    }
+}
+
+/// Files of a directory or zip archive, only one of them is held in memory at a time
+enum ImportFiles<R> {
+    Dir(fs::ReadDir),
+    /// The archive and the index of its next entry
+    Zip(ZipArchive<R>, usize),
+}
+
+impl<R: Read + Seek> ImportFiles<R> {
+    /// Name and content of the next file, directories are skipped
+    async fn next(&mut self) -> Result<Option<(String, Vec<u8>)>, Error> {
+        match self {
+            Self::Dir(entries) => {
+                while let Some(entry) = entries.next_entry().await? {
+                    let path = entry.path();
+                    if path.is_dir() {
+                        continue;
+                    }
+                    let name = entry.file_name().to_string_lossy().into_owned();
+                    return Ok(Some((name, fs::read(path).await?)));
+                }
+                Ok(None)
+            }
+            Self::Zip(archive, next) => {
+                while *next < archive.len() {
+                    let mut file = archive.by_index(*next)?;
+                    *next += 1;
+                    if file.is_dir() {
+                        continue;
+                    }
+                    let name = match Path::new(file.name()).file_name() {
+                        Some(name) => name.to_string_lossy().into_owned(),
+                        None => continue,
+                    };
+                    let mut content = Vec::with_capacity(file.size() as usize);
+                    file.read_to_end(&mut content)?;
+                    return Ok(Some((name, content)));
+                }
+                Ok(None)
+            }
+        }
+    }
+}
+
+// Mapset id and lowercase filetype of e.g. `948199.png`
+fn parse_filename(name: &str) -> Result<(u32, String), &'static str> {
+    let path = Path::new(name);
+    let mapset_id = path
+        .file_stem()
+        .and_then(|stem| stem.to_str())
+        .and_then(|stem| stem.parse().ok())
+        .ok_or("Name is not a mapset id")?;
+    let filetype = path
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .map(str::to_lowercase)
+        .filter(|ext| ext == "png" || ext == "jpg" || ext == "jpeg")
+        .ok_or("File type must be png, jpg, or jpeg")?;
+    Ok((mapset_id, filetype))
+}
+
+/// Difference hash of the image; similar images have hashes with few differing bits
+pub fn perceptual_hash(img: &DynamicImage) -> u64 {
+    let small = img.resize_exact(9, 8, FilterType::Triangle).to_luma();
+    let mut hash = 0;
+    for y in 0..8 {
+        for x in 0..8 {
+            hash <<= 1;
+            if small.get_pixel(x, y)[0] < small.get_pixel(x + 1, y)[0] {
+                hash |= 1;
+            }
+        }
+    }
+    hash
+}
+
+pub fn hash_distance(a: u64, b: u64) -> u32 {
+    (a ^ b).count_ones()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::{Rgba, RgbaImage};
+
+    fn gradient(w: u32, h: u32, flipped: bool) -> DynamicImage {
+        let img = RgbaImage::from_fn(w, h, |x, y| {
+            let x = if flipped { w - 1 - x } else { x };
+            let v = (x * 200 / w + y * 40 / h) as u8;
+            Rgba([v, v / 2, 255 - v, 255])
+        });
+        DynamicImage::ImageRgba8(img)
+    }
+
+    #[test]
+    fn resized_images_are_duplicates() {
+        let original = perceptual_hash(&gradient(800, 600, false));
+        let resized = perceptual_hash(&gradient(400, 300, false));
+        assert!(hash_distance(original, resized) <= DUPLICATE_DISTANCE);
+    }
+
+    #[test]
+    fn different_images_are_no_duplicates() {
+        let original = perceptual_hash(&gradient(800, 600, false));
+        let flipped = perceptual_hash(&gradient(800, 600, true));
+        assert!(hash_distance(original, flipped) > DUPLICATE_DISTANCE);
+    }
+
+    #[test]
+    fn filenames() {
+        assert_eq!(parse_filename("948199.PNG"), Ok((948199, "png".to_owned())));
+        assert!(parse_filename("bg.png").is_err());
+        assert!(parse_filename("948199.gif").is_err());
+    }
+}
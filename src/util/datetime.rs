@@ -15,6 +15,47 @@ pub fn sec_to_minsec(secs: u32) -> String {
     format!("{}:{:02}", secs / 60, secs % 60)
 }
 
+/// Longer durations like a stream's uptime, e.g. `2h 05m`
+pub fn sec_to_duration(secs: i64) -> String {
+    let minutes = secs.max(0) / 60;
+    if minutes < 60 {
+        format!("{}m", minutes)
+    } else {
+        format!("{}h {:02}m", minutes / 60, minutes % 60)
+    }
+}
+
+/// Monthly bg game season of the date, e.g. `202007` for July 2020
+pub fn season(date: &DateTime<Utc>) -> u32 {
+    date.year() as u32 * 100 + date.month()
+}
+
+pub fn previous_season(season: u32) -> u32 {
+    if season % 100 == 1 {
+        (season / 100 - 1) * 100 + 12
+    } else {
+        season - 1
+    }
+}
+
+pub fn season_name(season: u32) -> String {
+    let month = match season % 100 {
+        1 => "January",
+        2 => "February",
+        3 => "March",
+        4 => "April",
+        5 => "May",
+        6 => "June",
+        7 => "July",
+        8 => "August",
+        9 => "September",
+        10 => "October",
+        11 => "November",
+        _ => "December",
+    };
+    format!("{} {}", month, season / 100)
+}
+
 // thx saki :)
 pub fn how_long_ago(date: &DateTime<Utc>) -> String {
     let now = Utc::now();
@@ -117,4 +158,20 @@ mod tests {
         assert_eq!(sec_to_minsec(92), String::from("1:32"));
         assert_eq!(sec_to_minsec(3605), String::from("60:05"));
     }
+
+    #[test]
+    fn test_sec_to_duration() {
+        assert_eq!(sec_to_duration(59), "0m");
+        assert_eq!(sec_to_duration(45 * 60), "45m");
+        assert_eq!(sec_to_duration(2 * 3600 + 5 * 60 + 30), "2h 05m");
+    }
+
+    #[test]
+    fn test_seasons() {
+        let date = Utc.ymd(2020, 7, 12).and_hms(14, 0, 0);
+        assert_eq!(season(&date), 202_007);
+        assert_eq!(previous_season(202_007), 202_006);
+        assert_eq!(previous_season(202_001), 201_912);
+        assert_eq!(season_name(202_007), "July 2020");
+    }
 }
@@ -1,4 +1,10 @@
-use crate::{util::globals::AVATAR_URL, Guilds, MySQL};
+use crate::{
+    commands::invoked_command,
+    database::{check_permission, ModLogEntry, PermissionDenial},
+    embeds::{EmbedData, ModLogEmbed},
+    util::globals::AVATAR_URL,
+    CommandPermissions, Guilds, MySQL,
+};
 
 use failure::Error;
 use image::{
@@ -12,7 +18,7 @@ use serenity::{
     model::{
         channel::{EmbedField, Message, ReactionType},
         guild::Member,
-        id::{ChannelId, GuildId, UserId},
+        id::{ChannelId, GuildId, RoleId, UserId},
     },
     prelude::{Context, RwLock, TypeMap},
 };
@@ -149,6 +155,101 @@ pub async fn _add_guild(ctx: &Context, guild_id: GuildId, guild_name: &str) -> R
     Ok(())
 }
 
+/// Store the entry and post it in the mod log channel of its guild, if there is one
+pub async fn mod_log(ctx: &Context, mut entry: ModLogEntry) {
+    if entry.details.chars().count() > 1024 {
+        entry.details = entry.details.chars().take(1021).collect();
+        entry.details.push_str("...");
+    }
+    let guild_id = GuildId(entry.guild_id);
+    let channel = {
+        let data = ctx.data.read().await;
+        let mysql = data.get::<MySQL>().unwrap();
+        if let Err(why) = mysql.insert_mod_log(&entry).await {
+            warn!("Error while inserting mod log entry: {}", why);
+        }
+        let guilds = data.get::<Guilds>().unwrap();
+        guilds.get(&guild_id).and_then(|guild| guild.mod_log)
+    };
+    if let Some(channel) = channel {
+        let data = ModLogEmbed::new(&entry);
+        let result = channel
+            .send_message(ctx, |m| m.embed(|e| data.build(e)))
+            .await;
+        if let Err(why) = result {
+            warn!(
+                "Error while sending to mod log of guild {}: {}",
+                guild_id, why
+            );
+        }
+    }
+}
+
+/// Check whether the author can use the command in the channel of the message,
+/// otherwise return the reason why not
+pub async fn command_permission(ctx: &Context, msg: &Message) -> Result<(), String> {
+    let guild_id = match msg.guild_id {
+        Some(guild_id) => guild_id,
+        None => return Ok(()),
+    };
+    // The framework only passes the name of the sub command so resolve it from the message
+    let (command, group) = match invoked_command(&msg.content) {
+        Some(found) => found,
+        None => return Ok(()),
+    };
+    // Nobody should be able to lock themselves out of the configuration
+    if command == "permissions" {
+        return Ok(());
+    }
+    // The gateway sends the member along, otherwise check the cache and then the api
+    let member_roles: Vec<_> = match msg.member.as_ref().map(|member| &member.roles) {
+        Some(roles) => roles.iter().map(|role| role.0).collect(),
+        None => match msg.member(&ctx.cache).await {
+            Some(member) => member.roles.iter().map(|role| role.0).collect(),
+            None => match guild_id.member(ctx, msg.author.id).await {
+                Ok(member) => member.roles.iter().map(|role| role.0).collect(),
+                Err(why) => {
+                    warn!("Could not get member for command permissions: {}", why);
+                    Vec::new()
+                }
+            },
+        },
+    };
+    let result = {
+        let data = ctx.data.read().await;
+        let permissions = data.get::<CommandPermissions>().unwrap();
+        match permissions.get(&guild_id) {
+            Some(rules) => check_permission(rules, msg.channel_id.0, command, group, &member_roles),
+            None => Ok(()),
+        }
+    };
+    match result {
+        Ok(_) => Ok(()),
+        Err(PermissionDenial::Disabled { channel: true }) => Err(format!(
+            "The command `{}` is disabled in this channel",
+            command
+        )),
+        Err(PermissionDenial::Disabled { channel: false }) => Err(format!(
+            "The command `{}` is disabled in this server",
+            command
+        )),
+        Err(PermissionDenial::MissingRole(roles)) => {
+            let mut names = Vec::with_capacity(roles.len());
+            for role in roles {
+                match RoleId(role).to_role_cached(&ctx.cache).await {
+                    Some(role) => names.push(format!("`@{}`", role.name)),
+                    None => names.push(format!("`{}`", role)),
+                }
+            }
+            Err(format!(
+                "You need one of the roles {} to use the command `{}` here",
+                names.join(", "),
+                command
+            ))
+        }
+    }
+}
+
 pub trait CacheData {
     fn cache(&self) -> &Cache;
     fn data(&self) -> &Arc<RwLock<TypeMap>>;
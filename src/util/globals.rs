@@ -24,10 +24,17 @@ pub const AUTHORITY_ROLES: &str = "admin mod moderator";
 pub const HOMEPAGE: &str = "https://osu.ppy.sh/";
 pub const MAP_THUMB_URL: &str = "https://b.ppy.sh/thumb/";
 pub const AVATAR_URL: &str = "https://a.ppy.sh/";
+pub const OSU_API_V2: &str = "https://osu.ppy.sh/api/v2/";
+pub const OSU_OAUTH_TOKEN: &str = "https://osu.ppy.sh/oauth/token";
 
 pub const TWITCH_BASE: &str = "https://www.twitch.tv/";
 pub const TWITCH_STREAM_ENDPOINT: &str = "https://api.twitch.tv/helix/streams";
+pub const TWITCH_GAMES_ENDPOINT: &str = "https://api.twitch.tv/helix/games";
 pub const TWITCH_USERS_ENDPOINT: &str = "https://api.twitch.tv/helix/users";
+pub const TWITCH_EVENTSUB_ENDPOINT: &str = "https://api.twitch.tv/helix/eventsub/subscriptions";
+pub const TWITCH_OAUTH_TOKEN: &str = "https://id.twitch.tv/oauth2/token";
+pub const YOUTUBE_API: &str = "https://www.googleapis.com/youtube/v3/";
+pub const YOUTUBE_VIDEO_BASE: &str = "https://www.youtube.com/watch?v=";
 
 pub const SYMBOLS: [&str; 6] = ["♔", "♕", "♖", "♗", "♘", "♙"];
 // pub const SYMBOLS: [&str; 3] = ["🥇", "🥈", "🥉"];
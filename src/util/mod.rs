@@ -1,10 +1,13 @@
+pub mod bg_import;
 pub mod datetime;
 pub mod discord;
 pub mod globals;
 mod matrix;
 pub mod numbers;
+pub mod oauth;
 pub mod osu;
 pub mod pp;
+pub mod rank_roles;
 
 pub use discord::MessageExt;
 pub use matrix::Matrix;
@@ -0,0 +1,108 @@
+use failure::Error;
+use reqwest::Client;
+use serde_derive::Deserialize;
+use std::time::{Duration, Instant};
+use tokio::sync::RwLock;
+
+struct Token {
+    access_token: String,
+    expires_at: Instant,
+}
+
+impl Token {
+    // Renewed a minute early so it doesn't expire in the middle of a request
+    fn is_fresh(&self) -> bool {
+        self.expires_at > Instant::now() + Duration::from_secs(60)
+    }
+}
+
+/// Access token of the client credentials flow that is renewed when it's about to expire
+pub struct ClientToken {
+    api: &'static str,
+    url: &'static str,
+    client_id: String,
+    client_secret: String,
+    scope: Option<&'static str>,
+    token: RwLock<Token>,
+}
+
+impl ClientToken {
+    /// `api` is only used for logging, `url` is the token endpoint
+    pub async fn new(
+        client: &Client,
+        api: &'static str,
+        url: &'static str,
+        client_id: String,
+        client_secret: String,
+        scope: Option<&'static str>,
+    ) -> Result<Self, Error> {
+        let mut token = Self {
+            api,
+            url,
+            client_id,
+            client_secret,
+            scope,
+            token: RwLock::new(Token {
+                access_token: String::new(),
+                expires_at: Instant::now(),
+            }),
+        };
+        token.token = RwLock::new(token.request(client).await?);
+        Ok(token)
+    }
+
+    /// Current access token, requesting a new one if it is about to expire
+    pub async fn get(&self, client: &Client) -> Result<String, Error> {
+        {
+            let token = self.token.read().await;
+            if token.is_fresh() {
+                return Ok(token.access_token.clone());
+            }
+        }
+        let mut token = self.token.write().await;
+        // Another request might have renewed it while waiting for the lock
+        if !token.is_fresh() {
+            *token = self.request(client).await?;
+            debug!("Refreshed {} token", self.api);
+        }
+        Ok(token.access_token.clone())
+    }
+
+    /// Request a new token after `rejected` got revoked early,
+    /// unless another request already replaced it
+    pub async fn renew(&self, client: &Client, rejected: &str) -> Result<String, Error> {
+        let mut token = self.token.write().await;
+        if token.access_token == rejected {
+            *token = self.request(client).await?;
+            debug!("Refreshed {} token", self.api);
+        }
+        Ok(token.access_token.clone())
+    }
+
+    async fn request(&self, client: &Client) -> Result<Token, Error> {
+        let mut form = vec![
+            ("grant_type", "client_credentials"),
+            ("client_id", self.client_id.as_str()),
+            ("client_secret", self.client_secret.as_str()),
+        ];
+        if let Some(scope) = self.scope {
+            form.push(("scope", scope));
+        }
+        let response = client.post(self.url).form(&form).send().await?;
+        let response = match response.error_for_status() {
+            Ok(response) => response,
+            Err(why) => bail!("Could not authorize for {}: {}", self.api, why),
+        };
+        let token: OAuthToken = serde_json::from_slice(&response.bytes().await?)?;
+        Ok(Token {
+            access_token: token.access_token,
+            expires_at: Instant::now() + Duration::from_secs(token.expires_in),
+        })
+    }
+}
+
+#[derive(Deserialize)]
+struct OAuthToken {
+    access_token: String,
+    expires_in: u64,
+}
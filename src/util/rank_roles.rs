@@ -0,0 +1,119 @@
+use crate::{
+    database::{rank_role_changes, RankRole},
+    osu_cache::OsuCache,
+    DiscordLinks, Osu,
+};
+
+use rosu::models::GameMode;
+use serenity::{
+    cache::Cache,
+    http::Http,
+    model::id::GuildId,
+    prelude::{RwLock, TypeMap},
+};
+use std::sync::Arc;
+
+/// Rank roles that a linked member gains and loses
+pub struct RankRoleChange {
+    pub user_id: u64,
+    pub osu_name: String,
+    pub add: Vec<u64>,
+    pub remove: Vec<u64>,
+}
+
+/// Determine the rank role changes for all linked members of the guild
+pub async fn guild_changes(
+    cache: &Cache,
+    data: &RwLock<TypeMap>,
+    guild_id: GuildId,
+    rules: &[RankRole],
+) -> Vec<RankRoleChange> {
+    let members: Vec<(u64, Vec<u64>)> = match guild_id.to_guild_cached(cache).await {
+        Some(guild) => guild
+            .members
+            .iter()
+            .map(|(id, member)| (id.0, member.roles.iter().map(|role| role.0).collect()))
+            .collect(),
+        None => return Vec::new(),
+    };
+    // Don't hold the lock during the requests, it would block all commands
+    let (members, osu, osu_cache) = {
+        let data = data.read().await;
+        let links = data.get::<DiscordLinks>().unwrap();
+        let members: Vec<_> = members
+            .into_iter()
+            .filter_map(|(user_id, roles)| {
+                links
+                    .get(&user_id)
+                    .map(|name| (user_id, name.to_owned(), roles))
+            })
+            .collect();
+        let osu = Arc::clone(data.get::<Osu>().unwrap());
+        let osu_cache = Arc::clone(data.get::<OsuCache>().unwrap());
+        (members, osu, osu_cache)
+    };
+    let mut modes: Vec<GameMode> = Vec::with_capacity(4);
+    for rule in rules {
+        if !modes.contains(&rule.mode) {
+            modes.push(rule.mode);
+        }
+    }
+    let mut changes = Vec::new();
+    for (user_id, name, roles) in members {
+        let mut ranks = Vec::with_capacity(modes.len());
+        for &mode in modes.iter() {
+            let user_ranks = match osu_cache.user(&osu, &name, mode).await {
+                Ok(Some(user)) => Some((user.pp_rank, user.pp_country_rank)),
+                // Users that can't be found anymore, e.g. restricted ones, count as unranked
+                Ok(None) => Some((0, 0)),
+                Err(why) => {
+                    warn!(
+                        "Error while retrieving user {} for rank roles: {}",
+                        name, why
+                    );
+                    None
+                }
+            };
+            ranks.push((mode, user_ranks));
+        }
+        let (add, remove) = rank_role_changes(rules, &ranks, &roles);
+        if !add.is_empty() || !remove.is_empty() {
+            changes.push(RankRoleChange {
+                user_id,
+                osu_name: name,
+                add,
+                remove,
+            });
+        }
+    }
+    changes
+}
+
+/// Add and remove the roles, returns how many of the role updates failed
+pub async fn apply_changes(http: &Http, guild_id: GuildId, changes: &[RankRoleChange]) -> usize {
+    let mut failed = 0;
+    for change in changes {
+        for &role in change.add.iter() {
+            if let Err(why) = http.add_member_role(guild_id.0, change.user_id, role).await {
+                warn!(
+                    "Error while adding rank role {} to {}: {}",
+                    role, change.user_id, why
+                );
+                failed += 1;
+            }
+        }
+        for &role in change.remove.iter() {
+            if let Err(why) = http
+                .remove_member_role(guild_id.0, change.user_id, role)
+                .await
+            {
+                warn!(
+                    "Error while removing rank role {} from {}: {}",
+                    role, change.user_id, why
+                );
+                failed += 1;
+            }
+        }
+    }
+    failed
+}